@@ -1,23 +1,33 @@
 use memmap2::Mmap;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::OnceLock;
 use std::{
     fs::{self, File},
-    io::Read,
+    io::{self, Read, Write},
     path::Path,
 };
 
-use crate::bytecode::BytecodeParser;
+use crate::bytecode::{self, BytecodeParser, Coverage, Instruction};
+use crate::callgraph::{CallGraph, NodeKind};
+use crate::cfg::ControlFlowGraph;
 use crate::class::{Class, ForeignClass};
 use crate::code::Code;
-use crate::header::Header;
-use crate::lnp::LineNumberProgramIndex;
+use crate::decompile::{self, Function};
+use crate::error::{self, ReadContext};
+use crate::field::{Field, FieldValue};
+use crate::header::{Header, REGION_HEADER_SIZE};
+use crate::literal::{LiteralArray, ModuleRecord};
+use crate::lnp::{DebugInfo, LineNumberProgram, LineNumberProgramIndex, LineNumberTable};
+use crate::method::Method;
+use crate::pa;
 use crate::region::{
-    ClassRegionIndex, FieldRegionIndex, FieldType, MethodStringLiteralRegionIndex,
-    ProtoRegionIndex, Region, RegionHeader,
+    ClassRegionIndex, FieldRegionIndex, MethodStringLiteralRegionIndex, PrimitiveType,
+    ProtoRegionIndex, Region, RegionHeader, TypeRef,
 };
 use crate::source::Source;
 use crate::string::ABCString;
-use crate::{error, init_logging, literal};
+use crate::xref::{Xref, XrefIndex};
+use crate::{init_logging, literal};
 
 use super::uint32_t;
 
@@ -30,9 +40,18 @@ pub struct AbcFile<T> {
     classes: HashMap<uint32_t, Class>,
     foreign_classes: HashMap<uint32_t, ForeignClass>,
     regions: Vec<Region>,
-    literal_array_map: HashMap<usize, String>,
+    literal_array_map: HashMap<usize, LiteralArray>,
+    module_records: HashMap<usize, ModuleRecord>,
+    lnp_idx: LineNumberProgramIndex,
+    /// 交叉引用，第一次查询时构建
+    xref: OnceLock<XrefIndex>,
 }
 
+const fn assert_send_sync<T: Send + Sync>() {}
+
+// Python 接口需要在线程间共享 AbcFile
+const _: () = assert_send_sync::<AbcFile<Vec<u8>>>();
+
 impl<T> AbcFile<T>
 where
     T: AsRef<[u8]>,
@@ -41,29 +60,104 @@ where
         &self.header
     }
 
-    fn parse_header(&mut self) {
-        self.header = self.source.pread::<Header>(0).unwrap();
+    /// 校验文件头：魔数、文件大小、adler32 校验和以及各个索引是否越界
+    pub fn verify(&self) -> Result<(), error::Error> {
+        self.header.verify(self.source.as_ref())
+    }
+
+    fn parse_header(&mut self) -> Result<(), error::Error> {
+        self.header = self.source.pread::<Header>(0).at(0, "Header")?;
+        // 不支持的版本，指令集不同，解析出来的结果是错误的
+        self.header.bytecode_version().check()
+    }
+
+    /// 根据文件版本创建字节码解析器
+    pub(crate) fn bytecode_parser(&self) -> BytecodeParser {
+        BytecodeParser::with_version(self.header.bytecode_version())
+    }
+
+    pub(crate) fn regions(&self) -> &[Region] {
+        &self.regions
     }
 
     pub fn classes(&self) -> &HashMap<uint32_t, Class> {
         &self.classes
     }
 
-    fn init(&mut self) {
-        self.parse_header();
-        self.parse_class_index();
-        self.parse_region_index();
-        self.parse_literal_array_index();
+    /// 所有的字面量数组，键为它在文件中的偏移量
+    pub fn literal_arrays(&self) -> &HashMap<usize, LiteralArray> {
+        &self.literal_array_map
+    }
+
+    /// 获取 `offset` 处的字面量数组
+    pub fn get_literal_array(&self, offset: usize) -> Option<&LiteralArray> {
+        self.literal_array_map.get(&offset)
+    }
+
+    /// 所有的模块记录，键为它在文件中的偏移量
+    pub fn module_records(&self) -> &HashMap<usize, ModuleRecord> {
+        &self.module_records
+    }
+
+    fn init(&mut self) -> Result<(), error::Error> {
+        self.parse_header()?;
+        self.parse_class_index()?;
+        self.parse_region_index()?;
+        self.parse_annotations();
+        self.parse_literal_array_index()?;
+        self.parse_lnp_idx()?;
+        Ok(())
+    }
+
+    /// 解析类、字段和方法的注解，类型名需要通过类所在的区域获取
+    ///
+    /// 注解不影响其他数据的解析，无法解析的注解记录警告后跳过。
+    fn parse_annotations(&mut self) {
+        let source = self.source.as_ref();
+        for (offset, clazz) in self.classes.iter_mut() {
+            if !clazz.has_annotations() {
+                continue;
+            }
+            let offset = *offset as usize;
+            let Some(region) = self.regions.iter().find(|region| region.is_here(offset)) else {
+                tracing::warn!(
+                    "Class 0x{:x}: not in any region, annotations skipped",
+                    offset
+                );
+                continue;
+            };
+            clazz.resolve_annotations(source, region);
+        }
     }
 
     /// 解析 LiteralArray 并将数据存放起来
-    fn parse_literal_array_index(&mut self) {
+    fn parse_literal_array_index(&mut self) -> Result<(), error::Error> {
+        // 模块记录由 `moduleRecordIdx` 字段指向
+        let mut module_record_offsets = HashSet::new();
+        for clazz in self.classes.values() {
+            for field in clazz.fields() {
+                let Some(value) = field.value() else {
+                    continue;
+                };
+                if self.get_string_by_off(*field.name_off())? == "moduleRecordIdx" {
+                    module_record_offsets.insert(*value as usize);
+                }
+            }
+        }
+
+        for offset in &module_record_offsets {
+            let record = literal::parse_module_record(self.source.as_ref(), *offset)?;
+            self.module_records.insert(*offset, record);
+        }
+
         self.literal_array_map = literal::parse_literal_array_index(
             self.source.as_ref(),
             self.header.literalarray_idx_off(),
             self.header.literalarrays_size(),
             &self.regions,
-        );
+            &module_record_offsets,
+        )?;
+        Ok(())
     }
 
     fn get_region(&self, offset: usize) -> Option<&Region> {
@@ -79,53 +173,183 @@ where
         result
     }
 
-    pub fn parse_method(&self, name: String) {
-        let arr = name.split("->").collect::<Vec<&str>>();
-        let target_clazz = arr[0];
-        let target_method = arr[1];
+    fn get_region_or_err(&self, offset: usize) -> Result<&Region, error::Error> {
+        self.get_region(offset).at(offset, "Region")
+    }
+
+    /// 读取方法的 Code
+    fn read_code(&self, code_off: uint32_t) -> Result<Code, error::Error> {
+        self.source
+            .as_ref()
+            .pread::<Code>(code_off as usize)
+            .map_err(|e| e.rebase(code_off as usize))
+    }
+
+    /// 解码方法的指令，没有 Code 的方法返回空列表
+    fn parse_instructions(
+        &self,
+        parser: &BytecodeParser,
+        region: &Region,
+        method: &Method,
+    ) -> Result<Vec<Instruction>, error::Error> {
+        let code_off = *method.method_data().code_off();
+        if code_off == 0 {
+            return Ok(Vec::new());
+        }
+
+        let code = self.read_code(code_off)?;
+        parser.parse(&code, region, self.source.as_ref(), &self.literal_array_map)
+    }
+
+    /// 查找指定方法, 格式：类名->方法名[签名]
+    pub(crate) fn find_method(&self, name: &str) -> Result<(&Region, &Method), error::Error> {
+        self.find_method_entry(name)
+            .map(|(region, _, method)| (region, method))
+    }
+
+    /// 查找指定方法，同时返回方法的偏移量
+    fn find_method_entry(&self, name: &str) -> Result<(&Region, uint32_t, &Method), error::Error> {
+        let (target_clazz, target_method) = name
+            .split_once("->")
+            .ok_or_else(|| error::Error::InvalidId(format!("方法格式错误: {}", name)))?;
 
-        let bytecode_map = BytecodeParser::new();
         for item in &self.classes {
             let offset = item.0;
-            let region = self.get_region(*offset as usize).unwrap();
             let clazz = item.1;
 
             let class_name = clazz.name().str();
             if target_clazz == class_name {
-                for (_offset, method) in clazz.method_map().iter() {
-                    let _name = self.get_string_by_off(*method.name_off());
-                    if target_method == _name {
-                        let data = method.method_data();
-                        let code_off = data.code_off();
-                        let code = self
-                            .source
-                            .as_ref()
-                            .pread::<Code>(*code_off as usize)
-                            .unwrap();
-                        bytecode_map.parse(
-                            &code,
-                            region,
-                            self.source.as_ref(),
-                            &self.literal_array_map,
-                        );
-
-                        break;
+                let region = self.get_region_or_err(*offset as usize)?;
+                for (method_off, method) in clazz.method_map().iter() {
+                    let _name = self.get_string_by_off(*method.name_off())?;
+                    // 同名方法可以通过签名区分，如：La/b/c;->mtd(any, any)any
+                    let is_it = match target_method.strip_prefix(_name.as_str()) {
+                        Some("") => true,
+                        Some(sign) => {
+                            sign.starts_with('(') && {
+                                let proto = method.proto(self.source.as_ref(), region)?;
+                                sign == proto.to_string()
+                            }
+                        }
+                        None => false,
+                    };
+                    if is_it {
+                        return Ok((region, offset + *method_off as uint32_t, method));
                     }
                 }
 
                 break;
             }
         }
+
+        Err(error::Error::InvalidId(format!("方法不存在: {}", name)))
+    }
+
+    /// 解析指定方法, 格式：类名->方法名
+    pub fn parse_method(&self, name: &str) -> Result<Vec<Instruction>, error::Error> {
+        let (region, method) = self.find_method(name)?;
+        let bytecode_map = self.bytecode_parser();
+        self.parse_instructions(&bytecode_map, region, method)
+    }
+
+    /// 获取指定方法的 Code 及其所在的 Region，没有 Code 的方法返回 `None`
+    pub fn get_code(&self, name: &str) -> Result<Option<(&Region, Code)>, error::Error> {
+        let (region, method) = self.find_method(name)?;
+        let code_off = *method.method_data().code_off();
+        if code_off == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some((region, self.read_code(code_off)?)))
     }
-    /// 解析 Code，按需解析
-    // TODO: 解析整个文件，则输出到文件中？
-    // TODO: 解析指定类？
 
-    pub fn parse_code(&mut self) {
-        let bytecode_map = BytecodeParser::new();
+    /// 方法的异常处理表，格式与 ark_disasm 一致，`code` 和 `region` 来自 `get_code`
+    pub fn catch_directives(
+        &self,
+        code: &Code,
+        region: &Region,
+    ) -> Result<Vec<String>, error::Error> {
+        code.catch_directives(self.source.as_ref(), region)
+    }
+
+    /// 解码指定方法的指令并构建控制流图，没有 Code 的方法返回 `None`
+    fn build_cfg(
+        &self,
+        name: &str,
+    ) -> Result<Option<(&Region, Code, ControlFlowGraph)>, error::Error> {
+        let Some((region, code)) = self.get_code(name)? else {
+            return Ok(None);
+        };
+        let instructions = self.bytecode_parser().parse(
+            &code,
+            region,
+            self.source.as_ref(),
+            &self.literal_array_map,
+        )?;
+        let cfg = ControlFlowGraph::new(&instructions, &code);
+        Ok(Some((region, code, cfg)))
+    }
+
+    /// 构建指定方法的控制流图，没有 Code 的方法返回 `None`
+    pub fn get_cfg(&self, name: &str) -> Result<Option<ControlFlowGraph>, error::Error> {
+        Ok(self.build_cfg(name)?.map(|(_, _, cfg)| cfg))
+    }
+
+    /// 反编译指定方法，没有 Code 的方法返回 `None`
+    pub fn decompile_method(&self, name: &str) -> Result<Option<Function>, error::Error> {
+        let Some((region, code, cfg)) = self.build_cfg(name)? else {
+            return Ok(None);
+        };
+
+        let mut catch_types = Vec::new();
+        for try_block in code.try_blocks() {
+            let mut types = Vec::new();
+            for catch_block in try_block.catch_blocks() {
+                let catch_type = catch_block.catch_type(region)?;
+                types.push(match catch_type {
+                    Some(type_ref) => Some(type_ref.name(self.source.as_ref())?),
+                    None => None,
+                });
+            }
+            catch_types.push(types);
+        }
+        Ok(Some(decompile::decompile(name, &code, &cfg, catch_types)))
+    }
+
+    /// 执行指定方法的行号程序，没有调试信息的方法返回 `None`
+    pub fn get_line_number_table(
+        &self,
+        name: &str,
+    ) -> Result<Option<LineNumberTable>, error::Error> {
+        let (_, method) = self.find_method(name)?;
+        let debug_info_off = *method.method_data().debug_info_off() as usize;
+        if debug_info_off == 0 {
+            return Ok(None);
+        }
+
+        let debug_info = self
+            .source
+            .as_ref()
+            .pread::<DebugInfo>(debug_info_off)
+            .map_err(|e| e.rebase(debug_info_off))?;
+        let idx = *debug_info.line_number_program_idx() as usize;
+        let program_off = *self
+            .lnp_idx
+            .get(idx)
+            .at(debug_info_off, "DebugInfo.line_number_program_idx")?;
+
+        let program = LineNumberProgram::new(self.source.as_ref(), program_off as usize);
+        program.run(&debug_info).map(Some)
+    }
+
+    /// 解析所有方法的 Code，返回 `(类名->方法名, 指令列表)`
+    // TODO: 解析指定类？
+    pub fn parse_code(&self) -> Result<Vec<(String, Vec<Instruction>)>, error::Error> {
+        let bytecode_map = self.bytecode_parser();
+        let mut result = Vec::new();
         for item in &self.classes {
             let offset = item.0;
-            let region = self.get_region(*offset as usize).unwrap();
+            let region = self.get_region_or_err(*offset as usize)?;
             let clazz = item.1;
 
             let class_name = clazz.name().str();
@@ -133,40 +357,238 @@ where
             for field in clazz.fields() {
                 let off = field.name_off();
                 let type_idx = *field.type_idx();
-                let type_name = region.get_class_name(type_idx as usize);
+                let type_name = region
+                    .get_type(type_idx as usize)
+                    .at(type_idx as usize, "Field.type_idx")?
+                    .name(self.source.as_ref())?;
                 tracing::debug!(
                     "Field Name -> {}:{}",
-                    self.get_string_by_off(*off),
+                    self.get_string_by_off(*off)?,
                     type_name
                 );
-                // TODO: 解析 Field 的值
             }
 
-            // TODO: 调整代码的输出
             for (_offset, method) in clazz.method_map().iter() {
-                let name = self.get_string_by_off(*method.name_off());
-                println!("\n[方法]{}->{}", class_name, name);
-                let data = method.method_data();
-                let code_off = data.code_off();
-                let code = self
-                    .source
-                    .as_ref()
-                    .pread::<Code>(*code_off as usize)
-                    .unwrap();
-                bytecode_map.parse(&code, region, self.source.as_ref(), &self.literal_array_map);
+                let name = self.get_string_by_off(*method.name_off())?;
+                let instructions = self.parse_instructions(&bytecode_map, region, method)?;
+                result.push((format!("{}->{}", class_name, name), instructions));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// 所有方法的交叉引用，第一次调用时解析所有方法的 Code
+    pub fn xref(&self) -> Result<&XrefIndex, error::Error> {
+        if let Some(index) = self.xref.get() {
+            return Ok(index);
+        }
+        let index = XrefIndex::new(&self.parse_code()?);
+        Ok(self.xref.get_or_init(|| index))
+    }
+
+    /// 调用或引用指定方法的位置, 格式同 `parse_method`
+    pub fn callers_of(&self, name: &str) -> Result<&[Xref], error::Error> {
+        let (_, method_off, _) = self.find_method_entry(name)?;
+        Ok(self.xref()?.callers_of(method_off))
+    }
+
+    /// 使用字符串 `value` 的位置
+    pub fn string_refs(&self, value: &str) -> Result<&[Xref], error::Error> {
+        Ok(self.xref()?.string_refs(value))
+    }
+
+    /// 按名字访问属性或全局变量 `name` 的位置，如 `ldobjbyname`、`tryldglobalbyname`
+    pub fn name_refs(&self, name: &str) -> Result<&[Xref], error::Error> {
+        Ok(self.xref()?.name_refs(name))
+    }
+
+    /// 类的 `moduleRecordIdx` 字段指向的模块记录
+    fn module_record_of(&self, clazz: &Class) -> Result<Option<&ModuleRecord>, error::Error> {
+        for field in clazz.fields() {
+            let Some(value) = field.value() else {
+                continue;
+            };
+            if self.get_string_by_off(*field.name_off())? == "moduleRecordIdx" {
+                return Ok(self.module_records.get(&(*value as usize)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// 构建整个文件的调用图，导入的模块通过类的模块记录解析
+    pub fn call_graph(&self) -> Result<CallGraph, error::Error> {
+        let parser = self.bytecode_parser();
+        let mut graph = CallGraph::default();
+        // 名为 `@ohos.*`、`@system.*` 的类和外部类是系统 API
+        let names = self.classes.values().map(|clazz| clazz.name().str()).chain(
+            self.foreign_classes
+                .values()
+                .map(|clazz| clazz.name().str()),
+        );
+        for name in names {
+            let name = pa::record_name(&name);
+            if name.starts_with("@ohos.") || name.starts_with("@system.") {
+                graph.add_node(&name, NodeKind::System);
+            }
+        }
+
+        for (offset, clazz) in &self.classes {
+            let region = self.get_region_or_err(*offset as usize)?;
+            let record = self.module_record_of(clazz)?;
+            let class_name = clazz.name().str();
+            for method in clazz.method_map().values() {
+                let name = self.get_string_by_off(*method.name_off())?;
+                let instructions = self.parse_instructions(&parser, region, method)?;
+                graph.add_method(&format!("{}->{}", class_name, name), &instructions, record);
+            }
+        }
+        graph.finish();
+        Ok(graph)
+    }
+
+    /// 统计所有方法的解码覆盖率
+    pub fn coverage(&self) -> Result<Coverage, error::Error> {
+        let mut coverage = Coverage::default();
+        for (_, instructions) in self.parse_code()? {
+            coverage.merge(&Coverage::from_instructions(&instructions));
+        }
+        Ok(coverage)
+    }
+
+    /// 反汇编整个文件，以 `ark_disasm` 的 .pa 格式写入 `w`
+    ///
+    /// 字面量数组按偏移量排序，类和方法按名字排序，便于比较不同版本的输出。
+    pub fn write_pa<W: io::Write>(&self, w: &mut W) -> Result<(), error::Error> {
+        writeln!(w, ".language ECMAScript")?;
+        writeln!(w)?;
+
+        writeln!(w, "{}", pa::SECTION_SEPARATOR)?;
+        writeln!(w, "# LITERALS")?;
+        writeln!(w)?;
+        let mut literal_arrays: Vec<&LiteralArray> = self.literal_array_map.values().collect();
+        literal_arrays.sort_by_key(|array| *array.offset());
+        for (i, array) in literal_arrays.iter().enumerate() {
+            writeln!(w, "{}", pa::literal_array(i, array))?;
+            writeln!(w)?;
+        }
+
+        let mut classes: Vec<(&uint32_t, &Class)> = self.classes.iter().collect();
+        classes.sort_by_key(|(_, clazz)| clazz.name().str());
+        let mut foreign_classes: Vec<String> = self
+            .foreign_classes
+            .values()
+            .map(|clazz| clazz.name().str())
+            .collect();
+        foreign_classes.sort();
+
+        writeln!(w, "{}", pa::SECTION_SEPARATOR)?;
+        writeln!(w, "# RECORDS")?;
+        writeln!(w)?;
+        for name in &foreign_classes {
+            writeln!(w, ".record {} <external>", pa::record_name(name))?;
+            writeln!(w)?;
+        }
+        for (offset, clazz) in &classes {
+            let region = self.get_region_or_err(**offset as usize)?;
+            writeln!(w, ".record {} {{", pa::record_name(&clazz.name().str()))?;
+            for field in clazz.fields() {
+                let type_idx = *field.type_idx() as usize;
+                let type_name = region
+                    .get_type(type_idx)
+                    .at(type_idx, "Field.type_idx")?
+                    .name(self.source.as_ref())?;
+                let name = self.get_string_by_off(*field.name_off())?;
+                match (field.int_value(), field.value()) {
+                    (Some(value), _) if *value < 0 => {
+                        writeln!(w, "\t{} {} = {}", type_name, name, value)?
+                    }
+                    (Some(value), _) => writeln!(w, "\t{} {} = 0x{:x}", type_name, name, value)?,
+                    (None, Some(value)) => writeln!(w, "\t{} {} = 0x{:x}", type_name, name, value)?,
+                    (None, None) => writeln!(w, "\t{} {}", type_name, name)?,
+                }
+            }
+            writeln!(w, "}}")?;
+            writeln!(w)?;
+        }
+
+        writeln!(w, "{}", pa::SECTION_SEPARATOR)?;
+        writeln!(w, "# METHODS")?;
+        writeln!(w)?;
+        let parser = self.bytecode_parser();
+        for (offset, clazz) in &classes {
+            let region = self.get_region_or_err(**offset as usize)?;
+            let class_name = clazz.name().str();
+
+            let mut methods = Vec::new();
+            for method in clazz.method_map().values() {
+                let name = self.get_string_by_off(*method.name_off())?;
+                let signature = method.proto(self.source.as_ref(), region)?;
+                methods.push((name, signature, method));
+            }
+            methods.sort_by_key(|(name, signature, _)| (name.clone(), signature.to_string()));
+
+            for (name, signature, method) in methods {
+                let header =
+                    pa::function_header(&class_name, &name, &signature, method.access_flags());
+                let code_off = *method.method_data().code_off();
+                if code_off == 0 {
+                    writeln!(w, "{}", header)?;
+                    writeln!(w)?;
+                    continue;
+                }
+
+                let code = self.read_code(code_off)?;
+                let instructions =
+                    parser.parse(&code, region, self.source.as_ref(), &self.literal_array_map)?;
+                let branch_labels = bytecode::branch_labels(&instructions);
+                writeln!(w, "{} {{", header)?;
+                for instruction in &instructions {
+                    let pc = *instruction.offset();
+                    for label in code.labels_at(pc as u64) {
+                        writeln!(w, "{}:", label)?;
+                    }
+                    if let Some(label) = branch_labels.get(&pc) {
+                        writeln!(w, "{}:", label)?;
+                    }
+                    writeln!(w, "\t{}", pa::instruction(instruction, *code.num_regs()))?;
+                }
+                // 结束标签可能在最后一条指令之后
+                for label in code.labels_at(*code.code_size()) {
+                    writeln!(w, "{}:", label)?;
+                }
+                let directives = self.catch_directives(&code, region)?;
+                if !directives.is_empty() {
+                    writeln!(w)?;
+                    for directive in directives {
+                        writeln!(w, "\t{}", directive)?;
+                    }
+                }
+                writeln!(w, "}}")?;
+                writeln!(w)?;
             }
         }
+
+        Ok(())
+    }
+
+    /// 反汇编整个文件，以 .pa 格式写入 `path`
+    pub fn write_pa_file<P: AsRef<Path>>(&self, path: P) -> Result<(), error::Error> {
+        let mut w = io::BufWriter::new(File::create(path)?);
+        self.write_pa(&mut w)?;
+        w.flush()?;
+        Ok(())
     }
 
     /// 解析 Class
-    fn parse_class_index(&mut self) {
+    fn parse_class_index(&mut self) -> Result<(), error::Error> {
         let num_classes = self.header.classes_size() as usize;
         let class_idx_off = self.header.class_idx_off() as usize;
 
         // 一次性解析所有的Class
         for i in 0..num_classes {
             let off = class_idx_off + i * 4;
-            let class_idx_off = self.source.pread::<uint32_t>(off).unwrap();
+            let class_idx_off = self.source.pread::<uint32_t>(off).at(off, "ClassIndex")?;
 
             let is_foreign_class = self.is_foreign_off(class_idx_off);
 
@@ -175,22 +597,23 @@ where
                     .source
                     .as_ref()
                     .pread::<ForeignClass>(class_idx_off as usize)
-                    .unwrap();
+                    .map_err(|e| e.rebase(class_idx_off as usize))?;
                 self.foreign_classes.insert(class_idx_off, class);
             } else {
                 let class = self
                     .source
                     .as_ref()
                     .pread::<Class>(class_idx_off as usize)
-                    .unwrap();
+                    .map_err(|e| e.rebase(class_idx_off as usize))?;
                 self.classes.insert(class_idx_off, class);
             }
         }
+
+        Ok(())
     }
 
-    #[allow(dead_code)]
-    fn parse_lnp_idx(&mut self) {
-        // NOTE: 解析行号程序，未来再说。
+    /// 解析行号程序索引
+    fn parse_lnp_idx(&mut self) -> Result<(), error::Error> {
         let mut lnp_idx = LineNumberProgramIndex::default();
         let num_lnp = self.header().num_lnps() as usize;
         let lnp_off = self.header().lnp_idx_off() as usize;
@@ -199,56 +622,42 @@ where
                 .source
                 .as_ref()
                 .pread::<uint32_t>(lnp_off + i * 4)
-                .unwrap();
+                .at(lnp_off + i * 4, "LineNumberProgramIndex")?;
 
             lnp_idx.push(offset);
         }
-    }
-
-    fn get_class_name_by_offset(&self, idx: uint32_t) -> ABCString {
-        if self.is_foreign_off(idx) {
-            let v = self.foreign_classes.get(&idx).unwrap();
-            return v.name().clone();
-        }
 
-        self.classes.get(&idx).unwrap().name().clone()
+        self.lnp_idx = lnp_idx;
+        Ok(())
     }
 
-    /// 获取基本类型
-    fn get_primitive_type(&self, i: uint32_t) -> FieldType {
-        let names = [
-            "i8", "u8", "i16", "u16", "i32", "u32", "f32", "f64", "i64", "u64", "any",
-        ];
-
-        let n = names[i as usize];
-        FieldType {
-            name: n.to_string(),
+    /// 解析 ClassRegionIndex 中的类型，小于等于 0xb 的是基本类型，其他的是类的偏移量
+    fn parse_type_ref(&self, value: uint32_t) -> TypeRef {
+        if let Some(t) = PrimitiveType::from_u32(value) {
+            return TypeRef::Primitive(t);
         }
-    }
-
-    /// 解析字段类型
-    fn parse_field_type(&mut self, idx: uint32_t) -> FieldType {
-        if idx <= 0xb {
-            return self.get_primitive_type(idx);
+        if self.foreign_classes.contains_key(&value) {
+            return TypeRef::Foreign(value);
         }
-
-        let item = self.get_class_name_by_offset(idx).to_string();
-        FieldType { name: item }
+        if self.classes.contains_key(&value) {
+            return TypeRef::Class(value);
+        }
+        tracing::warn!("ClassRegionIndex: unresolved type 0x{:x}", value);
+        TypeRef::Unresolved(value)
     }
 
-    fn get_string_by_off(&self, off: uint32_t) -> String {
-        self.source
-            .as_ref()
-            .pread::<ABCString>(off as usize)
-            .unwrap()
-            .str()
+    fn get_string_by_off(&self, off: uint32_t) -> Result<String, error::Error> {
+        Ok(self.get_string(off)?.str())
     }
 
     /// 解析 RegionIndex
-    fn parse_region_index(&mut self) {
+    fn parse_region_index(&mut self) -> Result<(), error::Error> {
         for i in 0..self.header().region_size() as usize {
-            let off = self.header().region_off() as usize + i * 4;
-            let region_header = self.source.pread::<RegionHeader>(off).unwrap();
+            let off = self.header().region_off() as usize + i * REGION_HEADER_SIZE;
+            let region_header = self
+                .source
+                .pread::<RegionHeader>(off)
+                .at(off, "RegionHeader")?;
 
             // 解析 ClassRegionIndex
             let mut class_region_idx = ClassRegionIndex::default();
@@ -256,18 +665,22 @@ where
             for i in 0..region_header.class_idx_size() as usize {
                 let off = class_idx_off + i * 4;
 
-                // 一个FiedType 大小是u32
-                let class_offset = self.source.pread_with::<uint32_t>(off, scroll::LE).unwrap();
-                let f = self.parse_field_type(class_offset);
-                // tracing::debug!("FieldType: {} -> {:?}", off, &f);
-                class_region_idx.push(f);
+                // 一个 Type 大小是u32
+                let class_offset = self
+                    .source
+                    .pread_with::<uint32_t>(off, scroll::LE)
+                    .at(off, "ClassRegionIndex")?;
+                class_region_idx.push(self.parse_type_ref(class_offset));
             }
 
             // 解析 MethodStringLiteralRegionIndex
             let msl_off = region_header.method_string_literal_region_idx_off() as usize;
             let mut mslr_idx = MethodStringLiteralRegionIndex::default();
             for i in 0..region_header.method_string_literal_region_idx_size() as usize {
-                let offset = self.source.pread::<uint32_t>(msl_off + i * 4).unwrap();
+                let offset = self
+                    .source
+                    .pread::<uint32_t>(msl_off + i * 4)
+                    .at(msl_off + i * 4, "MethodStringLiteralRegionIndex")?;
                 mslr_idx.push(offset);
             }
 
@@ -280,7 +693,7 @@ where
                     let offset = self
                         .source
                         .pread_with::<uint32_t>(field_idx_off + i * 4, scroll::LE)
-                        .unwrap();
+                        .at(field_idx_off + i * 4, "FieldRegionIndex")?;
                     field_idx.push(offset);
                 }
             }
@@ -294,7 +707,7 @@ where
                     let offset = self
                         .source
                         .pread_with::<uint32_t>(proto_idx_off + i * 4, scroll::LE)
-                        .unwrap();
+                        .at(proto_idx_off + i * 4, "ProtoRegionIndex")?;
                     proto_idx.push(offset);
                 }
             }
@@ -308,6 +721,8 @@ where
             );
             self.regions.push(region);
         }
+
+        Ok(())
     }
 
     /// 判断数据是否在外部区域
@@ -317,6 +732,151 @@ where
         start <= class_idx && class_idx <= end
     }
 
+    /// 获取类型名，类和外部类的名字在需要时才从文件中读取
+    pub fn get_type_name(&self, type_ref: TypeRef) -> Result<String, error::Error> {
+        type_ref.name(self.source.as_ref())
+    }
+
+    /// 偏移量为 `class_off` 的类实现的接口名
+    pub fn get_interfaces(&self, class_off: uint32_t) -> Result<Vec<String>, error::Error> {
+        let clazz = self
+            .classes
+            .get(&class_off)
+            .ok_or_else(|| error::Error::InvalidId(format!("类不存在: {}", class_off)))?;
+        let region = self.get_region_or_err(class_off as usize)?;
+        clazz
+            .interfaces()
+            .iter()
+            .map(|idx| {
+                region
+                    .get_type(*idx as usize)
+                    .at(*idx as usize, "Class.INTERFACES")?
+                    .name(self.source.as_ref())
+            })
+            .collect()
+    }
+
+    /// 偏移量为 `class_off` 的类的源文件名，没有 `SOURCE_FILE` 标签时返回 `None`
+    pub fn get_source_file(&self, class_off: uint32_t) -> Result<Option<String>, error::Error> {
+        let clazz = self
+            .classes
+            .get(&class_off)
+            .ok_or_else(|| error::Error::InvalidId(format!("类不存在: {}", class_off)))?;
+        clazz
+            .source_file_off()
+            .map(|off| self.get_string_by_off(off))
+            .transpose()
+    }
+
+    /// 按源文件名对类分组，没有源文件名的类不包括在内
+    pub fn classes_by_source_file(&self) -> Result<BTreeMap<String, Vec<String>>, error::Error> {
+        let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (offset, clazz) in &self.classes {
+            if let Some(source_file) = self.get_source_file(*offset)? {
+                groups
+                    .entry(source_file)
+                    .or_default()
+                    .push(clazz.name().str());
+            }
+        }
+        for classes in groups.values_mut() {
+            classes.sort();
+        }
+        Ok(groups)
+    }
+
+    /// 解析字段的初始值，`class_off` 为字段所在类的偏移量
+    ///
+    /// `VALUE` 指向字面量数组或模块记录时优先按偏移量解析，其他的按字段类型解析：
+    /// 32 位及以下的整数直接存放，`f32` 为浮点数的位，`f64`、`i64`、`u64` 指向实际的值，
+    /// 引用类型指向字符串。
+    pub fn get_field_value(
+        &self,
+        class_off: uint32_t,
+        field: &Field,
+    ) -> Result<Option<FieldValue>, error::Error> {
+        if let Some(value) = field.int_value() {
+            return Ok(Some(FieldValue::Int(*value)));
+        }
+        let Some(value) = *field.value() else {
+            return Ok(None);
+        };
+
+        let offset = value as usize;
+        // 字面量数组或模块记录的偏移量，如 u32 类型的 `moduleRecordIdx`
+        let is_literal_array = self.literal_array_map.contains_key(&offset)
+            || self.module_records.contains_key(&offset);
+
+        let region = self.get_region_or_err(class_off as usize)?;
+        let type_idx = *field.type_idx() as usize;
+        let type_ref = region.get_type(type_idx).at(type_idx, "Field.type_idx")?;
+        let source = self.source.as_ref();
+        let value = match type_ref {
+            TypeRef::Primitive(PrimitiveType::F32) => FieldValue::Float(f32::from_bits(value)),
+            TypeRef::Primitive(PrimitiveType::F64) => {
+                FieldValue::Double(source.pread::<f64>(offset).at(offset, "Field.VALUE")?)
+            }
+            TypeRef::Primitive(PrimitiveType::I64) => {
+                FieldValue::Int(source.pread::<i64>(offset).at(offset, "Field.VALUE")?)
+            }
+            TypeRef::Primitive(PrimitiveType::U64) => {
+                FieldValue::UInt(source.pread::<u64>(offset).at(offset, "Field.VALUE")?)
+            }
+            TypeRef::Primitive(PrimitiveType::U32 | PrimitiveType::Any) if is_literal_array => {
+                FieldValue::LiteralArray(value)
+            }
+            // 不超过 32 位的整数直接存放在 VALUE 中
+            TypeRef::Primitive(t) => match t {
+                PrimitiveType::U1 => FieldValue::Int((value != 0) as i64),
+                PrimitiveType::I8 => FieldValue::Int(value as i8 as i64),
+                PrimitiveType::U8 => FieldValue::Int(value as u8 as i64),
+                PrimitiveType::I16 => FieldValue::Int(value as i16 as i64),
+                PrimitiveType::U16 => FieldValue::Int(value as u16 as i64),
+                PrimitiveType::I32 => FieldValue::Int(value as i32 as i64),
+                PrimitiveType::U32 => FieldValue::Int(value as i64),
+                _ => FieldValue::Raw(value),
+            },
+            TypeRef::Class(_) | TypeRef::Foreign(_) | TypeRef::Unresolved(_)
+                if is_literal_array =>
+            {
+                FieldValue::LiteralArray(value)
+            }
+            // 只有引用类型的 VALUE 指向字符串
+            TypeRef::Class(_) | TypeRef::Foreign(_) => match self.get_string(value) {
+                Ok(string) if !string.is_lossy() => FieldValue::String(string.str()),
+                _ => FieldValue::Raw(value),
+            },
+            TypeRef::Unresolved(_) => FieldValue::Raw(value),
+        };
+        Ok(Some(value))
+    }
+
+    /// 获取所有的字段，格式：`类名->字段名: 类型 (访问标志) = 初始值`
+    pub fn get_fields(&self) -> Result<Vec<String>, error::Error> {
+        let mut fields = Vec::new();
+        for (offset, clz) in self.classes.iter() {
+            let region = self.get_region_or_err(*offset as usize)?;
+            let class_name = clz.name().str();
+            for field in clz.fields() {
+                let name = self.get_string_by_off(*field.name_off())?;
+                let type_idx = *field.type_idx() as usize;
+                let type_name = region
+                    .get_type(type_idx)
+                    .at(type_idx, "Field.type_idx")?
+                    .name(self.source.as_ref())?;
+                let mut line = format!("{}->{}: {}", class_name, name, type_name);
+                if !field.access_flags().is_empty() {
+                    line.push_str(&format!(" ({})", field.access_flags().join(" ")));
+                }
+                if let Some(value) = self.get_field_value(*offset, field)? {
+                    line.push_str(&format!(" = {}", value));
+                }
+                fields.push(line);
+            }
+        }
+        Ok(fields)
+    }
+
     /// 获取所有的类名
     pub fn get_class_names(&self) -> Vec<String> {
         let mut class_names = Vec::new();
@@ -328,24 +888,48 @@ where
     }
 
     /// 获取所有的方法名
-    pub fn get_method_names(&self) -> Vec<String> {
+    pub fn get_method_names(&self) -> Result<Vec<String>, error::Error> {
         let mut method_names = Vec::new();
         for clz in self.classes.values() {
             let class_name = clz.name().str();
             for (_, method) in clz.method_map().iter() {
-                let name = self.get_string_by_off(*method.name_off());
+                let name = self.get_string_by_off(*method.name_off())?;
                 method_names.push(class_name.to_string() + "->" + &name);
             }
         }
-        method_names
+        Ok(method_names)
     }
 
-    /// 获取所有的字符串
-    pub fn get_strings(&self) -> Vec<String> {
+    /// 获取所有方法的签名，格式：类名->方法名(参数类型)返回值类型
+    pub fn get_method_signatures(&self) -> Result<Vec<String>, error::Error> {
+        let mut signatures = Vec::new();
+        for (offset, clz) in self.classes.iter() {
+            let region = self.get_region_or_err(*offset as usize)?;
+            let class_name = clz.name().str();
+            for (_, method) in clz.method_map().iter() {
+                let name = self.get_string_by_off(*method.name_off())?;
+                let proto = method.proto(self.source.as_ref(), region)?;
+                signatures.push(format!("{}->{}{}", class_name, name, proto));
+            }
+        }
+        Ok(signatures)
+    }
+
+    /// 读取 `offset` 处的字符串，包括编码和原始数据
+    pub fn get_string(&self, offset: uint32_t) -> Result<ABCString, error::Error> {
+        self.source
+            .as_ref()
+            .pread::<ABCString>(offset as usize)
+            .map_err(|e| e.rebase(offset as usize))
+    }
+
+    /// 获取所有的字符串及其偏移量
+    pub fn get_abc_strings(&self) -> Vec<(uint32_t, ABCString)> {
+        // method_map 的键是相对于类的偏移量
         let mut method_offsets = HashSet::new();
-        for clz in self.classes.values() {
+        for (class_off, clz) in self.classes.iter() {
             for (offset, _) in clz.method_map().iter() {
-                method_offsets.insert(offset);
+                method_offsets.insert(*class_off as usize + offset);
             }
         }
 
@@ -358,23 +942,35 @@ where
                     continue;
                 }
 
-                if self.literal_array_map.contains_key(&offset) {
+                if self.literal_array_map.contains_key(&offset)
+                    || self.module_records.contains_key(&offset)
+                {
                     continue;
                 }
 
-                // tracing::debug!("string offset -> {}", offset);
-                let string = self.get_string_by_off(offset as u32);
-                if string == "-utf8-error-" {
-                    tracing::warn!("{} -> 解析错误，不是字符串", offset);
+                // 这里的偏移量不一定指向字符串，读取失败的直接跳过
+                let Ok(string) = self.get_string(offset as uint32_t) else {
+                    tracing::warn!("{} -> 读取失败，不是字符串", offset);
                     continue;
+                };
+                if string.is_lossy() {
+                    tracing::warn!("{} -> 部分内容无法解码: {:?}", offset, string.raw());
                 }
                 tracing::debug!("{} -> {}", offset, string);
-                strings.push(string);
+                strings.push((offset as uint32_t, string));
             }
         }
 
         strings
     }
+
+    /// 获取所有的字符串
+    pub fn get_strings(&self) -> Vec<String> {
+        self.get_abc_strings()
+            .into_iter()
+            .map(|(_, string)| string.str())
+            .collect()
+    }
 }
 
 /// 用于读取 `Abc` 文件
@@ -425,8 +1021,11 @@ impl AbcReader {
             foreign_classes: HashMap::new(),
             regions: Vec::new(),
             literal_array_map: HashMap::new(),
+            module_records: HashMap::new(),
+            lnp_idx: LineNumberProgramIndex::default(),
+            xref: OnceLock::new(),
         };
-        abc_file.init();
+        abc_file.init()?;
 
         Ok(abc_file)
     }
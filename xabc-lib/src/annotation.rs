@@ -0,0 +1,381 @@
+use std::fmt;
+
+use getset::Getters;
+use scroll::{Pread, Uleb128};
+
+use crate::error::{self, ReadContext};
+use crate::region::Region;
+use crate::string::ABCString;
+use crate::{uint16_t, uint32_t};
+
+/// 嵌套注解的最大深度，防止畸形文件中的注解互相引用导致死循环
+const MAX_DEPTH: usize = 16;
+
+/// 注解的种类，对应 ClassData、MethodData 和 FieldData 中不同的 Tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationKind {
+    /// RUNTIME_ANNOTATION，运行时可见
+    Runtime,
+    /// ANNOTATION，运行时不可见，如 `L_ESSlotNumberAnnotation;`
+    Plain,
+    RuntimeType,
+    Type,
+}
+
+/// 注解元素的值
+// https://gitee.com/openharmony/arkcompiler_runtime_core/blob/master/libpandafile/annotation_data_accessor.h
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnnotationValue {
+    U1(bool),
+    I8(i8),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    String(String),
+    /// 类的偏移量
+    Record(uint32_t),
+    /// 方法的偏移量
+    Method(uint32_t),
+    /// 字段的偏移量
+    Enum(uint32_t),
+    MethodHandle(uint32_t),
+    /// 字面量数组的偏移量
+    LiteralArray(uint32_t),
+    Annotation(Box<Annotation>),
+    Array(Vec<AnnotationValue>),
+    NullString,
+    /// 无法确定含义的值
+    Raw(uint32_t),
+}
+
+impl fmt::Display for AnnotationValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnnotationValue::U1(value) => write!(f, "{}", value),
+            AnnotationValue::I8(value) => write!(f, "{}", value),
+            AnnotationValue::U8(value) => write!(f, "{}", value),
+            AnnotationValue::I16(value) => write!(f, "{}", value),
+            AnnotationValue::U16(value) => write!(f, "{}", value),
+            AnnotationValue::I32(value) => write!(f, "{}", value),
+            AnnotationValue::U32(value) => write!(f, "{}", value),
+            AnnotationValue::I64(value) => write!(f, "{}", value),
+            AnnotationValue::U64(value) => write!(f, "{}", value),
+            AnnotationValue::F32(value) => write!(f, "{}", value),
+            AnnotationValue::F64(value) => write!(f, "{}", value),
+            AnnotationValue::String(value) => write!(f, "\"{}\"", value),
+            AnnotationValue::Record(offset) => write!(f, "record 0x{:x}", offset),
+            AnnotationValue::Method(offset) => write!(f, "method 0x{:x}", offset),
+            AnnotationValue::Enum(offset) => write!(f, "enum 0x{:x}", offset),
+            AnnotationValue::MethodHandle(offset) => write!(f, "method_handle 0x{:x}", offset),
+            AnnotationValue::LiteralArray(offset) => write!(f, "literal_array 0x{:x}", offset),
+            AnnotationValue::Annotation(annotation) => write!(f, "{}", annotation),
+            AnnotationValue::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            AnnotationValue::NullString => write!(f, "null"),
+            AnnotationValue::Raw(value) => write!(f, "0x{:x}", value),
+        }
+    }
+}
+
+/// 注解元素，即一个名字-值对
+#[derive(Debug, Clone, PartialEq, Getters)]
+#[get = "pub"]
+pub struct AnnotationElement {
+    name: String,
+    /// 元素的类型，如 `'7'` 表示 u32，`'V'` 表示字符串数组
+    tag: char,
+    value: AnnotationValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Getters)]
+#[get = "pub"]
+pub struct Annotation {
+    /// 注解在文件中的偏移量
+    offset: uint32_t,
+    kind: AnnotationKind,
+    /// ClassRegionIndex 的一个索引
+    class_idx: uint16_t,
+    /// 注解的类型名，如 `L_ESSlotNumberAnnotation;`
+    type_name: String,
+    elements: Vec<AnnotationElement>,
+}
+
+impl Annotation {
+    /// 根据名字获取元素的值
+    pub fn get(&self, name: &str) -> Option<&AnnotationValue> {
+        self.elements
+            .iter()
+            .find(|element| element.name == name)
+            .map(|element| &element.value)
+    }
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", self.type_name)?;
+        for (i, element) in self.elements.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}={}", element.name, element.value)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// 数组的 Tag 对应的元素 Tag，如 `'K'`（u1[]）对应 `'1'`（u1）
+fn array_element_tag(tag: u8) -> Option<u8> {
+    const ARRAY_TAGS: &[u8] = b"KLMNOPQRSTUVWXYZ@";
+    const ELEMENT_TAGS: &[u8] = b"123456789ABCDEFGJ";
+    ARRAY_TAGS
+        .iter()
+        .position(|t| *t == tag)
+        .map(|i| ELEMENT_TAGS[i])
+}
+
+struct AnnotationReader<'a> {
+    source: &'a [u8],
+    region: &'a Region,
+    kind: AnnotationKind,
+}
+
+impl AnnotationReader<'_> {
+    fn read_string(&self, string_off: uint32_t) -> Result<String, error::Error> {
+        let s = self
+            .source
+            .pread::<ABCString>(string_off as usize)
+            .map_err(|e| e.rebase(string_off as usize))?;
+        Ok(s.str())
+    }
+
+    fn read_annotation(&self, offset: usize, depth: usize) -> Result<Annotation, error::Error> {
+        if depth > MAX_DEPTH {
+            return Err(error::Error::MalFormed(format!(
+                "Annotation: nested too deep at {}",
+                offset
+            )));
+        }
+
+        let class_idx = self
+            .source
+            .pread::<uint16_t>(offset)
+            .at(offset, "Annotation.class_idx")?;
+        let count = self
+            .source
+            .pread::<uint16_t>(offset + 2)
+            .at(offset + 2, "Annotation.count")? as usize;
+        let type_name = self
+            .region
+            .get_type(class_idx as usize)
+            .at(offset, "Annotation.class_idx")?
+            .name(self.source)?;
+
+        // 元素之后是每个元素的类型
+        let elements_off = offset + 4;
+        let types_off = elements_off + count * 8;
+        let mut elements = Vec::with_capacity(count);
+        for i in 0..count {
+            let element_off = elements_off + i * 8;
+            let name_off = self
+                .source
+                .pread::<uint32_t>(element_off)
+                .at(element_off, "AnnotationElement.name_off")?;
+            let value = self
+                .source
+                .pread::<uint32_t>(element_off + 4)
+                .at(element_off + 4, "AnnotationElement.value")?;
+            let tag = self
+                .source
+                .pread::<u8>(types_off + i)
+                .at(types_off + i, "Annotation.element_types")?;
+
+            elements.push(AnnotationElement {
+                name: self.read_string(name_off)?,
+                tag: tag as char,
+                value: self.read_value(tag, value, depth)?,
+            });
+        }
+
+        Ok(Annotation {
+            offset: offset as uint32_t,
+            kind: self.kind,
+            class_idx,
+            type_name,
+            elements,
+        })
+    }
+
+    /// 解析元素的值，宽度不超过 32 位的值直接存放在 `value` 中，其他的 `value` 是偏移量
+    fn read_value(
+        &self,
+        tag: u8,
+        value: uint32_t,
+        depth: usize,
+    ) -> Result<AnnotationValue, error::Error> {
+        let offset = value as usize;
+        let value = match tag {
+            b'8' | b'9' | b'B' => {
+                let bits = self
+                    .source
+                    .pread::<u64>(offset)
+                    .at(offset, "AnnotationElement.value")?;
+                self.read_scalar(tag, bits, depth)?
+            }
+            b'H' => AnnotationValue::Raw(value),
+            b'*' => AnnotationValue::NullString,
+            b'#' => AnnotationValue::LiteralArray(value),
+            _ => match array_element_tag(tag) {
+                Some(element_tag) => self.read_array(element_tag, offset, depth)?,
+                None => self.read_scalar(tag, value as u64, depth)?,
+            },
+        };
+        Ok(value)
+    }
+
+    /// ArrayValue：uleb128 的元素个数，之后是按元素类型宽度存放的元素
+    fn read_array(
+        &self,
+        element_tag: u8,
+        offset: usize,
+        depth: usize,
+    ) -> Result<AnnotationValue, error::Error> {
+        let mut cursor = offset;
+        let off = &mut cursor;
+        let count = Uleb128::read(self.source, off).at(offset, "ArrayValue.count")?;
+        let mut values = Vec::new();
+        for _ in 0..count {
+            let at = *off;
+            let bits = match element_tag {
+                b'1' | b'2' | b'3' => self.source.gread::<u8>(off).map(u64::from),
+                b'4' | b'5' => self.source.gread::<u16>(off).map(u64::from),
+                b'8' | b'9' | b'B' => self.source.gread::<u64>(off),
+                _ => self.source.gread::<u32>(off).map(u64::from),
+            }
+            .at(at, "ArrayValue.elements")?;
+            values.push(self.read_scalar(element_tag, bits, depth)?);
+        }
+        Ok(AnnotationValue::Array(values))
+    }
+
+    fn read_scalar(
+        &self,
+        tag: u8,
+        bits: u64,
+        depth: usize,
+    ) -> Result<AnnotationValue, error::Error> {
+        let value = match tag {
+            b'1' => AnnotationValue::U1(bits != 0),
+            b'2' => AnnotationValue::I8(bits as i8),
+            b'3' => AnnotationValue::U8(bits as u8),
+            b'4' => AnnotationValue::I16(bits as i16),
+            b'5' => AnnotationValue::U16(bits as u16),
+            b'6' => AnnotationValue::I32(bits as i32),
+            b'7' => AnnotationValue::U32(bits as u32),
+            b'8' => AnnotationValue::I64(bits as i64),
+            b'9' => AnnotationValue::U64(bits),
+            b'A' => AnnotationValue::F32(f32::from_bits(bits as u32)),
+            b'B' => AnnotationValue::F64(f64::from_bits(bits)),
+            b'C' => AnnotationValue::String(self.read_string(bits as uint32_t)?),
+            b'D' => AnnotationValue::Record(bits as uint32_t),
+            b'E' => AnnotationValue::Method(bits as uint32_t),
+            b'F' => AnnotationValue::Enum(bits as uint32_t),
+            b'G' => AnnotationValue::Annotation(Box::new(
+                self.read_annotation(bits as usize, depth + 1)?,
+            )),
+            b'J' => AnnotationValue::MethodHandle(bits as uint32_t),
+            _ => AnnotationValue::Raw(bits as uint32_t),
+        };
+        Ok(value)
+    }
+}
+
+/// 解析 `offset` 处的注解，`region` 为注解所属的类、方法或字段所在的区域
+pub fn parse_annotation(
+    source: &[u8],
+    offset: usize,
+    kind: AnnotationKind,
+    region: &Region,
+) -> Result<Annotation, error::Error> {
+    let reader = AnnotationReader {
+        source,
+        region,
+        kind,
+    };
+    reader.read_annotation(offset, 0)
+}
+
+/// 依次解析各个种类的注解，无法解析的注解记录警告后跳过
+pub(crate) fn parse_annotations(
+    source: &[u8],
+    region: &Region,
+    groups: [(AnnotationKind, &[uint32_t]); 4],
+) -> Vec<Annotation> {
+    let mut annotations = Vec::new();
+    for (kind, offsets) in groups {
+        for offset in offsets {
+            if let Some(annotation) = parse_or_warn(source, *offset as usize, kind, region) {
+                annotations.push(annotation);
+            }
+        }
+    }
+    annotations
+}
+
+fn parse_or_warn(
+    source: &[u8],
+    offset: usize,
+    kind: AnnotationKind,
+    region: &Region,
+) -> Option<Annotation> {
+    match parse_annotation(source, offset, kind, region) {
+        Ok(annotation) => Some(annotation),
+        Err(e) => {
+            tracing::warn!("Annotation 0x{:x}: {}", offset, e);
+            None
+        }
+    }
+}
+
+/// 解析参数注解，结果按参数的顺序排列，无法解析的注解记录警告后跳过
+///
+/// ParamAnnotations：u32 的参数个数，之后每个参数是一个 AnnotationArray（u32 的个数和注解的偏移量）。
+pub fn parse_param_annotations(
+    source: &[u8],
+    offset: usize,
+    kind: AnnotationKind,
+    region: &Region,
+) -> Result<Vec<Vec<Annotation>>, error::Error> {
+    let mut cursor = offset;
+    let off = &mut cursor;
+    let read_u32 = |off: &mut usize| -> Result<uint32_t, error::Error> {
+        let at = *off;
+        source.gread::<uint32_t>(off).at(at, "ParamAnnotations")
+    };
+
+    let mut params = Vec::new();
+    for _ in 0..read_u32(off)? {
+        let mut annotations = Vec::new();
+        for _ in 0..read_u32(off)? {
+            let annotation_off = read_u32(off)?;
+            if let Some(annotation) = parse_or_warn(source, annotation_off as usize, kind, region) {
+                annotations.push(annotation);
+            }
+        }
+        params.push(annotations);
+    }
+    Ok(params)
+}
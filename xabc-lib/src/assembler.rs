@@ -0,0 +1,385 @@
+/// 指令汇编：根据字节码表把助记符和操作数编码为字节码
+use std::collections::HashMap;
+
+use crate::bytecode::{ByteCodeFormat, BytecodeParser, FormatUnit};
+use crate::error;
+use crate::writer::{CatchDef, LiteralArrayId, MethodId, TryDef};
+
+/// 指令的操作数，与 `bytecode::Operand` 对应，保留字段 `RR`/`RRRR` 不需要给出
+#[derive(Debug, Clone, PartialEq)]
+pub enum Arg {
+    /// 寄存器，参数寄存器从 `num_regs` 开始编号
+    Reg(u16),
+    /// 立即数，负数按补码写入
+    Imm(i64),
+    /// 跳转目标的标签
+    Label(String),
+    /// 字符串，写入时分配 MethodStringLiteralRegionIndex 的索引
+    String(String),
+    Method(MethodId),
+    LiteralArray(LiteralArrayId),
+    /// 已分配的 MethodStringLiteralRegionIndex 索引，直接写入
+    Id(u16),
+}
+
+#[derive(Debug, Clone)]
+enum Item {
+    Label(String),
+    Instruction { mnemonic: String, args: Vec<Arg> },
+}
+
+/// 异常处理表，使用标签表示范围
+#[derive(Debug, Clone)]
+struct TryLabels {
+    begin: String,
+    end: String,
+    handler_begin: String,
+    handler_end: String,
+    /// 捕获的异常类型在 ClassRegionIndex 中的索引，`None` 表示捕获所有类型的异常
+    type_idx: Option<u16>,
+}
+
+/// 方法的 Code，按顺序添加指令和标签，写入时才编码
+#[derive(Debug, Clone, Default)]
+pub struct CodeBuilder {
+    num_regs: u64,
+    num_args: u64,
+    items: Vec<Item>,
+    tries: Vec<TryLabels>,
+}
+
+impl CodeBuilder {
+    /// `num_regs` 为寄存器数量，`num_args` 为参数数量（包括 FunctionObject、NewTarget 和 this）
+    pub fn new(num_regs: u64, num_args: u64) -> Self {
+        Self {
+            num_regs,
+            num_args,
+            ..Default::default()
+        }
+    }
+
+    /// 在当前位置放置标签
+    pub fn label(&mut self, name: &str) -> &mut Self {
+        self.items.push(Item::Label(name.to_string()));
+        self
+    }
+
+    /// 添加一条指令，同名的多种格式中选择能容纳操作数的最短格式
+    pub fn emit(&mut self, mnemonic: &str, args: &[Arg]) -> &mut Self {
+        self.items.push(Item::Instruction {
+            mnemonic: mnemonic.to_string(),
+            args: args.to_vec(),
+        });
+        self
+    }
+
+    /// 添加异常处理，`[begin, end)` 中的异常跳到 `[handler_begin, handler_end)` 处理
+    pub fn try_catch(
+        &mut self,
+        begin: &str,
+        end: &str,
+        handler_begin: &str,
+        handler_end: &str,
+        type_idx: Option<u16>,
+    ) -> &mut Self {
+        self.tries.push(TryLabels {
+            begin: begin.to_string(),
+            end: end.to_string(),
+            handler_begin: handler_begin.to_string(),
+            handler_end: handler_end.to_string(),
+            type_idx,
+        });
+        self
+    }
+
+    pub fn num_regs(&self) -> u64 {
+        self.num_regs
+    }
+
+    pub fn num_args(&self) -> u64 {
+        self.num_args
+    }
+
+    /// 指令中引用的字符串、方法和字面量数组，写入前需要为它们分配索引
+    pub(crate) fn id_args(&self) -> impl Iterator<Item = &Arg> {
+        self.items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Instruction { args, .. } => Some(args),
+                Item::Label(_) => None,
+            })
+            .flatten()
+            .filter(|arg| matches!(arg, Arg::String(_) | Arg::Method(_) | Arg::LiteralArray(_)))
+    }
+}
+
+/// 汇编后的 Code
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Assembled {
+    pub(crate) instructions: Vec<u8>,
+    /// 标签及其 pc
+    pub(crate) labels: HashMap<String, usize>,
+    pub(crate) try_blocks: Vec<TryDef>,
+}
+
+/// 同一个助记符的所有格式，按大小从小到大排列
+fn formats_by_mnemonic(parser: &BytecodeParser) -> HashMap<&str, Vec<(u16, &ByteCodeFormat)>> {
+    let mut map: HashMap<&str, Vec<(u16, &ByteCodeFormat)>> = HashMap::new();
+    for (opcode, bcf) in parser
+        .opcode_table
+        .iter()
+        .chain(parser.prefix_opcode_table.iter())
+    {
+        map.entry(bcf.mnemonic()).or_default().push((*opcode, bcf));
+    }
+    for formats in map.values_mut() {
+        formats.sort_by_key(|(opcode, bcf)| (bcf.get_size(), *opcode));
+    }
+    map
+}
+
+/// 立即数能否用 `bytes` 个字节表示，有符号或无符号均可
+fn imm_fits(value: i64, bytes: u32, signed: bool) -> bool {
+    if bytes >= 8 {
+        return true;
+    }
+    let bits = bytes * 8;
+    let min = -(1i64 << (bits - 1));
+    let max = if signed {
+        (1i64 << (bits - 1)) - 1
+    } else {
+        (1i64 << bits) - 1
+    };
+    min <= value && value <= max
+}
+
+fn imm_size(unit: &FormatUnit) -> Option<u32> {
+    match unit {
+        FormatUnit::IMM8 => Some(1),
+        FormatUnit::IMM16 => Some(2),
+        FormatUnit::IMM32 => Some(4),
+        FormatUnit::IMM64 => Some(8),
+        _ => None,
+    }
+}
+
+/// 格式中每个需要操作数的位置，`Imm4Imm4` 和 `V4V4` 各占两个
+fn operand_units(bcf: &ByteCodeFormat) -> Vec<&FormatUnit> {
+    let mut units = Vec::new();
+    for unit in bcf.formats() {
+        match unit {
+            FormatUnit::Opcode | FormatUnit::PrefixOpcode | FormatUnit::RR | FormatUnit::RRRR => {}
+            FormatUnit::V4V4 | FormatUnit::Imm4Imm4 => {
+                units.push(unit);
+                units.push(unit);
+            }
+            _ => units.push(unit),
+        }
+    }
+    units
+}
+
+/// 操作数是否能放进格式中，跳转的标签在确定位置之后再检查
+fn args_fit(bcf: &ByteCodeFormat, args: &[Arg]) -> bool {
+    let units = operand_units(bcf);
+    units.len() == args.len()
+        && units.iter().zip(args).all(|(unit, arg)| match (unit, arg) {
+            (FormatUnit::V4V4, Arg::Reg(reg)) => *reg < 0x10,
+            (FormatUnit::V8, Arg::Reg(reg)) => *reg <= 0xff,
+            (FormatUnit::V16, Arg::Reg(_)) => true,
+            (FormatUnit::Imm4Imm4, Arg::Imm(value)) => (0..0x10).contains(value),
+            (unit, Arg::Imm(value)) => match imm_size(unit) {
+                Some(size) => imm_fits(*value, size, bcf.is_branch()),
+                None => false,
+            },
+            (unit, Arg::Label(_)) => bcf.is_branch() && imm_size(unit).is_some(),
+            (FormatUnit::StringID, Arg::String(_)) => true,
+            (FormatUnit::MethodID, Arg::Method(_)) => true,
+            (FormatUnit::LiteralID, Arg::LiteralArray(_)) => true,
+            (FormatUnit::StringID | FormatUnit::MethodID | FormatUnit::LiteralID, Arg::Id(_)) => {
+                true
+            }
+            _ => false,
+        })
+}
+
+fn find_label(labels: &HashMap<String, usize>, name: &str) -> Result<usize, error::Error> {
+    labels
+        .get(name)
+        .copied()
+        .ok_or_else(|| error::Error::InvalidId(format!("标签不存在: {}", name)))
+}
+
+/// 编码 `builder` 中的指令，`resolve` 返回字符串、方法和字面量数组在 MethodStringLiteralRegionIndex 中的索引
+pub(crate) fn assemble(
+    builder: &CodeBuilder,
+    parser: &BytecodeParser,
+    resolve: &dyn Fn(&Arg) -> Option<u16>,
+) -> Result<Assembled, error::Error> {
+    let table = formats_by_mnemonic(parser);
+
+    // 每条指令可选的格式
+    let mut candidates = Vec::new();
+    for item in &builder.items {
+        if let Item::Instruction { mnemonic, args } = item {
+            let formats: Vec<(u16, &ByteCodeFormat)> = table
+                .get(mnemonic.as_str())
+                .ok_or_else(|| error::Error::InvalidId(format!("未知的指令: {}", mnemonic)))?
+                .iter()
+                .filter(|(_, bcf)| args_fit(bcf, args))
+                .copied()
+                .collect();
+            if formats.is_empty() {
+                return Err(error::Error::InvalidId(format!(
+                    "指令 {} 的操作数不匹配: {:?}",
+                    mnemonic, args
+                )));
+            }
+            candidates.push(formats);
+        }
+    }
+
+    // 跳转距离决定跳转指令的长度，从最短的格式开始，放不下时换更长的格式，直到不再变化
+    let mut chosen = vec![0; candidates.len()];
+    let (labels, pcs) = loop {
+        let mut labels = HashMap::new();
+        let mut pcs = Vec::new();
+        let mut pc = 0;
+        let mut i = 0;
+        for item in &builder.items {
+            match item {
+                Item::Label(name) => {
+                    labels.insert(name.clone(), pc);
+                }
+                Item::Instruction { .. } => {
+                    pcs.push(pc);
+                    pc += candidates[i][chosen[i]].1.get_size();
+                    i += 1;
+                }
+            }
+        }
+
+        let mut changed = false;
+        let mut i = 0;
+        for item in &builder.items {
+            let Item::Instruction { args, .. } = item else {
+                continue;
+            };
+            let bcf = candidates[i][chosen[i]].1;
+            for (unit, arg) in operand_units(bcf).into_iter().zip(args) {
+                if let (Some(size), Arg::Label(name)) = (imm_size(unit), arg) {
+                    let offset = find_label(&labels, name)? as i64 - pcs[i] as i64;
+                    if !imm_fits(offset, size, true) {
+                        if chosen[i] + 1 >= candidates[i].len() {
+                            return Err(error::Error::MalFormed(format!(
+                                "跳转距离过大: {} -> {}",
+                                pcs[i], name
+                            )));
+                        }
+                        chosen[i] += 1;
+                        changed = true;
+                    }
+                }
+            }
+            i += 1;
+        }
+        if !changed {
+            break (labels, pcs);
+        }
+    };
+
+    let mut instructions = Vec::new();
+    let mut i = 0;
+    for item in &builder.items {
+        let Item::Instruction { args, .. } = item else {
+            continue;
+        };
+        let (opcode, bcf) = candidates[i][chosen[i]];
+        let mut args = args.iter();
+        for unit in bcf.formats() {
+            match unit {
+                FormatUnit::Opcode => instructions.push(opcode as u8),
+                FormatUnit::PrefixOpcode => instructions.extend(opcode.to_le_bytes()),
+                FormatUnit::RR => instructions.push(0),
+                FormatUnit::RRRR => instructions.extend([0, 0]),
+                FormatUnit::V4V4 | FormatUnit::Imm4Imm4 => {
+                    // 第一个操作数在低 4 位
+                    let low = nibble(args.next());
+                    let high = nibble(args.next());
+                    instructions.push((high << 4) | low);
+                }
+                FormatUnit::V8 | FormatUnit::V16 => {
+                    let Some(Arg::Reg(reg)) = args.next() else {
+                        unreachable!()
+                    };
+                    if matches!(unit, FormatUnit::V8) {
+                        instructions.push(*reg as u8);
+                    } else {
+                        instructions.extend(reg.to_le_bytes());
+                    }
+                }
+                // args_fit 不会为 32位ID 选中任何操作数
+                FormatUnit::StringID32 => unreachable!(),
+                FormatUnit::StringID | FormatUnit::MethodID | FormatUnit::LiteralID => {
+                    let arg = args.next().unwrap();
+                    let idx = match arg {
+                        Arg::Id(idx) => Some(*idx),
+                        arg => resolve(arg),
+                    }
+                    .ok_or_else(|| error::Error::InvalidId(format!("未分配索引: {:?}", arg)))?;
+                    instructions.extend(idx.to_le_bytes());
+                }
+                FormatUnit::IMM8 | FormatUnit::IMM16 | FormatUnit::IMM32 | FormatUnit::IMM64 => {
+                    let value = match args.next().unwrap() {
+                        Arg::Imm(value) => *value,
+                        Arg::Label(name) => find_label(&labels, name)? as i64 - pcs[i] as i64,
+                        _ => unreachable!(),
+                    };
+                    let size = imm_size(unit).unwrap() as usize;
+                    instructions.extend(&value.to_le_bytes()[..size]);
+                }
+            }
+        }
+        i += 1;
+    }
+
+    let mut try_blocks = Vec::new();
+    for labels_of_try in &builder.tries {
+        let start_pc = find_label(&labels, &labels_of_try.begin)? as u64;
+        let end_pc = find_label(&labels, &labels_of_try.end)? as u64;
+        let handler_pc = find_label(&labels, &labels_of_try.handler_begin)? as u64;
+        let handler_end_pc = find_label(&labels, &labels_of_try.handler_end)? as u64;
+        let catch = CatchDef {
+            type_idx: labels_of_try.type_idx,
+            handler_pc,
+            code_size: handler_end_pc.saturating_sub(handler_pc),
+        };
+        let length = end_pc.saturating_sub(start_pc);
+        // 范围相同的 TryBlock 合并为一个，拥有多个 CatchBlock
+        match try_blocks
+            .iter_mut()
+            .find(|t: &&mut TryDef| t.start_pc == start_pc && t.length == length)
+        {
+            Some(try_block) => try_block.catches.push(catch),
+            None => try_blocks.push(TryDef {
+                start_pc,
+                length,
+                catches: vec![catch],
+            }),
+        }
+    }
+
+    Ok(Assembled {
+        instructions,
+        labels,
+        try_blocks,
+    })
+}
+
+fn nibble(arg: Option<&Arg>) -> u8 {
+    match arg {
+        Some(Arg::Reg(value)) => *value as u8 & 0xf,
+        Some(Arg::Imm(value)) => *value as u8 & 0xf,
+        _ => 0,
+    }
+}
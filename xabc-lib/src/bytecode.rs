@@ -1,14 +1,18 @@
 /// 解析字节码
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
 use getset::Getters;
 use scroll::Pread;
 
 use crate::{
     code::Code,
+    error::{self, ReadContext},
+    literal::LiteralArray,
     method::{self},
     region::Region,
     string::ABCString,
+    version::{Version, ISA_API_11, ISA_API_12, ISA_API_12_SENDABLE, ISA_BASE},
 };
 
 // https://developer.huawei.com/consumer/cn/doc/harmonyos-guides-V5/arkts-bytecode-fundamentals-V5#字节码格式说明
@@ -34,6 +38,8 @@ pub enum FormatUnit {
     LiteralID,
     StringID,
     MethodID,
+    /// 32位ID，直接是字符串在文件中的偏移量，只出现在旧版本的 deprecated 指令中
+    StringID32,
 }
 
 #[derive(Debug, Clone, Getters)]
@@ -42,6 +48,8 @@ pub struct ByteCodeFormat {
     name: String,
     formats: Vec<FormatUnit>,
     instruction: Vec<u8>,
+    /// 指令出现的最低版本
+    min_version: Version,
 }
 
 impl ByteCodeFormat {
@@ -50,9 +58,16 @@ impl ByteCodeFormat {
             name,
             formats,
             instruction: vec![],
+            min_version: ISA_BASE,
         }
     }
 
+    /// 设置指令出现的最低版本
+    pub fn since(mut self, version: Version) -> Self {
+        self.min_version = version;
+        self
+    }
+
     pub fn set_instruction(&mut self, instruction: Vec<u8>) {
         self.instruction = instruction;
     }
@@ -69,6 +84,7 @@ impl ByteCodeFormat {
                 FormatUnit::LiteralID => size += 2,
                 FormatUnit::StringID => size += 2,
                 FormatUnit::MethodID => size += 2,
+                FormatUnit::StringID32 => size += 4,
                 FormatUnit::Imm4Imm4 => size += 1,
                 FormatUnit::IMM8 => size += 1,
                 FormatUnit::IMM16 => size += 2,
@@ -82,140 +98,410 @@ impl ByteCodeFormat {
         size
     }
 
+    /// 助记符，即指令名的第一部分
+    pub fn mnemonic(&self) -> &str {
+        self.name.split_whitespace().next().unwrap_or_default()
+    }
+
+    /// 是否为跳转指令，跳转指令的立即数都是有符号的分支偏移量
+    pub fn is_branch(&self) -> bool {
+        self.mnemonic().starts_with('j')
+    }
+
+    /// 从 `offset` 处解码一条指令
     pub fn parse(
         &self,
-        instructions: &Vec<u8>,
+        instructions: &[u8],
         offset: usize,
         region: &Region,
         source: &[u8],
-        literal_array_map: &HashMap<usize, String>,
-    ) -> usize {
+        literal_array_map: &HashMap<usize, LiteralArray>,
+    ) -> Result<Instruction, error::Error> {
+        let start = offset;
         let mut offset = offset;
-        let opcode_name = self.name.split_whitespace().collect::<Vec<&str>>()[0];
-        let mut strx = format!("{} ", opcode_name);
-        let mut raw = String::from("0x");
+        let mut opcode = 0;
+        let mut prefix = None;
+        let mut operands = Vec::new();
 
         for unit in &self.formats {
             match unit {
                 FormatUnit::Opcode => {
-                    let data = instructions.pread::<u8>(offset).unwrap();
+                    let data = instructions.pread::<u8>(offset).at(offset, &self.name)?;
                     offset += 1;
-                    raw += &format!("{:02X}", data);
+                    opcode = data as u16;
                 }
                 FormatUnit::PrefixOpcode => {
-                    // 大小端互换
-                    let data = instructions.pread_with::<u16>(offset, scroll::BE).unwrap();
+                    // 前缀在低位，子操作码在高位，与字节码表的键一致
+                    let data = instructions
+                        .pread_with::<u16>(offset, scroll::LE)
+                        .at(offset, &self.name)?;
                     offset += 2;
-                    raw += &format!("{:04X}", data);
+                    opcode = data;
+                    prefix = Some((data & 0xff) as u8);
                 }
 
                 FormatUnit::V4V4 => {
-                    let data = instructions.pread::<u8>(offset).unwrap();
-                    raw += &format!("{:02X}", data);
+                    let data = instructions.pread::<u8>(offset).at(offset, &self.name)?;
                     offset += 1;
                     let high_nibble = (data >> 4) & 0b1111;
                     let low_nibble = data & 0b1111;
-                    strx += &format!("v{} v{}", low_nibble, high_nibble);
+                    operands.push(Operand::Register(low_nibble as u16));
+                    operands.push(Operand::Register(high_nibble as u16));
                 }
                 FormatUnit::V8 => {
-                    let data = instructions.pread::<u8>(offset).unwrap();
-                    raw += &format!("{:02X}", data);
+                    let data = instructions.pread::<u8>(offset).at(offset, &self.name)?;
                     offset += 1;
-                    strx += &format!("v{} ", data);
+                    operands.push(Operand::Register(data as u16));
                 }
                 FormatUnit::V16 => {
-                    let data = instructions.pread::<u16>(offset).unwrap();
-                    raw += &format!("{:04X}", data);
+                    let data = instructions.pread::<u16>(offset).at(offset, &self.name)?;
                     offset += 2;
-                    strx += &format!("v{} ", data);
+                    operands.push(Operand::Register(data));
                 }
                 // NOTE: 这个是索引，不是偏移
                 FormatUnit::LiteralID => {
-                    let data = instructions.pread_with::<u16>(offset, scroll::LE).unwrap();
-                    raw += &format!("{:04X}", data);
+                    let data = instructions
+                        .pread_with::<u16>(offset, scroll::LE)
+                        .at(offset, &self.name)?;
                     offset += 2;
 
-                    let array_off = *region.get_msl_offset(data as usize);
-                    let array_off = array_off as usize;
-                    let x = literal_array_map.get(&array_off).unwrap();
-                    strx += &format!("{{ {} }}", x);
+                    let array_off = *region
+                        .get_msl_offset(data as usize)
+                        .at(offset - 2, "LiteralID")?;
+                    let value = literal_array_map
+                        .get(&(array_off as usize))
+                        .at(array_off as usize, "LiteralArray")?;
+                    operands.push(Operand::LiteralArray {
+                        idx: data,
+                        offset: array_off,
+                        value: value.clone(),
+                    });
                 }
                 FormatUnit::StringID => {
-                    let data = instructions.pread_with::<u16>(offset, scroll::LE).unwrap();
-                    raw += &format!("{:04X}", data);
+                    let data = instructions
+                        .pread_with::<u16>(offset, scroll::LE)
+                        .at(offset, &self.name)?;
                     offset += 2;
 
-                    let string_offset = region.get_msl_offset(data as usize);
-                    let x = source
+                    let string_offset = *region
+                        .get_msl_offset(data as usize)
+                        .at(offset - 2, "StringID")?;
+                    let value = source
+                        .as_ref()
+                        .pread::<ABCString>(string_offset as usize)
+                        .map_err(|e| e.rebase(string_offset as usize))?
+                        .str();
+                    operands.push(Operand::String {
+                        idx: Some(data),
+                        offset: string_offset,
+                        value,
+                    });
+                }
+                FormatUnit::StringID32 => {
+                    let string_offset = instructions
+                        .pread_with::<u32>(offset, scroll::LE)
+                        .at(offset, &self.name)?;
+                    offset += 4;
+
+                    let value = source
                         .as_ref()
-                        .pread::<ABCString>(*string_offset as usize)
-                        .unwrap()
+                        .pread::<ABCString>(string_offset as usize)
+                        .map_err(|e| e.rebase(string_offset as usize))?
                         .str();
-                    strx += &format!("\"{}\" ", x);
+                    operands.push(Operand::String {
+                        idx: None,
+                        offset: string_offset,
+                        value,
+                    });
                 }
                 FormatUnit::MethodID => {
-                    let data = instructions.pread_with::<u16>(offset, scroll::LE).unwrap();
-                    raw += &format!("{:04X}", data);
+                    let data = instructions
+                        .pread_with::<u16>(offset, scroll::LE)
+                        .at(offset, &self.name)?;
                     offset += 2;
-                    let method_offset = region.get_msl_offset(data as usize);
-
-                    let method_sign =
-                        method::get_method_sign(source, *method_offset as usize, region);
+                    let method_offset = *region
+                        .get_msl_offset(data as usize)
+                        .at(offset - 2, "MethodID")?;
 
-                    strx += &method_sign.to_string();
-                    strx += " ";
+                    let name = method::get_method_sign(source, method_offset as usize, region)?;
+                    operands.push(Operand::Method {
+                        idx: data,
+                        offset: method_offset,
+                        name,
+                    });
                 }
                 FormatUnit::Imm4Imm4 => {
-                    let data = instructions.pread::<u8>(offset).unwrap();
-                    raw += &format!("{:02X}", data);
+                    let data = instructions.pread::<u8>(offset).at(offset, &self.name)?;
                     offset += 1;
-                    strx += "Imm4Imm4";
-                    strx += &format!("+{} ", data);
+                    operands.push(Operand::Imm((data & 0b1111) as u64));
+                    operands.push(Operand::Imm(((data >> 4) & 0b1111) as u64));
+                }
+                FormatUnit::IMM8 if self.is_branch() => {
+                    let data = instructions.pread::<i8>(offset).at(offset, &self.name)?;
+                    offset += 1;
+                    operands.push(Operand::branch(start, data as i32));
+                }
+                FormatUnit::IMM16 if self.is_branch() => {
+                    let data = instructions.pread::<i16>(offset).at(offset, &self.name)?;
+                    offset += 2;
+                    operands.push(Operand::branch(start, data as i32));
+                }
+                FormatUnit::IMM32 if self.is_branch() => {
+                    let data = instructions.pread::<i32>(offset).at(offset, &self.name)?;
+                    offset += 4;
+                    operands.push(Operand::branch(start, data));
                 }
                 FormatUnit::IMM8 => {
-                    let data = instructions.pread::<u8>(offset).unwrap();
-                    raw += &format!("{:02X}", data);
+                    let data = instructions.pread::<u8>(offset).at(offset, &self.name)?;
                     offset += 1;
-                    strx += &format!("+{} ", data);
+                    operands.push(Operand::Imm(data as u64));
                 }
                 FormatUnit::IMM16 => {
-                    let data = instructions.pread::<u16>(offset).unwrap();
-                    raw += &format!("{:04X}", data);
+                    let data = instructions.pread::<u16>(offset).at(offset, &self.name)?;
                     offset += 2;
-                    strx += &format!("+{} ", data);
+                    operands.push(Operand::Imm(data as u64));
                 }
                 FormatUnit::IMM32 => {
-                    let data = instructions.pread::<u32>(offset).unwrap();
-                    raw += &format!("{:08X}", data);
+                    let data = instructions.pread::<u32>(offset).at(offset, &self.name)?;
                     offset += 4;
-                    strx += "IMM32";
-                    strx += &format!("+{} ", data);
+                    operands.push(Operand::Imm(data as u64));
                 }
                 FormatUnit::IMM64 => {
-                    let data = instructions.pread::<u64>(offset).unwrap();
-                    raw += &format!("{:16X}", data);
+                    let data = instructions.pread::<u64>(offset).at(offset, &self.name)?;
                     offset += 8;
-                    strx += "IMM64";
-                    strx += &format!("+{} ", data);
+                    operands.push(Operand::Imm(data));
                 }
                 FormatUnit::RR => {
-                    let _ = instructions.pread::<u8>(offset).unwrap();
+                    let _ = instructions.pread::<u8>(offset).at(offset, &self.name)?;
                     offset += 1;
                 }
                 FormatUnit::RRRR => {
-                    let _ = instructions.pread::<u16>(offset).unwrap();
+                    let _ = instructions.pread::<u16>(offset).at(offset, &self.name)?;
                     offset += 2;
                 }
             }
         }
 
-        // println!("{} : {} : {}", raw, self.name, strx);
-        println!("{} : {}", raw, strx);
+        Ok(Instruction {
+            offset: start,
+            opcode,
+            mnemonic: self.mnemonic().to_string(),
+            prefix,
+            operands,
+            bytes: instructions[start..offset].to_vec(),
+            diagnostic: None,
+        })
+    }
+}
+
+/// 指令的操作数
+#[derive(Debug, Clone)]
+pub enum Operand {
+    /// 寄存器
+    Register(u16),
+    /// 立即数
+    Imm(u64),
+    /// 字符串，`idx` 是 MethodStringLiteralRegionIndex 的索引，`offset` 是字符串在文件中的偏移量
+    ///
+    /// 32位ID的指令直接给出偏移量，`idx` 为 `None`
+    String {
+        idx: Option<u16>,
+        offset: u32,
+        value: String,
+    },
+    /// 方法，`name` 是方法签名
+    Method { idx: u16, offset: u32, name: String },
+    /// 字面量数组
+    LiteralArray {
+        idx: u16,
+        offset: u32,
+        value: LiteralArray,
+    },
+    /// 无法解码的字节，只出现在 `.byte` 伪指令中
+    Byte(u8),
+    /// 分支偏移量，`target` 是相对于指令起始位置计算出的绝对 pc，`label` 是目标处的标签
+    Branch {
+        offset: i32,
+        target: i64,
+        label: Option<String>,
+    },
+}
+
+impl Operand {
+    fn branch(pc: usize, offset: i32) -> Self {
+        Operand::Branch {
+            offset,
+            target: pc as i64 + offset as i64,
+            label: None,
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Register(reg) => write!(f, "v{}", reg),
+            Operand::Imm(value) => write!(f, "+{}", value),
+            Operand::String { value, .. } => write!(f, "\"{}\"", value),
+            Operand::Method { name, .. } => write!(f, "{}", name),
+            Operand::LiteralArray { value, .. } => write!(f, "{{ {} }}", value),
+            Operand::Byte(value) => write!(f, "0x{:02x}", value),
+            Operand::Branch {
+                label: Some(label), ..
+            } => write!(f, "{}", label),
+            Operand::Branch { offset, .. } => write!(f, "{:+}", offset),
+        }
+    }
+}
+
+/// 解码后的指令
+#[derive(Debug, Clone, Getters)]
+#[get = "pub"]
+pub struct Instruction {
+    /// 指令在 Code 的 instructions 中的偏移量
+    offset: usize,
+    /// 操作码，前缀指令为 `(子操作码 << 8) | 前缀`
+    opcode: u16,
+    /// 助记符
+    mnemonic: String,
+    /// 前缀，如 `callruntime`、`wide`、`throw` 等
+    prefix: Option<u8>,
+    /// 操作数，保留字段 `RR`/`RRRR` 不计算在内
+    operands: Vec<Operand>,
+    /// 指令的原始字节
+    bytes: Vec<u8>,
+    /// 无法解码的原因，只有 `.byte` 伪指令才有
+    diagnostic: Option<String>,
+}
+
+/// 无法解码的字节使用的伪指令
+pub const BYTE_MNEMONIC: &str = ".byte";
+
+impl Instruction {
+    /// 指令的大小，以字节为单位
+    pub fn size(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// 将 `offset` 处的一个字节作为数据，生成 `.byte 0xNN` 伪指令
+    fn unknown(instructions: &[u8], offset: usize, diagnostic: String) -> Instruction {
+        let byte = instructions[offset];
+        Instruction {
+            offset,
+            opcode: byte as u16,
+            mnemonic: BYTE_MNEMONIC.to_string(),
+            prefix: None,
+            operands: vec![Operand::Byte(byte)],
+            bytes: vec![byte],
+            diagnostic: Some(diagnostic),
+        }
+    }
+
+    /// 是否为无法解码的 `.byte` 伪指令
+    pub fn is_unknown(&self) -> bool {
+        self.diagnostic.is_some()
+    }
+
+    /// 跳转的目标 pc，非跳转指令返回 `None`
+    pub fn branch_target(&self) -> Option<i64> {
+        self.operands.iter().find_map(|operand| match operand {
+            Operand::Branch { target, .. } => Some(*target),
+            _ => None,
+        })
+    }
+}
+
+/// 为跳转目标分配 `label_N` 标签，按目标 pc 从小到大编号。
+///
+/// 目标不在任何指令的起始位置时（越界或落在指令中间）不分配标签。
+pub fn branch_labels(instructions: &[Instruction]) -> BTreeMap<usize, String> {
+    let starts: Vec<usize> = instructions.iter().map(|i| i.offset).collect();
+    let mut targets: Vec<usize> = instructions
+        .iter()
+        .filter_map(Instruction::branch_target)
+        .filter_map(|target| usize::try_from(target).ok())
+        .filter(|target| starts.binary_search(target).is_ok())
+        .collect();
+    targets.sort_unstable();
+    targets.dedup();
+
+    targets
+        .into_iter()
+        .enumerate()
+        .map(|(i, target)| (target, format!("label_{}", i)))
+        .collect()
+}
+
+/// 解码覆盖率
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Getters)]
+#[get = "pub"]
+pub struct Coverage {
+    /// 成功解码的字节数
+    decoded_bytes: usize,
+    /// 无法解码的字节数
+    unknown_bytes: usize,
+}
+
+impl Coverage {
+    pub fn from_instructions(instructions: &[Instruction]) -> Self {
+        let mut coverage = Coverage::default();
+        for instruction in instructions {
+            if instruction.is_unknown() {
+                coverage.unknown_bytes += instruction.size();
+            } else {
+                coverage.decoded_bytes += instruction.size();
+            }
+        }
+        coverage
+    }
+
+    pub fn merge(&mut self, other: &Coverage) {
+        self.decoded_bytes += other.decoded_bytes;
+        self.unknown_bytes += other.unknown_bytes;
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        self.decoded_bytes + self.unknown_bytes
+    }
+
+    /// 成功解码的比例，没有指令时为 1
+    pub fn ratio(&self) -> f64 {
+        if self.total_bytes() == 0 {
+            return 1.0;
+        }
+        self.decoded_bytes as f64 / self.total_bytes() as f64
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "已解码: {} 字节，未知: {} 字节，覆盖率: {:.2}%",
+            self.decoded_bytes,
+            self.unknown_bytes,
+            self.ratio() * 100.0
+        )
+    }
+}
 
-        offset
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x")?;
+        for byte in &self.bytes {
+            write!(f, "{:02X}", byte)?;
+        }
+        write!(f, " : {}", self.mnemonic)?;
+        for operand in &self.operands {
+            write!(f, " {}", operand)?;
+        }
+        Ok(())
     }
 }
 
+/// 前缀操作码：callruntime、deprecated、wide、throw
+const PREFIXES: [u8; 4] = [0xfb, 0xfc, 0xfd, 0xfe];
+
 /// 字节码解析器
 pub struct BytecodeParser {
     // 存放字节码的字节码表
@@ -228,42 +514,42 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
     let opcode_vec = vec![
         // 0x00 	NONE 	ldundefined
         (
-            0x0,
+            0x00,
             ByteCodeFormat::new("ldundefined".to_owned(), vec![FormatUnit::Opcode]),
         ),
         // 0x01 	NONE 	ldnull
         (
-            0x1,
+            0x01,
             ByteCodeFormat::new("ldnull".to_owned(), vec![FormatUnit::Opcode]),
         ),
         // 0x02 	NONE 	ldtrue
         (
-            0x2,
+            0x02,
             ByteCodeFormat::new("ldtrue".to_owned(), vec![FormatUnit::Opcode]),
         ),
         // 0x03 	NONE 	ldfalse
         (
-            0x3,
+            0x03,
             ByteCodeFormat::new("ldfalse".to_owned(), vec![FormatUnit::Opcode]),
         ),
         // 0x04 	NONE 	createemptyobject
         (
-            0x4,
+            0x04,
             ByteCodeFormat::new("createemptyobject".to_owned(), vec![FormatUnit::Opcode]),
         ),
         // 0x05 	IMM8 	createemptyarray RR
         (
-            0x5,
+            0x05,
             ByteCodeFormat::new(
-                "createemptyarray".to_owned(),
+                "createemptyarray RR".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RR],
             ),
         ),
         // 0x06 	IMM8_ID16 	createarraywithbuffer RR, @AAAA
         (
-            0x6,
+            0x06,
             ByteCodeFormat::new(
-                "createarraywithbuffer".to_owned(),
+                "createarraywithbuffer RR, @AAAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RR, FormatUnit::LiteralID],
             ),
         ),
@@ -271,7 +557,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x07,
             ByteCodeFormat::new(
-                "createobjectwithbuffer".to_owned(),
+                "createobjectwithbuffer RR, @AAAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RR, FormatUnit::LiteralID],
             ),
         ),
@@ -292,7 +578,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x09,
             ByteCodeFormat::new(
-                "newlexenv".to_owned(),
+                "newlexenv +AA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::IMM8],
             ),
         ),
@@ -378,7 +664,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         ),
         // 0x14 	IMM8_V8 	greatereq RR, vAA
         (
-            0x13,
+            0x14,
             ByteCodeFormat::new(
                 "greatereq RR, vAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RR, FormatUnit::V8],
@@ -499,18 +785,12 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         // 0x23 	NONE 	istrue
         (
             0x23,
-            ByteCodeFormat::new(
-                "istrue".to_owned(),
-                vec![FormatUnit::Opcode, FormatUnit::RR],
-            ),
+            ByteCodeFormat::new("istrue".to_owned(), vec![FormatUnit::Opcode]),
         ),
         // 0x24 	NONE 	isfalse
         (
             0x24,
-            ByteCodeFormat::new(
-                "isfalse".to_owned(),
-                vec![FormatUnit::Opcode, FormatUnit::RR],
-            ),
+            ByteCodeFormat::new("isfalse".to_owned(), vec![FormatUnit::Opcode]),
         ),
         // 0x25 	IMM8_V8 	isin RR, vAA
         (
@@ -708,7 +988,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x36,
             ByteCodeFormat::new(
-                "getnextpropname".to_owned(),
+                "getnextpropname vAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::V8],
             ),
         ),
@@ -815,7 +1095,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
             0x42,
             ByteCodeFormat::new(
                 "ldobjbyname RR, @AAAA".to_owned(),
-                vec![FormatUnit::Opcode, FormatUnit::IMM8, FormatUnit::StringID],
+                vec![FormatUnit::Opcode, FormatUnit::RR, FormatUnit::StringID],
             ),
         ),
         // 0x43 	IMM8_ID16_V8 	stobjbyname RR, @AAAA, vBB
@@ -867,7 +1147,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x48,
             ByteCodeFormat::new(
-                "stconsttoglobalrecord RRRR, @AAAA".to_owned(),
+                "sttoglobalrecord RRRR, @AAAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::StringID],
             ),
         ),
@@ -1075,7 +1355,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x62,
             ByteCodeFormat::new(
-                "ldai".to_owned(),
+                "ldai +AAAAAAAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::IMM32],
             ),
         ),
@@ -1083,7 +1363,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x63,
             ByteCodeFormat::new(
-                "fldai".to_owned(),
+                "fldai +AAAAAAAAAAAAAAAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::IMM64],
             ),
         ),
@@ -1092,7 +1372,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
             0x64,
             ByteCodeFormat::new("return".to_owned(), vec![FormatUnit::Opcode]),
         ),
-        // 0x65 	NONE 	returnundefined 		返回undefined。
+        // 0x65 	NONE 	returnundefined 	返回undefined。
         (
             0x65,
             ByteCodeFormat::new("returnundefined".to_owned(), vec![FormatUnit::Opcode]),
@@ -1106,7 +1386,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x67,
             ByteCodeFormat::new(
-                "getiterator".to_owned(),
+                "getiterator RR".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RR],
             ),
         ),
@@ -1114,31 +1394,31 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x68,
             ByteCodeFormat::new(
-                "closeiterator".to_owned(),
+                "closeiterator RR, vAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RR, FormatUnit::V8],
             ),
         ),
-        // 0x69 	NONE 	poplexenv 		跳出当前的词法环境，进入外面一层词法环境。
+        // 0x69 	NONE 	poplexenv 	跳出当前的词法环境，进入外面一层词法环境。
         (
             0x69,
             ByteCodeFormat::new("poplexenv".to_owned(), vec![FormatUnit::Opcode]),
         ),
-        // 0x6a 	NONE 	ldnan 		将nan存放到acc中。
+        // 0x6a 	NONE 	ldnan 	将nan存放到acc中。
         (
             0x6a,
             ByteCodeFormat::new("ldnan".to_owned(), vec![FormatUnit::Opcode]),
         ),
-        // 0x6b 	NONE 	ldinfinity 		将infinity存放到acc中。
+        // 0x6b 	NONE 	ldinfinity 	将infinity存放到acc中。
         (
             0x6b,
             ByteCodeFormat::new("ldinfinity".to_owned(), vec![FormatUnit::Opcode]),
         ),
-        // 0x6c 	NONE 	getunmappedargs 		将当前函数的arguments存放到acc中。
+        // 0x6c 	NONE 	getunmappedargs 	将当前函数的arguments存放到acc中。
         (
             0x6c,
             ByteCodeFormat::new("getunmappedargs".to_owned(), vec![FormatUnit::Opcode]),
         ),
-        // 0x6d 	NONE 	ldglobal 		将global对象存放到acc中。
+        // 0x6d 	NONE 	ldglobal 	将global对象存放到acc中。
         (
             0x6d,
             ByteCodeFormat::new("ldglobal".to_owned(), vec![FormatUnit::Opcode]),
@@ -1148,12 +1428,12 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
             0x6e,
             ByteCodeFormat::new("ldnewtarget".to_owned(), vec![FormatUnit::Opcode]),
         ),
-        // 0x6f 	NONE 	ldthis 		将this存放到acc中。
+        // 0x6f 	NONE 	ldthis 	将this存放到acc中。
         (
             0x6f,
             ByteCodeFormat::new("ldthis".to_owned(), vec![FormatUnit::Opcode]),
         ),
-        // 0x70 	NONE 	ldhole 		将hole存放到acc中。
+        // 0x70 	NONE 	ldhole 	将hole存放到acc中。
         (
             0x70,
             ByteCodeFormat::new("ldhole".to_owned(), vec![FormatUnit::Opcode]),
@@ -1162,11 +1442,11 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x71,
             ByteCodeFormat::new(
-                "createregexpwithliteral".to_owned(),
+                "createregexpwithliteral RR, @AAAA, +BB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RR,
-                    FormatUnit::MethodID,
+                    FormatUnit::StringID,
                     FormatUnit::IMM8,
                 ],
             ),
@@ -1175,7 +1455,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x72,
             ByteCodeFormat::new(
-                "createregexpwithliteral".to_owned(),
+                "createregexpwithliteral RRRR, @AAAA, +BB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RRRR,
@@ -1188,7 +1468,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x73,
             ByteCodeFormat::new(
-                "callrange".to_owned(),
+                "callrange RR, +AA, vBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RR,
@@ -1201,7 +1481,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x74,
             ByteCodeFormat::new(
-                "definefunc".to_owned(),
+                "definefunc RRRR, @AAAA, +BB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RRRR,
@@ -1214,7 +1494,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x75,
             ByteCodeFormat::new(
-                "defineclasswithbuffer".to_owned(),
+                "defineclasswithbuffer RRRR, @AAAA, @BBBB, +CCCC, vDD".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RRRR,
@@ -1229,7 +1509,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x76,
             ByteCodeFormat::new(
-                "gettemplateobject".to_owned(),
+                "gettemplateobject RR".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RR],
             ),
         ),
@@ -1237,7 +1517,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x77,
             ByteCodeFormat::new(
-                "setobjectwithproto".to_owned(),
+                "setobjectwithproto RR, vAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RR, FormatUnit::V8],
             ),
         ),
@@ -1245,7 +1525,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x78,
             ByteCodeFormat::new(
-                "stownbyvalue".to_owned(),
+                "stownbyvalue RR, vAA, vBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RR,
@@ -1258,7 +1538,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x79,
             ByteCodeFormat::new(
-                "stownbyindex".to_owned(),
+                "stownbyindex RR, vAA, +BBBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RR,
@@ -1271,7 +1551,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x7a,
             ByteCodeFormat::new(
-                "stownbyname".to_owned(),
+                "stownbyname RR, @AAAA, vBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RR,
@@ -1284,7 +1564,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x7b,
             ByteCodeFormat::new(
-                "getmodulenamespace".to_owned(),
+                "getmodulenamespace +AA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::IMM8],
             ),
         ),
@@ -1292,7 +1572,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x7c,
             ByteCodeFormat::new(
-                "stmodulevar".to_owned(),
+                "stmodulevar +AA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::IMM8],
             ),
         ),
@@ -1300,7 +1580,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x7d,
             ByteCodeFormat::new(
-                "ldlocalmodulevar".to_owned(),
+                "ldlocalmodulevar +AA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::IMM8],
             ),
         ),
@@ -1316,7 +1596,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x7f,
             ByteCodeFormat::new(
-                "stglobalvar".to_owned(),
+                "stglobalvar RRRR, @AAAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::StringID],
             ),
         ),
@@ -1324,7 +1604,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x80,
             ByteCodeFormat::new(
-                "createemptyarray".to_owned(),
+                "createemptyarray RRRR".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RRRR],
             ),
         ),
@@ -1332,7 +1612,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x81,
             ByteCodeFormat::new(
-                "createarraywithbuffer".to_owned(),
+                "createarraywithbuffer RRRR, @AAAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::LiteralID],
             ),
         ),
@@ -1340,7 +1620,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x82,
             ByteCodeFormat::new(
-                "createobjectwithbuffer".to_owned(),
+                "createobjectwithbuffer RRRR, @AAAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::LiteralID],
             ),
         ),
@@ -1348,7 +1628,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x83,
             ByteCodeFormat::new(
-                "newobjrange".to_owned(),
+                "newobjrange RRRR, +AA, vBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RRRR,
@@ -1361,7 +1641,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x84,
             ByteCodeFormat::new(
-                "typeof".to_owned(),
+                "typeof RRRR".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RRRR],
             ),
         ),
@@ -1369,7 +1649,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x85,
             ByteCodeFormat::new(
-                "ldobjbyvalue".to_owned(),
+                "ldobjbyvalue RRRR, vAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::V8],
             ),
         ),
@@ -1377,7 +1657,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x86,
             ByteCodeFormat::new(
-                "stobjbyvalue".to_owned(),
+                "stobjbyvalue RRRR, vAA, vBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RRRR,
@@ -1390,168 +1670,166 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0x87,
             ByteCodeFormat::new(
-                "ldsuperbyvalue".to_owned(),
+                "ldsuperbyvalue RRRR, vAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::V8],
             ),
         ),
         // 0x88 	IMM16_IMM16 	ldobjbyindex RRRR, +AAAA
-
+        (
+            0x88,
+            ByteCodeFormat::new(
+                "ldobjbyindex RRRR, +AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::IMM16],
+            ),
+        ),
         // 0x89 	IMM16_V8_IMM16 	stobjbyindex RRRR, vAA, +BBBB
+        (
+            0x89,
+            ByteCodeFormat::new(
+                "stobjbyindex RRRR, vAA, +BBBB".to_owned(),
+                vec![
+                    FormatUnit::Opcode,
+                    FormatUnit::RRRR,
+                    FormatUnit::V8,
+                    FormatUnit::IMM16,
+                ],
+            ),
+        ),
         // 0x8a 	IMM8_IMM8 	ldlexvar +AA, +BB
+        (
+            0x8a,
+            ByteCodeFormat::new(
+                "ldlexvar +AA, +BB".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM8, FormatUnit::IMM8],
+            ),
+        ),
         // 0x8b 	IMM8_IMM8 	stlexvar +AA, +BB
-        // 0x8c 	IMM16_ID16 	tryldglobalbyname RRRR, @AAAA
-        // 0x8d 	IMM16_ID16 	trystglobalbyname RRRR, @AAAA
-        // 0x8e 	IMM8_ID16_V8 	stownbynamewithnameset RR, @AAAA, vBB
-        // 0x8f 	V16_V16 	mov vAAAA, vBBBB 	A, B：寄存器索引 	将寄存器B中的内容复制到寄存器A中。
-        // 0x90 	IMM16_ID16 	ldobjbyname RRRR, @AAAA
-        // 0x91 	IMM16_ID16_V8 	stobjbyname RRRR, @AAAA, vBB
-        // 0x92 	IMM16_ID16 	ldsuperbyname RRRR, @AAAA
-        // 0x93 	IMM16_ID16 	ldthisbyname RRRR, @AAAA
-        // 0x94 	IMM16_ID16 	stthisbyname RRRR, @AAAA
-        // 0x95 	IMM16 	ldthisbyvalue RRRR
-        // 0x96 	IMM16_V8 	stthisbyvalue RRRR, vAA
-        // 0x97 	V8 	asyncgeneratorreject vAA
-        // 0x98 	IMM32 	jmp +AAAAAAAA 	A：有符号的分支偏移量 	无条件跳转到分支A。
-        // 0x99 	IMM8_V8_V8 	stownbyvaluewithnameset RR, vAA, vBB
-        // 0x9a 	IMM32 	jeqz +AAAAAAAA
-        // 0x9b 	IMM16 	jnez +AAAA
-        // 0x9c 	IMM32 	jnez +AAAAAAAA
-        // 0x9d 	IMM16 	jstricteqz +AAAA
-        // 0x9e 	IMM16 	jnstricteqz +AAAA
-        // 0x9f 	IMM16 	jeqnull +AAAA
-        // 0xa0 	IMM16 	jnenull +AAAA
-        // 0xa1 	IMM16 	jstricteqnull +AAAA
-        // 0xa2 	IMM16 	jnstricteqnull +AAAA
-        // 0xa3 	IMM16 	jequndefined +AAAA
-        // 0xa4 	IMM16 	jneundefined +AAAA
-        // 0xa5 	IMM16 	jstrictequndefined +AAAA
-        // 0xa6 	IMM16 	jnstrictequndefined +AAAA
-        // 0xa7 	V8_IMM16 	jeq vAA, +BBBB
-        // 0xa8 	V8_IMM16 	jne vAA, +BBBB
-        // 0xa9 	V8_IMM16 	jstricteq vAA, +BBBB
-        // 0xaa 	V8_IMM16 	jnstricteq vAA, +BBBB
-        // 0xab 	IMM16 	getiterator RRRR
-        // 0xac 	IMM16_V8 	closeiterator RRRR, vAA
-        // 0xad 	NONE 	ldsymbol 		加载Symbol对象到acc中。
         (
-            0xad,
-            ByteCodeFormat::new("ldsymbol".to_owned(), vec![FormatUnit::Opcode]),
+            0x8b,
+            ByteCodeFormat::new(
+                "stlexvar +AA, +BB".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM8, FormatUnit::IMM8],
+            ),
         ),
-        // 0xae 	NONE 	asyncfunctionenter 		创建一个异步函数对象，并将这个对象存放到acc中。
+        // 0x8c 	IMM16_ID16 	tryldglobalbyname RRRR, @AAAA
         (
-            0xae,
-            ByteCodeFormat::new("asyncfunctionenter".to_owned(), vec![FormatUnit::Opcode]),
+            0x8c,
+            ByteCodeFormat::new(
+                "tryldglobalbyname RRRR, @AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::StringID],
+            ),
         ),
-        // 0xaf 	NONE 	ldfunction 		将当前的函数对象加载到acc中。
+        // 0x8d 	IMM16_ID16 	trystglobalbyname RRRR, @AAAA
         (
-            0xaf,
-            ByteCodeFormat::new("ldfunction".to_owned(), vec![FormatUnit::Opcode]),
+            0x8d,
+            ByteCodeFormat::new(
+                "trystglobalbyname RRRR, @AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::StringID],
+            ),
         ),
-        // 0xb0 	NONE 	debugger 		调试时用于暂停执行。
+        // 0x8e 	IMM8_ID16_V8 	stownbynamewithnameset RR, @AAAA, vBB
         (
-            0xb0,
-            ByteCodeFormat::new("debugger".to_owned(), vec![FormatUnit::Opcode]),
+            0x8e,
+            ByteCodeFormat::new(
+                "stownbynamewithnameset RR, @AAAA, vBB".to_owned(),
+                vec![
+                    FormatUnit::Opcode,
+                    FormatUnit::RR,
+                    FormatUnit::StringID,
+                    FormatUnit::V8,
+                ],
+            ),
         ),
-        // 0xb1 	V8 	creategeneratorobj vAA
+        // 0x8f 	V16_V16 	mov vAAAA, vBBBB 	A, B：寄存器索引 	将寄存器B中的内容复制到寄存器A中。
         (
-            0xb1,
+            0x8f,
             ByteCodeFormat::new(
-                "creategeneratorobj vAA".to_owned(),
-                vec![FormatUnit::Opcode, FormatUnit::V8],
+                "mov vAAAA, vBBBB".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::V16, FormatUnit::V16],
             ),
         ),
-        // 0xb2 	V8_V8 	createiterresultobj vAA, vBB
+        // 0x90 	IMM16_ID16 	ldobjbyname RRRR, @AAAA
         (
-            0xb2,
+            0x90,
             ByteCodeFormat::new(
-                "createiterresultobj vAA, vBB".to_owned(),
-                vec![FormatUnit::Opcode, FormatUnit::V8, FormatUnit::V8],
+                "ldobjbyname RRRR, @AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::StringID],
             ),
         ),
-        // 0xb3 	IMM8_V8_V8 	createobjectwithexcludedkeys +AA, vBB, vCC
+        // 0x91 	IMM16_ID16_V8 	stobjbyname RRRR, @AAAA, vBB
         (
-            0xb3,
+            0x91,
             ByteCodeFormat::new(
-                "createobjectwithexcludedkeys +AA, vBB, vCC".to_owned(),
+                "stobjbyname RRRR, @AAAA, vBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
-                    FormatUnit::IMM8,
-                    FormatUnit::V8,
+                    FormatUnit::RRRR,
+                    FormatUnit::StringID,
                     FormatUnit::V8,
                 ],
             ),
         ),
-        // 0xb4 	IMM8_V8 	newobjapply RR, vAA
+        // 0x92 	IMM16_ID16 	ldsuperbyname RRRR, @AAAA
         (
-            0xb4,
+            0x92,
             ByteCodeFormat::new(
-                "newobjapply RR, vAA".to_owned(),
-                vec![FormatUnit::Opcode, FormatUnit::RR, FormatUnit::V8],
+                "ldsuperbyname RRRR, @AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::StringID],
             ),
         ),
-        // 0xb5 	IMM16_V8 	newobjapply RRRR, vAA
+        // 0x93 	IMM16_ID16 	ldthisbyname RRRR, @AAAA
         (
-            0xb5,
+            0x93,
             ByteCodeFormat::new(
-                "newobjapply RRRR, vAA".to_owned(),
-                vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::V8],
+                "ldthisbyname RRRR, @AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::StringID],
             ),
         ),
-        // 0xb6 	IMM8_ID16 	newlexenvwithname +AA, @BBBB
+        // 0x94 	IMM16_ID16 	stthisbyname RRRR, @AAAA
         (
-            0xb6,
+            0x94,
             ByteCodeFormat::new(
-                "newlexenvwithname".to_owned(),
-                vec![FormatUnit::Opcode, FormatUnit::IMM8, FormatUnit::LiteralID],
+                "stthisbyname RRRR, @AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::StringID],
             ),
         ),
-        // 0xb7 	V8 	createasyncgeneratorobj vAA
+        // 0x95 	IMM16 	ldthisbyvalue RRRR
         (
-            0xb7,
+            0x95,
             ByteCodeFormat::new(
-                "createasyncgeneratorobj vAA".to_owned(),
-                vec![FormatUnit::Opcode, FormatUnit::V8],
+                "ldthisbyvalue RRRR".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::RRRR],
             ),
         ),
-        // 0xb8 	V8_V8_V8 	asyncgeneratorresolve vAA, vBB, vCC
+        // 0x96 	IMM16_V8 	stthisbyvalue RRRR, vAA
         (
-            0xb8,
+            0x96,
             ByteCodeFormat::new(
-                "asyncgeneratorresolve".to_owned(),
-                vec![
-                    FormatUnit::Opcode,
-                    FormatUnit::V8,
-                    FormatUnit::V8,
-                    FormatUnit::V8,
-                ],
+                "stthisbyvalue RRRR, vAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::V8],
             ),
         ),
-        // 0xb9 	IMM8_V8 	supercallspread RR, vAA
+        // 0x97 	V8 	asyncgeneratorreject vAA
         (
-            0xb9,
+            0x97,
             ByteCodeFormat::new(
-                "supercallspread RR, vAA".to_owned(),
-                vec![FormatUnit::Opcode, FormatUnit::RR, FormatUnit::V8],
+                "asyncgeneratorreject vAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::V8],
             ),
         ),
-        // 0xba 	IMM8_V8_V8 	applyspread RR, vAA, vBB
+        // 0x98 	IMM32 	jmp +AAAAAAAA 	A：有符号的分支偏移量 	无条件跳转到分支A。
         (
-            0xba,
+            0x98,
             ByteCodeFormat::new(
-                "applyspread RR, vAA, vBB".to_owned(),
-                vec![
-                    FormatUnit::Opcode,
-                    FormatUnit::RR,
-                    FormatUnit::V8,
-                    FormatUnit::V8,
-                ],
+                "jmp +AAAAAAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM32],
             ),
         ),
-        // 0xba 	IMM8_V8_V8 	apply RR, vAA, vBB
+        // 0x99 	IMM8_V8_V8 	stownbyvaluewithnameset RR, vAA, vBB
         (
-            0xba,
+            0x99,
             ByteCodeFormat::new(
-                "apply RR, vAA, vBB".to_owned(),
+                "stownbyvaluewithnameset RR, vAA, vBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RR,
@@ -1560,36 +1838,303 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
                 ],
             ),
         ),
-        // 0xbb 	IMM8_IMM8_V8 	supercallarrowrange RR, +AA, vBB
+        // 0x9a 	IMM32 	jeqz +AAAAAAAA
         (
-            0xbb,
+            0x9a,
             ByteCodeFormat::new(
-                "supercallarrowrange".to_owned(),
-                vec![
-                    FormatUnit::Opcode,
-                    FormatUnit::RR,
-                    FormatUnit::IMM8,
-                    FormatUnit::V8,
-                ],
+                "jeqz +AAAAAAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM32],
             ),
         ),
-        // 0xbc 	V8_V8_V8_V8 	definegettersetterbyvalue vAA, vBB, vCC, vDD
+        // 0x9b 	IMM16 	jnez +AAAA
         (
-            0xbc,
+            0x9b,
             ByteCodeFormat::new(
-                "definegettersetterbyvalue".to_owned(),
-                vec![
-                    FormatUnit::Opcode,
-                    FormatUnit::V8,
-                    FormatUnit::V8,
-                    FormatUnit::V8,
-                    FormatUnit::V8,
-                ],
+                "jnez +AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM16],
             ),
         ),
-        // 0xbd 	NONE 	dynamicimport 	默认入参：acc：值 	使用acc中的值作为参数，执行ImportCalls，并把结果存放到acc中。
+        // 0x9c 	IMM32 	jnez +AAAAAAAA
         (
-            0xbd,
+            0x9c,
+            ByteCodeFormat::new(
+                "jnez +AAAAAAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM32],
+            ),
+        ),
+        // 0x9d 	IMM16 	jstricteqz +AAAA
+        (
+            0x9d,
+            ByteCodeFormat::new(
+                "jstricteqz +AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM16],
+            ),
+        ),
+        // 0x9e 	IMM16 	jnstricteqz +AAAA
+        (
+            0x9e,
+            ByteCodeFormat::new(
+                "jnstricteqz +AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM16],
+            ),
+        ),
+        // 0x9f 	IMM16 	jeqnull +AAAA
+        (
+            0x9f,
+            ByteCodeFormat::new(
+                "jeqnull +AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM16],
+            ),
+        ),
+        // 0xa0 	IMM16 	jnenull +AAAA
+        (
+            0xa0,
+            ByteCodeFormat::new(
+                "jnenull +AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM16],
+            ),
+        ),
+        // 0xa1 	IMM16 	jstricteqnull +AAAA
+        (
+            0xa1,
+            ByteCodeFormat::new(
+                "jstricteqnull +AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM16],
+            ),
+        ),
+        // 0xa2 	IMM16 	jnstricteqnull +AAAA
+        (
+            0xa2,
+            ByteCodeFormat::new(
+                "jnstricteqnull +AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM16],
+            ),
+        ),
+        // 0xa3 	IMM16 	jequndefined +AAAA
+        (
+            0xa3,
+            ByteCodeFormat::new(
+                "jequndefined +AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM16],
+            ),
+        ),
+        // 0xa4 	IMM16 	jneundefined +AAAA
+        (
+            0xa4,
+            ByteCodeFormat::new(
+                "jneundefined +AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM16],
+            ),
+        ),
+        // 0xa5 	IMM16 	jstrictequndefined +AAAA
+        (
+            0xa5,
+            ByteCodeFormat::new(
+                "jstrictequndefined +AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM16],
+            ),
+        ),
+        // 0xa6 	IMM16 	jnstrictequndefined +AAAA
+        (
+            0xa6,
+            ByteCodeFormat::new(
+                "jnstrictequndefined +AAAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM16],
+            ),
+        ),
+        // 0xa7 	V8_IMM16 	jeq vAA, +BBBB
+        (
+            0xa7,
+            ByteCodeFormat::new(
+                "jeq vAA, +BBBB".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::V8, FormatUnit::IMM16],
+            ),
+        ),
+        // 0xa8 	V8_IMM16 	jne vAA, +BBBB
+        (
+            0xa8,
+            ByteCodeFormat::new(
+                "jne vAA, +BBBB".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::V8, FormatUnit::IMM16],
+            ),
+        ),
+        // 0xa9 	V8_IMM16 	jstricteq vAA, +BBBB
+        (
+            0xa9,
+            ByteCodeFormat::new(
+                "jstricteq vAA, +BBBB".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::V8, FormatUnit::IMM16],
+            ),
+        ),
+        // 0xaa 	V8_IMM16 	jnstricteq vAA, +BBBB
+        (
+            0xaa,
+            ByteCodeFormat::new(
+                "jnstricteq vAA, +BBBB".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::V8, FormatUnit::IMM16],
+            ),
+        ),
+        // 0xab 	IMM16 	getiterator RRRR
+        (
+            0xab,
+            ByteCodeFormat::new(
+                "getiterator RRRR".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::RRRR],
+            ),
+        ),
+        // 0xac 	IMM16_V8 	closeiterator RRRR, vAA
+        (
+            0xac,
+            ByteCodeFormat::new(
+                "closeiterator RRRR, vAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::V8],
+            ),
+        ),
+        // 0xad 	NONE 	ldsymbol 	加载Symbol对象到acc中。
+        (
+            0xad,
+            ByteCodeFormat::new("ldsymbol".to_owned(), vec![FormatUnit::Opcode]),
+        ),
+        // 0xae 	NONE 	asyncfunctionenter 	创建一个异步函数对象，并将这个对象存放到acc中。
+        (
+            0xae,
+            ByteCodeFormat::new("asyncfunctionenter".to_owned(), vec![FormatUnit::Opcode]),
+        ),
+        // 0xaf 	NONE 	ldfunction 	将当前的函数对象加载到acc中。
+        (
+            0xaf,
+            ByteCodeFormat::new("ldfunction".to_owned(), vec![FormatUnit::Opcode]),
+        ),
+        // 0xb0 	NONE 	debugger 	调试时用于暂停执行。
+        (
+            0xb0,
+            ByteCodeFormat::new("debugger".to_owned(), vec![FormatUnit::Opcode]),
+        ),
+        // 0xb1 	V8 	creategeneratorobj vAA
+        (
+            0xb1,
+            ByteCodeFormat::new(
+                "creategeneratorobj vAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::V8],
+            ),
+        ),
+        // 0xb2 	V8_V8 	createiterresultobj vAA, vBB
+        (
+            0xb2,
+            ByteCodeFormat::new(
+                "createiterresultobj vAA, vBB".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::V8, FormatUnit::V8],
+            ),
+        ),
+        // 0xb3 	IMM8_V8_V8 	createobjectwithexcludedkeys +AA, vBB, vCC
+        (
+            0xb3,
+            ByteCodeFormat::new(
+                "createobjectwithexcludedkeys +AA, vBB, vCC".to_owned(),
+                vec![
+                    FormatUnit::Opcode,
+                    FormatUnit::IMM8,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                ],
+            ),
+        ),
+        // 0xb4 	IMM8_V8 	newobjapply RR, vAA
+        (
+            0xb4,
+            ByteCodeFormat::new(
+                "newobjapply RR, vAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::RR, FormatUnit::V8],
+            ),
+        ),
+        // 0xb5 	IMM16_V8 	newobjapply RRRR, vAA
+        (
+            0xb5,
+            ByteCodeFormat::new(
+                "newobjapply RRRR, vAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::RRRR, FormatUnit::V8],
+            ),
+        ),
+        // 0xb6 	IMM8_ID16 	newlexenvwithname +AA, @BBBB
+        (
+            0xb6,
+            ByteCodeFormat::new(
+                "newlexenvwithname +AA, @BBBB".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::IMM8, FormatUnit::LiteralID],
+            ),
+        ),
+        // 0xb7 	V8 	createasyncgeneratorobj vAA
+        (
+            0xb7,
+            ByteCodeFormat::new(
+                "createasyncgeneratorobj vAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::V8],
+            ),
+        ),
+        // 0xb8 	V8_V8_V8 	asyncgeneratorresolve vAA, vBB, vCC
+        (
+            0xb8,
+            ByteCodeFormat::new(
+                "asyncgeneratorresolve vAA, vBB, vCC".to_owned(),
+                vec![
+                    FormatUnit::Opcode,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                ],
+            ),
+        ),
+        // 0xb9 	IMM8_V8 	supercallspread RR, vAA
+        (
+            0xb9,
+            ByteCodeFormat::new(
+                "supercallspread RR, vAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::RR, FormatUnit::V8],
+            ),
+        ),
+        // 0xba 	IMM8_V8_V8 	apply RR, vAA, vBB
+        (
+            0xba,
+            ByteCodeFormat::new(
+                "apply RR, vAA, vBB".to_owned(),
+                vec![
+                    FormatUnit::Opcode,
+                    FormatUnit::RR,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                ],
+            ),
+        ),
+        // 0xbb 	IMM8_IMM8_V8 	supercallarrowrange RR, +AA, vBB
+        (
+            0xbb,
+            ByteCodeFormat::new(
+                "supercallarrowrange RR, +AA, vBB".to_owned(),
+                vec![
+                    FormatUnit::Opcode,
+                    FormatUnit::RR,
+                    FormatUnit::IMM8,
+                    FormatUnit::V8,
+                ],
+            ),
+        ),
+        // 0xbc 	V8_V8_V8_V8 	definegettersetterbyvalue vAA, vBB, vCC, vDD
+        (
+            0xbc,
+            ByteCodeFormat::new(
+                "definegettersetterbyvalue vAA, vBB, vCC, vDD".to_owned(),
+                vec![
+                    FormatUnit::Opcode,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                ],
+            ),
+        ),
+        // 0xbd 	NONE 	dynamicimport 	默认入参：acc：值 	使用acc中的值作为参数，执行ImportCalls，并把结果存放到acc中。
+        (
+            0xbd,
             ByteCodeFormat::new("dynamicimport".to_owned(), vec![FormatUnit::Opcode]),
         ),
         // 0xbe 	IMM16_ID16_IMM8 	definemethod RRRR, @AAAA, +BB
@@ -1691,7 +2236,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
                 "stsuperbyvalue RR, vAA, vBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
-                    FormatUnit::IMM8,
+                    FormatUnit::RR,
                     FormatUnit::V8,
                     FormatUnit::V8,
                 ],
@@ -1739,18 +2284,24 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         // 0xcd 	V8 	asyncfunctionresolve vAA
         (
             0xcd,
-            ByteCodeFormat::new("asyncfunctionresolve".to_owned(), vec![FormatUnit::Opcode]),
+            ByteCodeFormat::new(
+                "asyncfunctionresolve vAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::V8],
+            ),
         ),
         // 0xce 	V8 	asyncfunctionreject vAA
         (
             0xce,
-            ByteCodeFormat::new("asyncfunctionreject".to_owned(), vec![FormatUnit::Opcode]),
+            ByteCodeFormat::new(
+                "asyncfunctionreject vAA".to_owned(),
+                vec![FormatUnit::Opcode, FormatUnit::V8],
+            ),
         ),
         // 0xcf 	IMM8 	copyrestargs +AA 	A：形参列表中剩余参数所在的位次 	复制剩余参数，并将复制出的参数数组副本存放到acc中。
         (
             0xcf,
             ByteCodeFormat::new(
-                "copyrestargs".to_owned(),
+                "copyrestargs +AA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::IMM8],
             ),
         ),
@@ -1758,7 +2309,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0xd0,
             ByteCodeFormat::new(
-                "stsuperbyname".to_owned(),
+                "stsuperbyname RR, @AAAA, vBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RR,
@@ -1771,7 +2322,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0xd1,
             ByteCodeFormat::new(
-                "stsuperbyname".to_owned(),
+                "stsuperbyname RRRR, @AAAA, vBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RRRR,
@@ -1784,7 +2335,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0xd2,
             ByteCodeFormat::new(
-                "stownbyvaluewithnameset".to_owned(),
+                "stownbyvaluewithnameset RRRR, vAA, vBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RRRR,
@@ -1797,7 +2348,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0xd3,
             ByteCodeFormat::new(
-                "ldbigint".to_owned(),
+                "ldbigint @AAAA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::StringID],
             ),
         ),
@@ -1805,7 +2356,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0xd4,
             ByteCodeFormat::new(
-                "stownbynamewithnameset".to_owned(),
+                "stownbynamewithnameset RRRR, @AAAA, vBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RRRR,
@@ -1814,7 +2365,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
                 ],
             ),
         ),
-        // 0xd5 	NONE 	nop 		无操作。
+        // 0xd5 	NONE 	nop 	无操作。
         (
             0xd5,
             ByteCodeFormat::new("nop".to_owned(), vec![FormatUnit::Opcode]),
@@ -1823,7 +2374,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0xd6,
             ByteCodeFormat::new(
-                "setgeneratorstate".to_owned(),
+                "setgeneratorstate +AA".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::IMM8],
             ),
         ),
@@ -1831,7 +2382,7 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0xd7,
             ByteCodeFormat::new(
-                "getasynciterator".to_owned(),
+                "getasynciterator RR".to_owned(),
                 vec![FormatUnit::Opcode, FormatUnit::RR],
             ),
         ),
@@ -1839,20 +2390,21 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
         (
             0xd8,
             ByteCodeFormat::new(
-                "ldprivateproperty".to_owned(),
+                "ldprivateproperty RR, +AAAA, +BBBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RR,
                     FormatUnit::IMM16,
                     FormatUnit::IMM16,
                 ],
-            ),
+            )
+            .since(ISA_API_11),
         ),
         // 0xd9 	IMM8_IMM16_IMM16_V8 	stprivateproperty RR, +AAAA, +BBBB, vCC
         (
             0xd9,
             ByteCodeFormat::new(
-                "stprivateproperty".to_owned(),
+                "stprivateproperty RR, +AAAA, +BBBB, vCC".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RR,
@@ -1860,319 +2412,846 @@ fn init_opcode_map() -> HashMap<u16, ByteCodeFormat> {
                     FormatUnit::IMM16,
                     FormatUnit::V8,
                 ],
-            ),
+            )
+            .since(ISA_API_11),
         ),
         // 0xda 	IMM8_IMM16_IMM16 	testin RR, +AAAA, +BBBB
         (
             0xda,
             ByteCodeFormat::new(
-                "testin".to_owned(),
+                "testin RR, +AAAA, +BBBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RR,
                     FormatUnit::IMM16,
                     FormatUnit::IMM16,
                 ],
-            ),
+            )
+            .since(ISA_API_11),
         ),
         // 0xdb 	IMM8_ID16_V8 	definefieldbyname RR, @AAAA, vBB
         (
             0xdb,
             ByteCodeFormat::new(
-                "definefieldbyname".to_owned(),
+                "definefieldbyname RR, @AAAA, vBB".to_owned(),
                 vec![
                     FormatUnit::Opcode,
                     FormatUnit::RR,
                     FormatUnit::StringID,
                     FormatUnit::V8,
                 ],
-            ),
-        ),
-        // 0xfb 	PREF_NONE 	callruntime.notifyconcurrentresult 	默认入参：acc：并发函数的返回值
-        (
-            0xfb,
-            ByteCodeFormat::new(
-                "callruntime.notifyconcurrentresult".to_owned(),
-                vec![FormatUnit::PrefixOpcode],
-            ),
+            )
+            .since(ISA_API_11),
         ),
-        // 0xfd 	PREF_IMM16_V8_V8 	wide.createobjectwithexcludedkeys +AAAA, vBB, vCC
+        // 0xdc 	IMM8_ID16_V8 	definepropertybyname RR, @AAAA, vBB
         (
-            0xfd,
+            0xdc,
             ByteCodeFormat::new(
-                "wide.createobjectwithexcludedkeys".to_owned(),
+                "definepropertybyname RR, @AAAA, vBB".to_owned(),
                 vec![
-                    FormatUnit::PrefixOpcode,
-                    FormatUnit::IMM16,
+                    FormatUnit::Opcode,
+                    FormatUnit::RR,
+                    FormatUnit::StringID,
+                    FormatUnit::V8,
+                ],
+            )
+            .since(ISA_API_12),
+        ),
+    ];
+
+    let mut map = HashMap::new();
+    for team in &opcode_vec {
+        map.insert(team.0, team.1.clone());
+    }
+    map
+}
+
+fn init_prefix_opcode_map() -> HashMap<u16, ByteCodeFormat> {
+    let prefix_opcode_vec = vec![
+        // 0xfb 	PREF_NONE 	callruntime.notifyconcurrentresult
+        (
+            0xfb,
+            ByteCodeFormat::new(
+                "callruntime.notifyconcurrentresult".to_owned(),
+                vec![FormatUnit::PrefixOpcode],
+            )
+            .since(ISA_API_11),
+        ),
+        // 0x01fb 	PREF_IMM8_V8_V8 	callruntime.definefieldbyvalue RR, vAA, vBB
+        (
+            0x01fb,
+            ByteCodeFormat::new(
+                "callruntime.definefieldbyvalue RR, vAA, vBB".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::RR,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                ],
+            )
+            .since(ISA_API_11),
+        ),
+        // 0x02fb 	PREF_IMM8_IMM32_V8 	callruntime.definefieldbyindex RR, +AAAAAAAA, vBB
+        (
+            0x02fb,
+            ByteCodeFormat::new(
+                "callruntime.definefieldbyindex RR, +AAAAAAAA, vBB".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::RR,
+                    FormatUnit::IMM32,
+                    FormatUnit::V8,
+                ],
+            )
+            .since(ISA_API_11),
+        ),
+        // 0x03fb 	PREF_NONE 	callruntime.topropertykey
+        (
+            0x03fb,
+            ByteCodeFormat::new(
+                "callruntime.topropertykey".to_owned(),
+                vec![FormatUnit::PrefixOpcode],
+            )
+            .since(ISA_API_11),
+        ),
+        // 0x04fb 	PREF_IMM16_ID16 	callruntime.createprivateproperty +AAAA, @BBBB
+        (
+            0x04fb,
+            ByteCodeFormat::new(
+                "callruntime.createprivateproperty +AAAA, @BBBB".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::IMM16,
+                    FormatUnit::LiteralID,
+                ],
+            )
+            .since(ISA_API_11),
+        ),
+        // 0x05fb 	PREF_IMM8_IMM16_IMM16_V8 	callruntime.defineprivateproperty RR, +AAAA, +BBBB, vCC
+        (
+            0x05fb,
+            ByteCodeFormat::new(
+                "callruntime.defineprivateproperty RR, +AAAA, +BBBB, vCC".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::RR,
+                    FormatUnit::IMM16,
+                    FormatUnit::IMM16,
+                    FormatUnit::V8,
+                ],
+            )
+            .since(ISA_API_11),
+        ),
+        // 0x06fb 	PREF_IMM8_V8 	callruntime.callinit RR, vAA
+        (
+            0x06fb,
+            ByteCodeFormat::new(
+                "callruntime.callinit RR, vAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::RR, FormatUnit::V8],
+            )
+            .since(ISA_API_11),
+        ),
+        // 0x07fb 	PREF_IMM16_ID16_ID16_IMM16_V8 	callruntime.definesendableclass RRRR, @AAAA, @BBBB, +CCCC, vDD
+        (
+            0x07fb,
+            ByteCodeFormat::new(
+                "callruntime.definesendableclass RRRR, @AAAA, @BBBB, +CCCC, vDD".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::RRRR,
+                    FormatUnit::MethodID,
+                    FormatUnit::LiteralID,
+                    FormatUnit::IMM16,
+                    FormatUnit::V8,
+                ],
+            )
+            .since(ISA_API_12),
+        ),
+        // 0x08fb 	PREF_IMM16 	callruntime.ldsendableclass +AAAA
+        (
+            0x08fb,
+            ByteCodeFormat::new(
+                "callruntime.ldsendableclass +AAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16],
+            )
+            .since(ISA_API_12),
+        ),
+        // 0x09fb 	PREF_IMM8 	callruntime.ldsendableexternalmodulevar +AA
+        (
+            0x09fb,
+            ByteCodeFormat::new(
+                "callruntime.ldsendableexternalmodulevar +AA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM8],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x0afb 	PREF_IMM16 	callruntime.wideldsendableexternalmodulevar +AAAA
+        (
+            0x0afb,
+            ByteCodeFormat::new(
+                "callruntime.wideldsendableexternalmodulevar +AAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x0bfb 	PREF_IMM8 	callruntime.newsendableenv +AA
+        (
+            0x0bfb,
+            ByteCodeFormat::new(
+                "callruntime.newsendableenv +AA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM8],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x0cfb 	PREF_IMM16 	callruntime.widenewsendableenv +AAAA
+        (
+            0x0cfb,
+            ByteCodeFormat::new(
+                "callruntime.widenewsendableenv +AAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x0dfb 	PREF_IMM4_IMM4 	callruntime.stsendablevar +A, +B
+        (
+            0x0dfb,
+            ByteCodeFormat::new(
+                "callruntime.stsendablevar +A, +B".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::Imm4Imm4],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x0efb 	PREF_IMM8_IMM8 	callruntime.stsendablevar +AA, +BB
+        (
+            0x0efb,
+            ByteCodeFormat::new(
+                "callruntime.stsendablevar +AA, +BB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM8, FormatUnit::IMM8],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x0ffb 	PREF_IMM16_IMM16 	callruntime.widestsendablevar +AAAA, +BBBB
+        (
+            0x0ffb,
+            ByteCodeFormat::new(
+                "callruntime.widestsendablevar +AAAA, +BBBB".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::IMM16,
+                    FormatUnit::IMM16,
+                ],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x10fb 	PREF_IMM4_IMM4 	callruntime.ldsendablevar +A, +B
+        (
+            0x10fb,
+            ByteCodeFormat::new(
+                "callruntime.ldsendablevar +A, +B".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::Imm4Imm4],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x11fb 	PREF_IMM8_IMM8 	callruntime.ldsendablevar +AA, +BB
+        (
+            0x11fb,
+            ByteCodeFormat::new(
+                "callruntime.ldsendablevar +AA, +BB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM8, FormatUnit::IMM8],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x12fb 	PREF_IMM16_IMM16 	callruntime.wideldsendablevar +AAAA, +BBBB
+        (
+            0x12fb,
+            ByteCodeFormat::new(
+                "callruntime.wideldsendablevar +AAAA, +BBBB".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::IMM16,
+                    FormatUnit::IMM16,
+                ],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x13fb 	PREF_IMM8 	callruntime.istrue RR
+        (
+            0x13fb,
+            ByteCodeFormat::new(
+                "callruntime.istrue RR".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::RR],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x14fb 	PREF_IMM8 	callruntime.isfalse RR
+        (
+            0x14fb,
+            ByteCodeFormat::new(
+                "callruntime.isfalse RR".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::RR],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x15fb 	PREF_IMM8 	callruntime.ldlazymodulevar +AA
+        (
+            0x15fb,
+            ByteCodeFormat::new(
+                "callruntime.ldlazymodulevar +AA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM8],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x16fb 	PREF_IMM16 	callruntime.wideldlazymodulevar +AAAA
+        (
+            0x16fb,
+            ByteCodeFormat::new(
+                "callruntime.wideldlazymodulevar +AAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x17fb 	PREF_IMM8 	callruntime.ldlazysendablemodulevar +AA
+        (
+            0x17fb,
+            ByteCodeFormat::new(
+                "callruntime.ldlazysendablemodulevar +AA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM8],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0x18fb 	PREF_IMM16 	callruntime.wideldlazysendablemodulevar +AAAA
+        (
+            0x18fb,
+            ByteCodeFormat::new(
+                "callruntime.wideldlazysendablemodulevar +AAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16],
+            )
+            .since(ISA_API_12_SENDABLE),
+        ),
+        // 0xfc 	PREF_NONE 	deprecated.ldlexenv
+        (
+            0xfc,
+            ByteCodeFormat::new(
+                "deprecated.ldlexenv".to_owned(),
+                vec![FormatUnit::PrefixOpcode],
+            ),
+        ),
+        // 0x01fc 	PREF_NONE 	deprecated.poplexenv
+        (
+            0x01fc,
+            ByteCodeFormat::new(
+                "deprecated.poplexenv".to_owned(),
+                vec![FormatUnit::PrefixOpcode],
+            ),
+        ),
+        // 0x02fc 	PREF_V8_V8 	deprecated.getiteratornext vAA, vBB
+        (
+            0x02fc,
+            ByteCodeFormat::new(
+                "deprecated.getiteratornext vAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8, FormatUnit::V8],
+            ),
+        ),
+        // 0x03fc 	PREF_IMM16 	deprecated.createarraywithbuffer +AAAA
+        (
+            0x03fc,
+            ByteCodeFormat::new(
+                "deprecated.createarraywithbuffer +AAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16],
+            ),
+        ),
+        // 0x04fc 	PREF_IMM16 	deprecated.createobjectwithbuffer +AAAA
+        (
+            0x04fc,
+            ByteCodeFormat::new(
+                "deprecated.createobjectwithbuffer +AAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16],
+            ),
+        ),
+        // 0x05fc 	PREF_V8 	deprecated.tonumber vAA
+        (
+            0x05fc,
+            ByteCodeFormat::new(
+                "deprecated.tonumber vAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8],
+            ),
+        ),
+        // 0x06fc 	PREF_V8 	deprecated.tonumeric vAA
+        (
+            0x06fc,
+            ByteCodeFormat::new(
+                "deprecated.tonumeric vAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8],
+            ),
+        ),
+        // 0x07fc 	PREF_V8 	deprecated.neg vAA
+        (
+            0x07fc,
+            ByteCodeFormat::new(
+                "deprecated.neg vAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8],
+            ),
+        ),
+        // 0x08fc 	PREF_V8 	deprecated.not vAA
+        (
+            0x08fc,
+            ByteCodeFormat::new(
+                "deprecated.not vAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8],
+            ),
+        ),
+        // 0x09fc 	PREF_V8 	deprecated.inc vAA
+        (
+            0x09fc,
+            ByteCodeFormat::new(
+                "deprecated.inc vAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8],
+            ),
+        ),
+        // 0x0afc 	PREF_V8 	deprecated.dec vAA
+        (
+            0x0afc,
+            ByteCodeFormat::new(
+                "deprecated.dec vAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8],
+            ),
+        ),
+        // 0x0bfc 	PREF_V8 	deprecated.callarg0 vAA
+        (
+            0x0bfc,
+            ByteCodeFormat::new(
+                "deprecated.callarg0 vAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8],
+            ),
+        ),
+        // 0x0cfc 	PREF_V8_V8 	deprecated.callarg1 vAA, vBB
+        (
+            0x0cfc,
+            ByteCodeFormat::new(
+                "deprecated.callarg1 vAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8, FormatUnit::V8],
+            ),
+        ),
+        // 0x0dfc 	PREF_V8_V8_V8 	deprecated.callargs2 vAA, vBB, vCC
+        (
+            0x0dfc,
+            ByteCodeFormat::new(
+                "deprecated.callargs2 vAA, vBB, vCC".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                ],
+            ),
+        ),
+        // 0x0efc 	PREF_V8_V8_V8_V8 	deprecated.callargs3 vAA, vBB, vCC, vDD
+        (
+            0x0efc,
+            ByteCodeFormat::new(
+                "deprecated.callargs3 vAA, vBB, vCC, vDD".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
                     FormatUnit::V8,
                     FormatUnit::V8,
                 ],
             ),
         ),
-        // 0xfe 	PREF_NONE 	throw 	默认入参：acc：异常 	抛出acc中存放的异
+        // 0x0ffc 	PREF_IMM16_V8 	deprecated.callrange +AAAA, vBB
+        (
+            0x0ffc,
+            ByteCodeFormat::new(
+                "deprecated.callrange +AAAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16, FormatUnit::V8],
+            ),
+        ),
+        // 0x10fc 	PREF_V8_V8_V8 	deprecated.callspread vAA, vBB, vCC
+        (
+            0x10fc,
+            ByteCodeFormat::new(
+                "deprecated.callspread vAA, vBB, vCC".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                ],
+            ),
+        ),
+        // 0x11fc 	PREF_IMM16_V8 	deprecated.callthisrange +AAAA, vBB
+        (
+            0x11fc,
+            ByteCodeFormat::new(
+                "deprecated.callthisrange +AAAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16, FormatUnit::V8],
+            ),
+        ),
+        // 0x12fc 	PREF_ID16_IMM16_IMM16_V8_V8 	deprecated.defineclasswithbuffer @AAAA, +BBBB, +CCCC, vDD, vEE
+        (
+            0x12fc,
+            ByteCodeFormat::new(
+                "deprecated.defineclasswithbuffer @AAAA, +BBBB, +CCCC, vDD, vEE".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::MethodID,
+                    FormatUnit::IMM16,
+                    FormatUnit::IMM16,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                ],
+            ),
+        ),
+        // 0x13fc 	PREF_V8 	deprecated.resumegenerator vAA
+        (
+            0x13fc,
+            ByteCodeFormat::new(
+                "deprecated.resumegenerator vAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8],
+            ),
+        ),
+        // 0x14fc 	PREF_V8 	deprecated.getresumemode vAA
+        (
+            0x14fc,
+            ByteCodeFormat::new(
+                "deprecated.getresumemode vAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8],
+            ),
+        ),
+        // 0x15fc 	PREF_V8 	deprecated.gettemplateobject vAA
+        (
+            0x15fc,
+            ByteCodeFormat::new(
+                "deprecated.gettemplateobject vAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8],
+            ),
+        ),
+        // 0x16fc 	PREF_V8_V8 	deprecated.delobjprop vAA, vBB
+        (
+            0x16fc,
+            ByteCodeFormat::new(
+                "deprecated.delobjprop vAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8, FormatUnit::V8],
+            ),
+        ),
+        // 0x17fc 	PREF_V8_V8 	deprecated.suspendgenerator vAA, vBB
+        (
+            0x17fc,
+            ByteCodeFormat::new(
+                "deprecated.suspendgenerator vAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8, FormatUnit::V8],
+            ),
+        ),
+        // 0x18fc 	PREF_V8_V8 	deprecated.asyncfunctionawaituncaught vAA, vBB
+        (
+            0x18fc,
+            ByteCodeFormat::new(
+                "deprecated.asyncfunctionawaituncaught vAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8, FormatUnit::V8],
+            ),
+        ),
+        // 0x19fc 	PREF_V8_V8 	deprecated.copydataproperties vAA, vBB
+        (
+            0x19fc,
+            ByteCodeFormat::new(
+                "deprecated.copydataproperties vAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8, FormatUnit::V8],
+            ),
+        ),
+        // 0x1afc 	PREF_V8_V8 	deprecated.setobjectwithproto vAA, vBB
+        (
+            0x1afc,
+            ByteCodeFormat::new(
+                "deprecated.setobjectwithproto vAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8, FormatUnit::V8],
+            ),
+        ),
+        // 0x1bfc 	PREF_V8_V8 	deprecated.ldobjbyvalue vAA, vBB
+        (
+            0x1bfc,
+            ByteCodeFormat::new(
+                "deprecated.ldobjbyvalue vAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8, FormatUnit::V8],
+            ),
+        ),
+        // 0x1cfc 	PREF_V8_V8 	deprecated.ldsuperbyvalue vAA, vBB
+        (
+            0x1cfc,
+            ByteCodeFormat::new(
+                "deprecated.ldsuperbyvalue vAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8, FormatUnit::V8],
+            ),
+        ),
+        // 0x1dfc 	PREF_V8_IMM32 	deprecated.ldobjbyindex vAA, +BBBBBBBB
         (
-            0xfe,
-            ByteCodeFormat::new("thrown".to_owned(), vec![FormatUnit::PrefixOpcode]),
+            0x1dfc,
+            ByteCodeFormat::new(
+                "deprecated.ldobjbyindex vAA, +BBBBBBBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8, FormatUnit::IMM32],
+            ),
         ),
-    ];
-
-    let mut map = HashMap::new();
-    for team in &opcode_vec {
-        map.insert(team.0, team.1.clone());
-    }
-    map
-}
-
-fn init_prefix_opcode_map() -> HashMap<u16, ByteCodeFormat> {
-    let prefix_opcode_vec = vec![
+        // 0x1efc 	PREF_V8_V8_V8 	deprecated.asyncfunctionresolve vAA, vBB, vCC
         (
-            0x01fb,
+            0x1efc,
             ByteCodeFormat::new(
-                "callruntime.definefieldbyvalue RR, vAA, vBB".to_owned(),
+                "deprecated.asyncfunctionresolve vAA, vBB, vCC".to_owned(),
                 vec![
                     FormatUnit::PrefixOpcode,
-                    FormatUnit::RR,
+                    FormatUnit::V8,
                     FormatUnit::V8,
                     FormatUnit::V8,
                 ],
             ),
         ),
+        // 0x1ffc 	PREF_V8_V8_V8 	deprecated.asyncfunctionreject vAA, vBB, vCC
         (
-            0x01fd,
+            0x1ffc,
             ByteCodeFormat::new(
-                "wide.newobjrange +AAAA, vBB".to_owned(),
-                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16, FormatUnit::V8],
+                "deprecated.asyncfunctionreject vAA, vBB, vCC".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                ],
             ),
         ),
+        // 0x20fc 	PREF_IMM4_IMM4_V8 	deprecated.stlexvar +A, +B, vCC
         (
-            0x01fe,
-            ByteCodeFormat::new("throw.notexists".to_owned(), vec![FormatUnit::PrefixOpcode]),
+            0x20fc,
+            ByteCodeFormat::new(
+                "deprecated.stlexvar +A, +B, vCC".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::Imm4Imm4,
+                    FormatUnit::V8,
+                ],
+            ),
         ),
+        // 0x21fc 	PREF_IMM8_IMM8_V8 	deprecated.stlexvar +AA, +BB, vCC
         (
-            0x02fb,
+            0x21fc,
             ByteCodeFormat::new(
-                "callruntime.definefieldbyindex RR, +AAAAAAAA, vBB".to_owned(),
+                "deprecated.stlexvar +AA, +BB, vCC".to_owned(),
                 vec![
                     FormatUnit::PrefixOpcode,
-                    FormatUnit::RR,
-                    FormatUnit::IMM32,
+                    FormatUnit::IMM8,
+                    FormatUnit::IMM8,
                     FormatUnit::V8,
                 ],
             ),
         ),
+        // 0x22fc 	PREF_IMM16_IMM16_V8 	deprecated.stlexvar +AAAA, +BBBB, vCC
         (
-            0x02fd,
+            0x22fc,
             ByteCodeFormat::new(
-                "wide.newlexenv +AAAA".to_owned(),
-                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16],
+                "deprecated.stlexvar +AAAA, +BBBB, vCC".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::IMM16,
+                    FormatUnit::IMM16,
+                    FormatUnit::V8,
+                ],
             ),
         ),
+        // 0x23fc 	PREF_ID32 	deprecated.getmodulenamespace @AAAAAAAA
         (
-            0x02fe,
+            0x23fc,
             ByteCodeFormat::new(
-                "throw.patternnoncoercible".to_owned(),
-                vec![FormatUnit::PrefixOpcode],
+                "deprecated.getmodulenamespace @AAAAAAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::StringID32],
             ),
         ),
+        // 0x24fc 	PREF_ID32 	deprecated.stmodulevar @AAAAAAAA
         (
-            0x03fb,
+            0x24fc,
             ByteCodeFormat::new(
-                "wide.newlexenvwithname +AAAA, @BBBB".to_owned(),
-                vec![
-                    FormatUnit::PrefixOpcode,
-                    FormatUnit::IMM16,
-                    FormatUnit::LiteralID,
-                ],
+                "deprecated.stmodulevar @AAAAAAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::StringID32],
             ),
         ),
+        // 0x25fc 	PREF_ID32_V8 	deprecated.ldobjbyname @AAAAAAAA, vBB
         (
-            0x03fe,
+            0x25fc,
             ByteCodeFormat::new(
-                "throw.deletesuperproperty".to_owned(),
-                vec![FormatUnit::PrefixOpcode],
+                "deprecated.ldobjbyname @AAAAAAAA, vBB".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::StringID32,
+                    FormatUnit::V8,
+                ],
             ),
         ),
+        // 0x26fc 	PREF_ID32_V8 	deprecated.ldsuperbyname @AAAAAAAA, vBB
         (
-            0x04fb,
+            0x26fc,
             ByteCodeFormat::new(
-                "callruntime.createprivateproperty +AAAA, @BBBB".to_owned(),
+                "deprecated.ldsuperbyname @AAAAAAAA, vBB".to_owned(),
                 vec![
                     FormatUnit::PrefixOpcode,
-                    FormatUnit::IMM16,
-                    FormatUnit::LiteralID,
+                    FormatUnit::StringID32,
+                    FormatUnit::V8,
                 ],
             ),
         ),
+        // 0x27fc 	PREF_ID32_IMM8 	deprecated.ldmodulevar @AAAAAAAA, +BB
         (
-            0x04fd,
+            0x27fc,
             ByteCodeFormat::new(
-                "wide.callrange +AAAA, vBB".to_owned(),
-                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16, FormatUnit::V8],
+                "deprecated.ldmodulevar @AAAAAAAA, +BB".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::StringID32,
+                    FormatUnit::IMM8,
+                ],
             ),
         ),
-        // 0x04fe 	PREF_V8 	throw.constassignment vAA 	A：常量变量的名称 	抛出异常：对常量变量进行赋值。
+        // 0x28fc 	PREF_ID32 	deprecated.stconsttoglobalrecord @AAAAAAAA
         (
-            0x04fe,
+            0x28fc,
             ByteCodeFormat::new(
-                "throw.constassignment".to_owned(),
-                vec![FormatUnit::PrefixOpcode, FormatUnit::V8],
+                "deprecated.stconsttoglobalrecord @AAAAAAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::StringID32],
             ),
         ),
-        // 0x05fb 	PREF_IMM8_IMM_16_IMM_16_V8 	callruntime.defineprivateproperty RR, +AAAA, +BBBB, vCC
+        // 0x29fc 	PREF_ID32 	deprecated.stlettoglobalrecord @AAAAAAAA
         (
-            0x05fb,
+            0x29fc,
             ByteCodeFormat::new(
-                "callruntime.defineprivateproperty".to_owned(),
-                vec![
-                    FormatUnit::PrefixOpcode,
-                    FormatUnit::RR,
-                    FormatUnit::IMM16,
-                    FormatUnit::IMM16,
-                    FormatUnit::V8,
-                ],
+                "deprecated.stlettoglobalrecord @AAAAAAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::StringID32],
             ),
         ),
-        // 0x05fd 	PREF_IMM16_V8 	wide.callthisrange +AAAA, vBB
+        // 0x2afc 	PREF_ID32 	deprecated.stclasstoglobalrecord @AAAAAAAA
         (
-            0x05fd,
+            0x2afc,
             ByteCodeFormat::new(
-                "wide.callthisrange".to_owned(),
-                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16, FormatUnit::V8],
+                "deprecated.stclasstoglobalrecord @AAAAAAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::StringID32],
             ),
         ),
-        // 0x05fb 	PREF_IMM8_IMM_16_IMM_16_V8 	callruntime.defineprivateproperty RR, +AAAA, +BBBB, vCC
+        // 0x2bfc 	PREF_NONE 	deprecated.ldhomeobject
         (
-            0x05fe,
+            0x2bfc,
             ByteCodeFormat::new(
-                "throw.ifnotobject".to_owned(),
-                vec![
-                    FormatUnit::PrefixOpcode,
-                    FormatUnit::RR,
-                    FormatUnit::IMM16,
-                    FormatUnit::IMM16,
-                    FormatUnit::V8,
-                ],
+                "deprecated.ldhomeobject".to_owned(),
+                vec![FormatUnit::PrefixOpcode],
             ),
         ),
-        // 0x05fd 	PREF_IMM16_V8 	wide.callthisrange +AAAA, vBB
+        // 0x2cfc 	PREF_IMM16 	deprecated.createobjecthavingmethod +AAAA
         (
-            0x05fd,
+            0x2cfc,
             ByteCodeFormat::new(
-                "wide.callthisrange".to_owned(),
-                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16, FormatUnit::V8],
+                "deprecated.createobjecthavingmethod +AAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16],
             ),
         ),
-        // 0x05fe 	PREF_V8 	throw.ifnotobject vAA 	A：对象 	如果A不是一个对象，抛出异常。
+        // 0x2dfc 	PREF_V8 	deprecated.dynamicimport vAA
         (
-            0x05fe,
+            0x2dfc,
             ByteCodeFormat::new(
-                "throw.ifnotobject".to_owned(),
+                "deprecated.dynamicimport vAA".to_owned(),
                 vec![FormatUnit::PrefixOpcode, FormatUnit::V8],
             ),
         ),
-        // 0x06fb 	PREF_IMM8_V8 	callruntime.callinit +RR, vAA
+        // 0x2efc 	PREF_V8_V8 	deprecated.asyncgeneratorreject vAA, vBB
         (
-            0x06fb,
+            0x2efc,
             ByteCodeFormat::new(
-                "callruntime.callinit".to_owned(),
-                vec![FormatUnit::PrefixOpcode, FormatUnit::RR, FormatUnit::V8],
+                "deprecated.asyncgeneratorreject vAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8, FormatUnit::V8],
             ),
         ),
-        // 0x06fd 	PREF_IMM16_V8 	wide.supercallthisrange +AAAA, vBB
+        // 0xfd 	PREF_IMM16_V8_V8 	wide.createobjectwithexcludedkeys +AAAA, vBB, vCC
         (
-            0x06fd,
+            0xfd,
             ByteCodeFormat::new(
-                "wide.supercallthisrange".to_owned(),
-                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16, FormatUnit::V8],
+                "wide.createobjectwithexcludedkeys +AAAA, vBB, vCC".to_owned(),
+                vec![
+                    FormatUnit::PrefixOpcode,
+                    FormatUnit::IMM16,
+                    FormatUnit::V8,
+                    FormatUnit::V8,
+                ],
             ),
         ),
-        // 0x06fd 	PREF_IMM16_V8 	wide.supercallthisrange +AAAA, vBB
+        // 0x01fd 	PREF_IMM16_V8 	wide.newobjrange +AAAA, vBB
         (
-            0x06fe,
+            0x01fd,
             ByteCodeFormat::new(
-                "throw.undefinedifhole".to_owned(),
+                "wide.newobjrange +AAAA, vBB".to_owned(),
                 vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16, FormatUnit::V8],
             ),
         ),
-        // 0x06fe 	PREF_V8_V8 	throw.undefinedifhole vAA, vBB
+        // 0x02fd 	PREF_IMM16 	wide.newlexenv +AAAA
         (
-            0x06fe,
+            0x02fd,
             ByteCodeFormat::new(
-                "throw.undefinedifhole".to_owned(),
-                vec![FormatUnit::PrefixOpcode, FormatUnit::V8, FormatUnit::V8],
+                "wide.newlexenv +AAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16],
             ),
         ),
-        // 0x07fb 	PREF_IMM16_ID16_ID16_IMM16_V8 	callruntime.definesendableclass RRRR, @AAAA, @BBBB, +CCCC, vDD
+        // 0x03fd 	PREF_IMM16_ID16 	wide.newlexenvwithname +AAAA, @BBBB
         (
-            0x07fb,
+            0x03fd,
             ByteCodeFormat::new(
-                "callruntime.definesendableclass".to_owned(),
+                "wide.newlexenvwithname +AAAA, @BBBB".to_owned(),
                 vec![
                     FormatUnit::PrefixOpcode,
-                    FormatUnit::RRRR,
-                    FormatUnit::MethodID,
-                    FormatUnit::LiteralID,
                     FormatUnit::IMM16,
-                    FormatUnit::V8,
+                    FormatUnit::LiteralID,
                 ],
             ),
         ),
-        // 0x07fd 	PREF_IMM16_V8 	wide.supercallarrowrange +AAAA, vBB
+        // 0x04fd 	PREF_IMM16_V8 	wide.callrange +AAAA, vBB
         (
-            0x07fd,
+            0x04fd,
             ByteCodeFormat::new(
-                "wide.supercallarrowrange".to_owned(),
+                "wide.callrange +AAAA, vBB".to_owned(),
                 vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16, FormatUnit::V8],
             ),
         ),
-        // 0x07fe 	PREF_IMM8 	throw.ifsupernotcorrectcall +AA
+        // 0x05fd 	PREF_IMM16_V8 	wide.callthisrange +AAAA, vBB
         (
-            0x07fe,
+            0x05fd,
             ByteCodeFormat::new(
-                "throw.ifsupernotcorrectcall".to_owned(),
-                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM8],
+                "wide.callthisrange +AAAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16, FormatUnit::V8],
             ),
         ),
-        // 0x08fb 	PREF_IMM16 	callruntime.ldsendableclass +AAAA
+        // 0x06fd 	PREF_IMM16_V8 	wide.supercallthisrange +AAAA, vBB
         (
-            0x08fb,
+            0x06fd,
             ByteCodeFormat::new(
-                "callruntime.ldsendableclass".to_owned(),
-                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16],
+                "wide.supercallthisrange +AAAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16, FormatUnit::V8],
             ),
         ),
-        // 0x08fd 	PREF_IMM32 	wide.ldobjbyindex +AAAAAAAA
+        // 0x07fd 	PREF_IMM16_V8 	wide.supercallarrowrange +AAAA, vBB
         (
-            0x08fd,
+            0x07fd,
             ByteCodeFormat::new(
-                "wide.ldobjbyindex".to_owned(),
-                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM32],
+                "wide.supercallarrowrange +AAAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16, FormatUnit::V8],
             ),
         ),
-        // 0x08fe 	PREF_IMM16 	throw.ifsupernotcorrectcall +AAAA
+        // 0x08fd 	PREF_IMM32 	wide.ldobjbyindex +AAAAAAAA
         (
-            0x08fe,
+            0x08fd,
             ByteCodeFormat::new(
-                "throw.ifsupernotcorrectcall".to_owned(),
-                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16],
+                "wide.ldobjbyindex +AAAAAAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM32],
             ),
         ),
         // 0x09fd 	PREF_V8_IMM32 	wide.stobjbyindex vAA, +BBBBBBBB
         (
             0x09fd,
             ByteCodeFormat::new(
-                "wide.stobjbyindex".to_owned(),
+                "wide.stobjbyindex vAA, +BBBBBBBB".to_owned(),
                 vec![FormatUnit::PrefixOpcode, FormatUnit::V8, FormatUnit::IMM32],
             ),
         ),
-        // 0x09fe 	PREF_ID16 	throw.undefinedifholewithname @AAAA
-        (
-            0x09fe,
-            ByteCodeFormat::new(
-                "throw.undefinedifholewithname".to_owned(),
-                vec![FormatUnit::PrefixOpcode, FormatUnit::StringID],
-            ),
-        ),
         // 0x0afd 	PREF_V8_IMM32 	wide.stownbyindex vAA, +BBBBBBBB
         (
             0x0afd,
@@ -2261,6 +3340,80 @@ fn init_prefix_opcode_map() -> HashMap<u16, ByteCodeFormat> {
                 vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16],
             ),
         ),
+        // 0xfe 	PREF_NONE 	throw
+        (
+            0xfe,
+            ByteCodeFormat::new("throw".to_owned(), vec![FormatUnit::PrefixOpcode]),
+        ),
+        // 0x01fe 	PREF_NONE 	throw.notexists
+        (
+            0x01fe,
+            ByteCodeFormat::new("throw.notexists".to_owned(), vec![FormatUnit::PrefixOpcode]),
+        ),
+        // 0x02fe 	PREF_NONE 	throw.patternnoncoercible
+        (
+            0x02fe,
+            ByteCodeFormat::new(
+                "throw.patternnoncoercible".to_owned(),
+                vec![FormatUnit::PrefixOpcode],
+            ),
+        ),
+        // 0x03fe 	PREF_NONE 	throw.deletesuperproperty
+        (
+            0x03fe,
+            ByteCodeFormat::new(
+                "throw.deletesuperproperty".to_owned(),
+                vec![FormatUnit::PrefixOpcode],
+            ),
+        ),
+        // 0x04fe 	PREF_V8 	throw.constassignment vAA 	A：常量变量的名称 	抛出异常：对常量变量进行赋值。
+        (
+            0x04fe,
+            ByteCodeFormat::new(
+                "throw.constassignment vAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8],
+            ),
+        ),
+        // 0x05fe 	PREF_V8 	throw.ifnotobject vAA 	A：对象 	如果A不是一个对象，抛出异常。
+        (
+            0x05fe,
+            ByteCodeFormat::new(
+                "throw.ifnotobject vAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8],
+            ),
+        ),
+        // 0x06fe 	PREF_V8_V8 	throw.undefinedifhole vAA, vBB
+        (
+            0x06fe,
+            ByteCodeFormat::new(
+                "throw.undefinedifhole vAA, vBB".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::V8, FormatUnit::V8],
+            ),
+        ),
+        // 0x07fe 	PREF_IMM8 	throw.ifsupernotcorrectcall +AA
+        (
+            0x07fe,
+            ByteCodeFormat::new(
+                "throw.ifsupernotcorrectcall +AA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM8],
+            ),
+        ),
+        // 0x08fe 	PREF_IMM16 	throw.ifsupernotcorrectcall +AAAA
+        (
+            0x08fe,
+            ByteCodeFormat::new(
+                "throw.ifsupernotcorrectcall +AAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::IMM16],
+            ),
+        ),
+        // 0x09fe 	PREF_ID16 	throw.undefinedifholewithname @AAAA
+        (
+            0x09fe,
+            ByteCodeFormat::new(
+                "throw.undefinedifholewithname @AAAA".to_owned(),
+                vec![FormatUnit::PrefixOpcode, FormatUnit::StringID],
+            ),
+        ),
     ];
 
     let mut ok = HashMap::new();
@@ -2271,9 +3424,17 @@ fn init_prefix_opcode_map() -> HashMap<u16, ByteCodeFormat> {
 }
 
 impl BytecodeParser {
+    /// 支持的最高版本的字节码表
     pub fn new() -> Self {
-        let opcode_table = init_opcode_map();
-        let prefix_opcode_table = init_prefix_opcode_map();
+        Self::with_version(Version::MAX)
+    }
+
+    /// 根据文件版本选择字节码表，高版本才出现的指令不参与解析
+    pub fn with_version(version: Version) -> Self {
+        let mut opcode_table = init_opcode_map();
+        let mut prefix_opcode_table = init_prefix_opcode_map();
+        opcode_table.retain(|_, bcf| bcf.min_version <= version);
+        prefix_opcode_table.retain(|_, bcf| bcf.min_version <= version);
 
         Self {
             opcode_table,
@@ -2281,50 +3442,90 @@ impl BytecodeParser {
         }
     }
 
-    fn get_opcode(&self, opcode: u16) -> &ByteCodeFormat {
-        self.opcode_table.get(&opcode).unwrap()
-    }
+    /// 查找 `offset` 处的指令格式，找不到时返回原因
+    fn lookup(&self, instructions: &[u8], offset: usize) -> Result<&ByteCodeFormat, String> {
+        let opcode = instructions[offset];
+        let bcf = if PREFIXES.contains(&opcode) {
+            let Ok(pref_opcode) = instructions.pread_with::<u16>(offset, scroll::LE) else {
+                return Err(format!("truncated prefix opcode 0x{:02X}", opcode));
+            };
+            self.prefix_opcode_table
+                .get(&pref_opcode)
+                .ok_or_else(|| format!("unknown opcode 0x{:04X}", pref_opcode))?
+        } else {
+            self.opcode_table
+                .get(&(opcode as u16))
+                .ok_or_else(|| format!("unknown opcode 0x{:02X}", opcode))?
+        };
 
-    fn get_prefix_opcode(&self, opcode: u16) -> Option<&ByteCodeFormat> {
-        self.prefix_opcode_table.get(&opcode)
+        if offset + bcf.get_size() > instructions.len() {
+            return Err(format!(
+                "truncated instruction {}, need {} bytes, {} left",
+                bcf.mnemonic(),
+                bcf.get_size(),
+                instructions.len() - offset
+            ));
+        }
+        Ok(bcf)
     }
 
+    /// 解码方法的所有指令
     pub fn parse(
         &self,
         code: &Code,
         region: &Region,
         source: &[u8],
-        literal_array_map: &HashMap<usize, String>,
-    ) {
+        literal_array_map: &HashMap<usize, LiteralArray>,
+    ) -> Result<Vec<Instruction>, error::Error> {
         let instructions = code.instructions();
+        let mut result = Vec::new();
         let mut offset = 0;
         let size = instructions.len();
-        loop {
-            let pref_opcode = instructions.pread::<u16>(offset).unwrap();
-            let bcf = self.get_prefix_opcode(pref_opcode);
-
-            if bcf.is_none() {
-                let opcode = instructions.pread::<u8>(offset).unwrap();
-                let bcf = self.get_opcode(opcode as u16);
-                offset = bcf.parse(instructions, offset, region, source, literal_array_map);
-            } else {
-                offset =
-                    bcf.unwrap()
-                        .parse(instructions, offset, region, source, literal_array_map);
-            }
-
-            if offset >= size {
-                break;
+        while offset < size {
+            let bcf = match self.lookup(instructions, offset) {
+                Ok(bcf) => bcf,
+                Err(diagnostic) => {
+                    // 无法解码的字节当作数据，继续解码后面的指令
+                    tracing::warn!("{} at {}", diagnostic, offset);
+                    result.push(Instruction::unknown(instructions, offset, diagnostic));
+                    offset += 1;
+                    continue;
+                }
+            };
+            match bcf.parse(instructions, offset, region, source, literal_array_map) {
+                Ok(instruction) => {
+                    offset += instruction.size();
+                    result.push(instruction);
+                }
+                Err(e) => {
+                    // 操作数引用的字符串、方法或字面量数组无法解析，整条指令的字节都当作数据
+                    let diagnostic = format!("unresolved operand of {}: {}", bcf.mnemonic(), e);
+                    tracing::warn!("{} at {}", diagnostic, offset);
+                    for _ in 0..bcf.get_size() {
+                        result.push(Instruction::unknown(
+                            instructions,
+                            offset,
+                            diagnostic.clone(),
+                        ));
+                        offset += 1;
+                    }
+                }
             }
+        }
 
-            // 剩余最后一个
-            if offset + 1 == size {
-                let opcode = instructions.pread::<u8>(offset).unwrap();
-                let bcf = self.get_opcode(opcode as u16);
-                bcf.parse(instructions, offset, region, source, literal_array_map);
-                break;
+        // 跳转指令直接引用目标处的标签
+        let labels = branch_labels(&result);
+        for instruction in &mut result {
+            for operand in &mut instruction.operands {
+                if let Operand::Branch { target, label, .. } = operand {
+                    *label = usize::try_from(*target)
+                        .ok()
+                        .and_then(|target| labels.get(&target).cloned());
+                }
             }
         }
+
+        Ok(result)
     }
 }
 
@@ -0,0 +1,451 @@
+/// 调用图：方法之间的调用关系，以及对导入模块、系统 API 的调用
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt::{self, Write};
+
+use getset::Getters;
+
+use crate::bytecode::{Instruction, Operand};
+use crate::dataflow::{Effects, Location};
+use crate::literal::{Literal, ModuleRecord};
+
+/// 节点的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NodeKind {
+    /// 文件中定义的方法
+    Method,
+    /// 从其他模块导入的变量，包括动态导入的模块
+    Module,
+    /// 系统 API，即 `@ohos.*`、`@system.*` 模块
+    System,
+    /// 全局变量，或无法确定对象的属性，如 `JSON.stringify`、`*.push`
+    Global,
+}
+
+impl fmt::Display for NodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            NodeKind::Method => "method",
+            NodeKind::Module => "module",
+            NodeKind::System => "system",
+            NodeKind::Global => "global",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+/// 边的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CallKind {
+    /// `call*` 指令，被调用的函数在 acc 中
+    Call,
+    /// `newobjrange`，构造函数在第一个寄存器中
+    New,
+    /// `definefunc`、`definemethod`、`defineclasswithbuffer` 等定义了方法，包括字面量数组中的方法
+    Define,
+    /// `dynamicimport`
+    Import,
+}
+
+impl fmt::Display for CallKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            CallKind::Call => "call",
+            CallKind::New => "new",
+            CallKind::Define => "define",
+            CallKind::Import => "import",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+/// 调用图中的一条边
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Getters)]
+#[get = "pub"]
+pub struct CallEdge {
+    /// 调用者，格式：类名->方法名
+    caller: String,
+    /// 被调用者，方法的格式同 `caller`，其他节点为模块名加属性名，如 `@ohos.hilog.info`
+    callee: String,
+    kind: CallKind,
+    /// 调用指令的偏移量
+    pc: usize,
+}
+
+/// 整个文件的调用图
+#[derive(Debug, Clone, Default, Getters)]
+#[get = "pub"]
+pub struct CallGraph {
+    /// 节点名 -> 类型
+    nodes: BTreeMap<String, NodeKind>,
+    /// 按调用者、被调用者和 pc 排序
+    edges: Vec<CallEdge>,
+}
+
+/// 模块名统一为 `@ohos.net.http` 的形式，`@ohos:net.http` 是 OHM 格式的写法
+fn module_name(request: &str) -> String {
+    for scope in ["@ohos", "@system"] {
+        if let Some(name) = request
+            .strip_prefix(scope)
+            .and_then(|name| name.strip_prefix(':'))
+        {
+            return format!("{}.{}", scope, name);
+        }
+    }
+    request.to_string()
+}
+
+/// `@ohos.*`、`@system.*` 是系统 API，其他的是普通模块
+fn module_kind(module: &str) -> NodeKind {
+    if module.starts_with("@ohos.") || module.starts_with("@system.") {
+        NodeKind::System
+    } else {
+        NodeKind::Module
+    }
+}
+
+/// 方法签名 `类名->方法名(参数类型)返回值类型` 去掉参数和返回值
+fn method_name(sign: &str) -> &str {
+    sign.split_once('(').map_or(sign, |(name, _)| name)
+}
+
+/// acc 或寄存器中的值
+#[derive(Debug, Clone)]
+enum Value {
+    Node(String, NodeKind),
+    Str(String),
+}
+
+impl Value {
+    /// 读取属性 `name`，无法确定对象时记为 `*.name`
+    fn member(base: Option<&Value>, name: &str) -> Value {
+        match base {
+            Some(Value::Node(base, kind)) if *kind != NodeKind::Method => {
+                Value::Node(format!("{}.{}", base, name), *kind)
+            }
+            _ => Value::Node(format!("*.{}", name), NodeKind::Global),
+        }
+    }
+}
+
+impl CallGraph {
+    /// 添加一个节点，已有的节点不变
+    pub(crate) fn add_node(&mut self, name: &str, kind: NodeKind) {
+        self.nodes.entry(name.to_string()).or_insert(kind);
+    }
+
+    fn add_edge(&mut self, caller: &str, callee: Value, kind: CallKind, pc: usize) {
+        let Value::Node(callee, node_kind) = callee else {
+            return;
+        };
+        self.add_node(&callee, node_kind);
+        self.edges.push(CallEdge {
+            caller: caller.to_string(),
+            callee,
+            kind,
+            pc,
+        });
+    }
+
+    /// 按顺序模拟方法的指令，跟踪 acc 和寄存器中的函数，不考虑分支
+    ///
+    /// `record` 是方法所在类的模块记录，用于解析 `ldexternalmodulevar` 等导入的变量。
+    pub(crate) fn add_method(
+        &mut self,
+        caller: &str,
+        instructions: &[Instruction],
+        record: Option<&ModuleRecord>,
+    ) {
+        self.add_node(caller, NodeKind::Method);
+
+        let mut acc: Option<Value> = None;
+        let mut registers: HashMap<u16, Value> = HashMap::new();
+        for instruction in instructions {
+            let pc = *instruction.offset();
+            let mnemonic = instruction.mnemonic().as_str();
+            let operands = instruction.operands();
+            let registers_of: Vec<u16> = operands
+                .iter()
+                .filter_map(|operand| match operand {
+                    Operand::Register(reg) => Some(*reg),
+                    _ => None,
+                })
+                .collect();
+            let imm = operands.iter().find_map(|operand| match operand {
+                Operand::Imm(value) => Some(*value as usize),
+                _ => None,
+            });
+            let string = operands.iter().find_map(|operand| match operand {
+                Operand::String { value, .. } => Some(value.as_str()),
+                _ => None,
+            });
+
+            // 定义的方法，包括字面量数组中的方法
+            let mut defined = None;
+            for operand in operands {
+                match operand {
+                    Operand::Method { name, .. } => {
+                        let value = Value::Node(method_name(name).to_string(), NodeKind::Method);
+                        self.add_edge(caller, value.clone(), CallKind::Define, pc);
+                        defined = Some(value);
+                    }
+                    Operand::LiteralArray { value, .. } => {
+                        for entry in value.entries() {
+                            if let Literal::Method { name, .. }
+                            | Literal::GeneratorMethod { name, .. }
+                            | Literal::AsyncGeneratorMethod { name, .. }
+                            | Literal::Getter { name, .. }
+                            | Literal::Setter { name, .. } = entry
+                            {
+                                let value =
+                                    Value::Node(method_name(name).to_string(), NodeKind::Method);
+                                self.add_edge(caller, value, CallKind::Define, pc);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            let result = match mnemonic {
+                "lda" => {
+                    acc = registers_of
+                        .first()
+                        .and_then(|reg| registers.get(reg))
+                        .cloned();
+                    continue;
+                }
+                "sta" => {
+                    match &acc {
+                        Some(value) => registers.insert(registers_of[0], value.clone()),
+                        None => registers.remove(&registers_of[0]),
+                    };
+                    continue;
+                }
+                "mov" => {
+                    match registers.get(&registers_of[1]).cloned() {
+                        Some(value) => registers.insert(registers_of[0], value),
+                        None => registers.remove(&registers_of[0]),
+                    };
+                    continue;
+                }
+                "lda.str" => string.map(|value| Value::Str(value.to_string())),
+                "ldexternalmodulevar" | "wide.ldexternalmodulevar" => record
+                    .zip(imm)
+                    .and_then(|(record, idx)| record.regular_imports().get(idx))
+                    .and_then(|import| {
+                        let request = record?.module_request(*import.module_request_idx())?;
+                        let module = module_name(request);
+                        let name = match import.import_name().as_str() {
+                            "default" | "*" => module.clone(),
+                            name => format!("{}.{}", module, name),
+                        };
+                        Some(Value::Node(name, module_kind(&module)))
+                    }),
+                "getmodulenamespace" | "wide.getmodulenamespace" => record
+                    .zip(imm)
+                    .and_then(|(record, idx)| record.namespace_imports().get(idx))
+                    .and_then(|import| {
+                        let request = record?.module_request(*import.module_request_idx())?;
+                        let module = module_name(request);
+                        let kind = module_kind(&module);
+                        Some(Value::Node(module, kind))
+                    }),
+                "tryldglobalbyname" | "ldglobalvar" => {
+                    string.map(|name| Value::Node(name.to_string(), NodeKind::Global))
+                }
+                "ldobjbyname" | "ldthisbyname" | "ldsuperbyname" => {
+                    let base = match mnemonic {
+                        "ldobjbyname" => acc.as_ref(),
+                        _ => None,
+                    };
+                    string.map(|name| Value::member(base, name))
+                }
+                "dynamicimport" => {
+                    if let Some(Value::Str(request)) = &acc {
+                        let module = module_name(request);
+                        let kind = module_kind(&module);
+                        self.add_edge(caller, Value::Node(module, kind), CallKind::Import, pc);
+                    }
+                    None
+                }
+                "newobjrange" | "wide.newobjrange" => {
+                    if let Some(callee) = registers_of.first().and_then(|reg| registers.get(reg)) {
+                        self.add_edge(caller, callee.clone(), CallKind::New, pc);
+                    }
+                    None
+                }
+                "callarg0"
+                | "callarg1"
+                | "callargs2"
+                | "callargs3"
+                | "callrange"
+                | "wide.callrange"
+                | "callthis0"
+                | "callthis1"
+                | "callthis2"
+                | "callthis3"
+                | "callthisrange"
+                | "wide.callthisrange"
+                | "callruntime.callinit" => {
+                    if let Some(callee) = acc.clone() {
+                        self.add_edge(caller, callee, CallKind::Call, pc);
+                    }
+                    None
+                }
+                // 旧版本的调用指令，被调用的函数在第一个寄存器中
+                "deprecated.callarg0"
+                | "deprecated.callarg1"
+                | "deprecated.callargs2"
+                | "deprecated.callargs3"
+                | "deprecated.callrange"
+                | "deprecated.callspread"
+                | "deprecated.callthisrange" => {
+                    if let Some(callee) = registers_of.first().and_then(|reg| registers.get(reg)) {
+                        self.add_edge(caller, callee.clone(), CallKind::Call, pc);
+                    }
+                    None
+                }
+                _ => defined,
+            };
+
+            for location in Effects::of(instruction).writes() {
+                match location {
+                    Location::Acc => acc = result.clone(),
+                    Location::Register(reg) => {
+                        registers.remove(reg);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 排序并去掉重复的边
+    pub(crate) fn finish(&mut self) {
+        self.edges.sort();
+        self.edges.dedup();
+    }
+
+    /// 方法调用的节点
+    pub fn callees<'a>(&'a self, caller: &'a str) -> impl Iterator<Item = &'a CallEdge> {
+        self.edges.iter().filter(move |edge| edge.caller == caller)
+    }
+
+    /// 调用节点的方法
+    pub fn callers<'a>(&'a self, callee: &'a str) -> impl Iterator<Item = &'a CallEdge> {
+        self.edges.iter().filter(move |edge| edge.callee == callee)
+    }
+
+    /// 直接或间接调用 `target` 的方法，`target` 也匹配它的属性，如 `@ohos.net.http` 匹配 `@ohos.net.http.createHttp`
+    ///
+    /// 回调函数无法确定在哪里调用，因此定义方法的边也会被跟踪。
+    pub fn reaching(&self, target: &str) -> BTreeSet<&str> {
+        let prefix = format!("{}.", target);
+        let mut queue: VecDeque<&str> = self
+            .nodes
+            .keys()
+            .filter(|name| *name == target || name.starts_with(&prefix))
+            .map(String::as_str)
+            .collect();
+        let mut result = BTreeSet::new();
+        while let Some(node) = queue.pop_front() {
+            for edge in self.callers(node) {
+                if result.insert(edge.caller.as_str()) {
+                    queue.push_back(&edge.caller);
+                }
+            }
+        }
+        result
+    }
+
+    /// 输出为 JSON：`{"nodes": [{"name", "kind"}], "edges": [{"caller", "callee", "kind", "pc"}]}`
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\n  \"nodes\": [");
+        for (i, (name, kind)) in self.nodes.iter().enumerate() {
+            let _ = write!(
+                json,
+                "{}\n    {{\"name\": \"{}\", \"kind\": \"{}\"}}",
+                if i > 0 { "," } else { "" },
+                escape_json(name),
+                kind
+            );
+        }
+        json.push_str("\n  ],\n  \"edges\": [");
+        for (i, edge) in self.edges.iter().enumerate() {
+            let _ = write!(
+                json,
+                "{}\n    {{\"caller\": \"{}\", \"callee\": \"{}\", \"kind\": \"{}\", \"pc\": {}}}",
+                if i > 0 { "," } else { "" },
+                escape_json(&edge.caller),
+                escape_json(&edge.callee),
+                edge.kind,
+                edge.pc
+            );
+        }
+        json.push_str("\n  ]\n}\n");
+        json
+    }
+
+    /// 输出为 DOT，同一对节点之间相同类型的边只输出一次，系统 API 标为红色
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        let _ = writeln!(dot, "digraph \"callgraph\" {{");
+        let _ = writeln!(dot, "    node [shape=box, fontname=\"monospace\"];");
+        for (name, kind) in &self.nodes {
+            let style = match kind {
+                NodeKind::Method => "",
+                NodeKind::Module => ", shape=ellipse",
+                NodeKind::System => ", shape=ellipse, color=red, fontcolor=red",
+                NodeKind::Global => ", shape=ellipse, style=dashed",
+            };
+            let _ = writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\"{}];",
+                escape_dot(name),
+                escape_dot(name),
+                style
+            );
+        }
+        let edges: BTreeSet<(&str, &str, CallKind)> = self
+            .edges
+            .iter()
+            .map(|edge| (edge.caller.as_str(), edge.callee.as_str(), edge.kind))
+            .collect();
+        for (caller, callee, kind) in edges {
+            let label = match kind {
+                CallKind::Call => String::new(),
+                kind => format!(" [label=\"{}\", style=dashed]", kind),
+            };
+            let _ = writeln!(
+                dot,
+                "    \"{}\" -> \"{}\"{};",
+                escape_dot(caller),
+                escape_dot(callee),
+                label
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
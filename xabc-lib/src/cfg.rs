@@ -0,0 +1,355 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+use getset::Getters;
+
+use crate::bytecode::{self, Instruction};
+use crate::code::Code;
+
+/// 结束方法执行的指令，它们所在的基本块没有后继（异常边除外）
+const TERMINATORS: [&str; 7] = [
+    "return",
+    "returnundefined",
+    "throw",
+    "throw.notexists",
+    "throw.patternnoncoercible",
+    "throw.deletesuperproperty",
+    "throw.constassignment",
+];
+
+/// 边的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// 无条件跳转或顺序执行
+    Unconditional,
+    /// 条件跳转成立，跳到目标处
+    True,
+    /// 条件跳转不成立，顺序执行下一条指令
+    False,
+    /// 从 TryBlock 到异常处理块
+    Exception,
+}
+
+impl EdgeKind {
+    /// DOT 中边的标签，无条件的边没有标签
+    fn dot_label(&self) -> Option<&'static str> {
+        match self {
+            EdgeKind::Unconditional => None,
+            EdgeKind::True => Some("true"),
+            EdgeKind::False => Some("false"),
+            EdgeKind::Exception => Some("exception"),
+        }
+    }
+}
+
+/// 控制流图中的一条边，`from` 和 `to` 是基本块的编号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Getters)]
+#[get = "pub"]
+pub struct Edge {
+    from: usize,
+    to: usize,
+    kind: EdgeKind,
+}
+
+/// 基本块
+#[derive(Debug, Clone, Getters)]
+#[get = "pub"]
+pub struct BasicBlock {
+    /// 基本块的编号，即在 `ControlFlowGraph::blocks` 中的下标
+    id: usize,
+    /// 第一条指令的偏移量
+    start_pc: usize,
+    /// 最后一条指令之后的偏移量
+    end_pc: usize,
+    instructions: Vec<Instruction>,
+    /// 前驱基本块的编号
+    predecessors: Vec<usize>,
+    /// 后继基本块的编号
+    successors: Vec<usize>,
+}
+
+impl BasicBlock {
+    /// 基本块的最后一条指令
+    pub fn last_instruction(&self) -> Option<&Instruction> {
+        self.instructions.last()
+    }
+}
+
+/// 方法的控制流图，入口为 0 号基本块
+#[derive(Debug, Clone, Getters)]
+#[get = "pub"]
+pub struct ControlFlowGraph {
+    blocks: Vec<BasicBlock>,
+    edges: Vec<Edge>,
+    /// 每个基本块的直接支配者，入口和不可达的基本块为 `None`
+    idoms: Vec<Option<usize>>,
+}
+
+impl ControlFlowGraph {
+    /// 根据方法的指令和 Code 中的 TryBlock 构建控制流图
+    pub fn new(instructions: &[Instruction], code: &Code) -> Self {
+        let leaders = Self::leaders(instructions, code);
+
+        // 划分基本块
+        let mut blocks: Vec<BasicBlock> = Vec::new();
+        for instruction in instructions {
+            let pc = *instruction.offset();
+            if blocks.is_empty() || leaders.contains(&pc) {
+                blocks.push(BasicBlock {
+                    id: blocks.len(),
+                    start_pc: pc,
+                    end_pc: pc,
+                    instructions: Vec::new(),
+                    predecessors: Vec::new(),
+                    successors: Vec::new(),
+                });
+            }
+            let block = blocks.last_mut().unwrap();
+            block.end_pc = pc + instruction.size();
+            block.instructions.push(instruction.clone());
+        }
+
+        let block_at: HashMap<usize, usize> = blocks.iter().map(|b| (b.start_pc, b.id)).collect();
+
+        let mut edges = Vec::new();
+        for block in &blocks {
+            let Some(last) = block.last_instruction() else {
+                continue;
+            };
+            let next = block_at.get(&block.end_pc).copied();
+            let target = last
+                .branch_target()
+                .and_then(|target| usize::try_from(target).ok())
+                .and_then(|target| block_at.get(&target).copied());
+
+            let mut add = |to: Option<usize>, kind| {
+                if let Some(to) = to {
+                    edges.push(Edge {
+                        from: block.id,
+                        to,
+                        kind,
+                    });
+                }
+            };
+
+            if last.branch_target().is_some() {
+                if last.mnemonic() == "jmp" {
+                    add(target, EdgeKind::Unconditional);
+                } else {
+                    add(target, EdgeKind::True);
+                    add(next, EdgeKind::False);
+                }
+            } else if !TERMINATORS.contains(&last.mnemonic().as_str()) {
+                add(next, EdgeKind::Unconditional);
+            }
+
+            // TryBlock 中的任何一个基本块都可能跳到异常处理块
+            for try_block in code.try_blocks() {
+                if !try_block.contains(block.start_pc as u64) {
+                    continue;
+                }
+                for catch_block in try_block.catch_blocks() {
+                    let handler = block_at.get(&(*catch_block.handler_pc() as usize));
+                    add(handler.copied(), EdgeKind::Exception);
+                }
+            }
+        }
+
+        for edge in &edges {
+            if !blocks[edge.from].successors.contains(&edge.to) {
+                blocks[edge.from].successors.push(edge.to);
+            }
+            if !blocks[edge.to].predecessors.contains(&edge.from) {
+                blocks[edge.to].predecessors.push(edge.from);
+            }
+        }
+
+        let idoms = Self::compute_idoms(&blocks);
+        Self {
+            blocks,
+            edges,
+            idoms,
+        }
+    }
+
+    /// 基本块的起始指令：入口、跳转目标、跳转和终止指令的下一条、TryBlock 边界和异常处理入口
+    fn leaders(instructions: &[Instruction], code: &Code) -> BTreeSet<usize> {
+        let mut leaders = BTreeSet::new();
+        leaders.insert(0);
+        for instruction in instructions {
+            let end_pc = *instruction.offset() + instruction.size();
+            if let Some(target) = instruction.branch_target() {
+                if let Ok(target) = usize::try_from(target) {
+                    leaders.insert(target);
+                }
+                leaders.insert(end_pc);
+            } else if TERMINATORS.contains(&instruction.mnemonic().as_str()) {
+                leaders.insert(end_pc);
+            }
+        }
+        for try_block in code.try_blocks() {
+            leaders.insert(*try_block.start_pc() as usize);
+            leaders.insert(try_block.end_pc() as usize);
+            for catch_block in try_block.catch_blocks() {
+                leaders.insert(*catch_block.handler_pc() as usize);
+            }
+        }
+        leaders
+    }
+
+    /// Cooper-Harvey-Kennedy 迭代算法计算直接支配者
+    fn compute_idoms(blocks: &[BasicBlock]) -> Vec<Option<usize>> {
+        let mut idoms = vec![None; blocks.len()];
+        if blocks.is_empty() {
+            return idoms;
+        }
+
+        // 逆后序
+        let mut order = Vec::new();
+        let mut visited = vec![false; blocks.len()];
+        let mut stack = vec![(0, 0)];
+        visited[0] = true;
+        while let Some((block, i)) = stack.pop() {
+            if let Some(&succ) = blocks[block].successors.get(i) {
+                stack.push((block, i + 1));
+                if !visited[succ] {
+                    visited[succ] = true;
+                    stack.push((succ, 0));
+                }
+            } else {
+                order.push(block);
+            }
+        }
+        order.reverse();
+        let mut rpo = vec![usize::MAX; blocks.len()];
+        for (i, block) in order.iter().enumerate() {
+            rpo[*block] = i;
+        }
+
+        let intersect = |idoms: &[Option<usize>], mut a: usize, mut b: usize| {
+            while a != b {
+                while rpo[a] > rpo[b] {
+                    a = idoms[a].unwrap();
+                }
+                while rpo[b] > rpo[a] {
+                    b = idoms[b].unwrap();
+                }
+            }
+            a
+        };
+
+        idoms[0] = Some(0);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &block in order.iter().skip(1) {
+                let mut new_idom = None;
+                for &pred in &blocks[block].predecessors {
+                    if idoms[pred].is_none() {
+                        continue;
+                    }
+                    new_idom = Some(match new_idom {
+                        None => pred,
+                        Some(idom) => intersect(&idoms, pred, idom),
+                    });
+                }
+                if new_idom.is_some() && idoms[block] != new_idom {
+                    idoms[block] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+
+        idoms[0] = None;
+        idoms
+    }
+
+    /// 基本块的直接支配者
+    pub fn immediate_dominator(&self, block: usize) -> Option<usize> {
+        self.idoms.get(block).copied().flatten()
+    }
+
+    /// 支配 `block` 的所有基本块，从 `block` 自身到入口；不可达的基本块返回空
+    pub fn dominators(&self, block: usize) -> Vec<usize> {
+        if !self.is_reachable(block) {
+            return Vec::new();
+        }
+        let mut result = vec![block];
+        let mut current = block;
+        while let Some(idom) = self.immediate_dominator(current) {
+            result.push(idom);
+            current = idom;
+        }
+        result
+    }
+
+    /// `a` 是否支配 `b`
+    pub fn dominates(&self, a: usize, b: usize) -> bool {
+        self.dominators(b).contains(&a)
+    }
+
+    /// 基本块是否能从入口到达
+    pub fn is_reachable(&self, block: usize) -> bool {
+        block == 0 || self.immediate_dominator(block).is_some()
+    }
+
+    /// 包含 `pc` 处指令的基本块
+    pub fn block_at(&self, pc: usize) -> Option<&BasicBlock> {
+        self.blocks
+            .iter()
+            .find(|block| block.start_pc <= pc && pc < block.end_pc)
+    }
+
+    /// 导出为 Graphviz DOT 格式，每个基本块带有它的反汇编
+    pub fn to_dot(&self, name: &str) -> String {
+        let instructions: Vec<Instruction> = self
+            .blocks
+            .iter()
+            .flat_map(|block| block.instructions.iter().cloned())
+            .collect();
+        let labels = bytecode::branch_labels(&instructions);
+
+        let mut dot = String::new();
+        let _ = writeln!(dot, "digraph \"{}\" {{", escape_dot(name));
+        let _ = writeln!(dot, "    node [shape=box, fontname=\"monospace\"];");
+        for block in &self.blocks {
+            let mut text = format!(
+                "bb{} [{:#06x}, {:#06x})\\l",
+                block.id, block.start_pc, block.end_pc
+            );
+            if let Some(label) = labels.get(&block.start_pc) {
+                let _ = write!(text, "{}:\\l", label);
+            }
+            for instruction in &block.instructions {
+                let _ = write!(text, "{}\\l", escape_dot(&instruction.to_string()));
+            }
+            let _ = writeln!(dot, "    bb{} [label=\"{}\"];", block.id, text);
+        }
+        for edge in &self.edges {
+            match edge.kind.dot_label() {
+                Some(label) => {
+                    let style = if edge.kind == EdgeKind::Exception {
+                        ", style=dashed"
+                    } else {
+                        ""
+                    };
+                    let _ = writeln!(
+                        dot,
+                        "    bb{} -> bb{} [label=\"{}\"{}];",
+                        edge.from, edge.to, label, style
+                    );
+                }
+                None => {
+                    let _ = writeln!(dot, "    bb{} -> bb{};", edge.from, edge.to);
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// 转义 DOT 字符串中的特殊字符
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 
+use crate::annotation::{self, Annotation, AnnotationKind};
 use crate::field::Field;
 use crate::method::Method;
+use crate::region::Region;
 
-use crate::{error, string::ABCString, uint32_t};
+use crate::error::{self, ReadContext};
+use crate::{string::ABCString, uint32_t};
 use getset::Getters;
 use scroll::ctx;
 use scroll::Pread;
@@ -19,7 +22,7 @@ pub struct ForeignClass {
 impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for ForeignClass {
     type Error = error::Error;
     fn try_from_ctx(source: &'a [u8], _: scroll::Endian) -> Result<(Self, usize), Self::Error> {
-        let name = source.pread::<ABCString>(0).unwrap();
+        let name = source.pread::<ABCString>(0)?;
 
         Ok((ForeignClass { name }, source.len()))
     }
@@ -28,6 +31,7 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for ForeignClass {
 #[derive(Debug, Getters)]
 #[get = "pub"]
 pub struct Class {
+    /// 类数据结束的位置，即类的大小，包括所有的字段和方法
     offset: usize,
     /// 类名
     #[get = "pub"]
@@ -37,9 +41,22 @@ pub struct Class {
     access_flags: Vec<String>,
     num_fields: u64,
     num_methods: u64,
-    // class_data: Vec<TaggedValue>,
+    /// 实现的接口，值为 ClassRegionIndex 的索引
+    interfaces: Vec<u16>,
+    /// 源语言，0 为 ECMAScript
+    source_lang: Option<u8>,
+    /// 注解的偏移量
+    runtime_annotation_offs: Vec<uint32_t>,
+    annotation_offs: Vec<uint32_t>,
+    runtime_type_annotation_offs: Vec<uint32_t>,
+    type_annotation_offs: Vec<uint32_t>,
+    /// 解析后的注解，由 AbcFile 加载时填充
+    annotations: Vec<Annotation>,
+    /// 源文件名的偏移量，指向一个 String
+    source_file_off: Option<uint32_t>,
     fields: Vec<Field>,
     // methods: Vec<Method>,
+    /// 方法相对于类起始位置的偏移量 -> 方法
     method_map: HashMap<usize, Method>,
 }
 
@@ -47,29 +64,36 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Class {
     type Error = error::Error;
     fn try_from_ctx(source: &'a [u8], _: scroll::Endian) -> Result<(Self, usize), Self::Error> {
         let mut off = 0;
-        let name = source.pread::<ABCString>(0).unwrap();
+        let name = source.pread::<ABCString>(0)?;
         off += name.length();
 
-        let supper_class_off = source.pread::<uint32_t>(off).unwrap();
+        let supper_class_off = source
+            .pread::<uint32_t>(off)
+            .at(off, "Class.super_class_off")?;
         let mut supper_class = String::new();
         if supper_class_off != 0 {
             let str = source
                 .pread::<ABCString>(supper_class_off as usize)
-                .unwrap();
+                .map_err(|e| e.rebase(supper_class_off as usize))?;
             supper_class = str.str();
         }
         off += 4;
 
         let off = &mut off;
-        let access_flags = Uleb128::read(source, off).unwrap();
+        let access_flags = Uleb128::read(source, off).at(*off, "Class.access_flags")?;
         let access_flags = ClassAccessFlags::parse(access_flags);
-        let num_fields = Uleb128::read(source, off).unwrap();
-        let num_methods = Uleb128::read(source, off).unwrap();
+        let num_fields = Uleb128::read(source, off).at(*off, "Class.num_fields")?;
+        let num_methods = Uleb128::read(source, off).at(*off, "Class.num_methods")?;
 
-        // let mut offset = *off;
-        // TODO: ClassData
+        let mut interfaces = Vec::new();
+        let mut source_lang = None;
+        let mut runtime_annotation_offs = Vec::new();
+        let mut annotation_offs = Vec::new();
+        let mut runtime_type_annotation_offs = Vec::new();
+        let mut type_annotation_offs = Vec::new();
+        let mut source_file_off = None;
         'l: loop {
-            let tag_value = source.pread::<u8>(*off).unwrap();
+            let tag_value = source.pread::<u8>(*off).at(*off, "Class.class_data")?;
             *off += 1;
             match tag_value {
                 0x00 => {
@@ -77,31 +101,48 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Class {
                     break 'l;
                 }
                 0x01 => {
-                    debug!("INTERFACES");
+                    let count = Uleb128::read(source, off).at(*off, "Class.INTERFACES")?;
+                    for _ in 0..count {
+                        let idx = source.pread::<u16>(*off).at(*off, "Class.INTERFACES")?;
+                        *off += 2;
+                        interfaces.push(idx);
+                    }
+                    debug!("INTERFACES -> {:?}", interfaces);
                 }
                 0x02 => {
-                    let data = source.pread::<u8>(*off).unwrap();
+                    let data = source.pread::<u8>(*off).at(*off, "Class.SOURCE_LANG")?;
                     *off += 1;
                     debug!("SOURCE_LANG -> {}", data);
+                    source_lang = Some(data);
                 }
-                0x03 => {
-                    debug!("RUNTIME_ANNOTATION");
-                }
-                0x04 => {
-                    debug!("ANNOTATION");
-                }
-                0x05 => {
-                    debug!("RUNTIME_TYPE_ANNOTATION");
-                }
-                0x06 => {
-                    debug!("TYPE_ANNOTATION");
+                // 注解可以有多个，值都是指向 Annotation 的偏移量
+                0x03..=0x06 => {
+                    let annotation_off = source
+                        .pread::<uint32_t>(*off)
+                        .at(*off, "Class.ANNOTATION")?;
+                    *off += 4;
+                    debug!("ANNOTATION 0x{:02X} -> {}", tag_value, annotation_off);
+                    match tag_value {
+                        0x03 => runtime_annotation_offs.push(annotation_off),
+                        0x04 => annotation_offs.push(annotation_off),
+                        0x05 => runtime_type_annotation_offs.push(annotation_off),
+                        _ => type_annotation_offs.push(annotation_off),
+                    }
                 }
                 0x07 => {
-                    debug!("SOURCE_FILE");
+                    let string_off = source
+                        .pread::<uint32_t>(*off)
+                        .at(*off, "Class.SOURCE_FILE")?;
+                    *off += 4;
+                    debug!("SOURCE_FILE -> {}", string_off);
+                    source_file_off = Some(string_off);
                 }
                 _ => {
-                    tracing::error!("Error! -> UNKNOWN: {}", tag_value);
-                    break 'l;
+                    return Err(error::Error::MalFormed(format!(
+                        "Class: unknown tag 0x{:02X} at {}",
+                        tag_value,
+                        *off - 1
+                    )));
                 }
             }
         }
@@ -109,7 +150,9 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Class {
         let mut offset = *off;
         let mut fields = Vec::new();
         for _ in 0..num_fields {
-            let field = source.pread::<Field>(offset).unwrap();
+            let field = source
+                .pread::<Field>(offset)
+                .map_err(|e| e.rebase(offset))?;
             let size = *field.size();
             offset += size;
             fields.push(field);
@@ -118,13 +161,14 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Class {
         // let mut methods = Vec::new();
         let mut method_map = HashMap::new();
         for _ in 0..num_methods {
-            // TODO: 记录这个offset，并且保存起来，未来建立一个 map
-            let method = source.pread::<Method>(offset).unwrap();
+            let method = source
+                .pread::<Method>(offset)
+                .map_err(|e| e.rebase(offset))?;
 
             let size = *method.size();
+            method_map.insert(offset, method);
             offset += size;
 
-            method_map.insert(offset, method);
             // methods.push(method);
         }
 
@@ -136,6 +180,14 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Class {
                 access_flags,
                 num_fields,
                 num_methods,
+                interfaces,
+                source_lang,
+                runtime_annotation_offs,
+                annotation_offs,
+                runtime_type_annotation_offs,
+                type_annotation_offs,
+                annotations: Vec::new(),
+                source_file_off,
                 fields,
                 // methods,
                 method_map,
@@ -153,6 +205,42 @@ impl Class {
     pub fn get_method(&self, offset: usize) -> Option<&Method> {
         self.method_map.get(&offset)
     }
+
+    /// 解析类及其字段、方法的注解
+    pub(crate) fn resolve_annotations(&mut self, source: &[u8], region: &Region) {
+        self.annotations = annotation::parse_annotations(
+            source,
+            region,
+            [
+                (AnnotationKind::Runtime, &self.runtime_annotation_offs),
+                (AnnotationKind::Plain, &self.annotation_offs),
+                (
+                    AnnotationKind::RuntimeType,
+                    &self.runtime_type_annotation_offs,
+                ),
+                (AnnotationKind::Type, &self.type_annotation_offs),
+            ],
+        );
+        for field in self.fields.iter_mut() {
+            field.resolve_annotations(source, region);
+        }
+        for method in self.method_map.values_mut() {
+            method.resolve_annotations(source, region);
+        }
+    }
+
+    /// 类及其字段、方法是否有注解
+    pub(crate) fn has_annotations(&self) -> bool {
+        !(self.runtime_annotation_offs.is_empty()
+            && self.annotation_offs.is_empty()
+            && self.runtime_type_annotation_offs.is_empty()
+            && self.type_annotation_offs.is_empty())
+            || self.fields.iter().any(|field| field.has_annotations())
+            || self
+                .method_map
+                .values()
+                .any(|method| method.has_annotations())
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -1,34 +1,49 @@
 use getset::Getters;
-use scroll::{ctx, Uleb128};
+use scroll::{ctx, Pread, Uleb128};
 
-use crate::error;
+use crate::error::{self, ReadContext};
+use crate::region::{Region, TypeRef};
 
-// TODO: 解析 `TryBlock`
-#[allow(dead_code)]
 #[derive(Debug, Getters, Default)]
 #[get = "pub"]
-struct TryBlock {
+pub struct TryBlock {
     /// TryBlock的第一条指令距离其所在Code的instructions的起始位置的偏移量。
     start_pc: u64,
     /// TryBlock的大小，以字节为单位。
     length: u64,
-    /// 与TryBlock关联的CatchBlock的数量，值为1。
+    /// 与TryBlock关联的CatchBlock的数量。
     num_catches: u64,
-    /// 与TryBlock关联的CatchBlock的数组，数组中有且仅有一个可以捕获所有类型的异常的CatchBlock。
-    catch_blocks: Vec<(CatchBlock, usize)>,
+    /// 与TryBlock关联的CatchBlock的数组，最多有一个可以捕获所有类型的异常的CatchBlock。
+    catch_blocks: Vec<CatchBlock>,
+}
+
+impl TryBlock {
+    /// TryBlock 之后第一条指令的偏移量
+    pub fn end_pc(&self) -> u64 {
+        self.start_pc + self.length
+    }
+
+    /// 判断指令是否在 TryBlock 中
+    pub fn contains(&self, pc: u64) -> bool {
+        self.start_pc <= pc && pc < self.end_pc()
+    }
 }
 
 impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for TryBlock {
     type Error = error::Error;
     fn try_from_ctx(source: &'a [u8], _: scroll::Endian) -> Result<(Self, usize), Self::Error> {
         let off = &mut 0;
-        let start_pc = Uleb128::read(source, off).unwrap();
-        let length = Uleb128::read(source, off).unwrap();
-        let num_catches = Uleb128::read(source, off).unwrap();
+        let start_pc = Uleb128::read(source, off).at(*off, "TryBlock.start_pc")?;
+        let length = Uleb128::read(source, off).at(*off, "TryBlock.length")?;
+        let num_catches = Uleb128::read(source, off).at(*off, "TryBlock.num_catches")?;
 
-        let catch_blocks = (0..num_catches)
-            .map(|_| CatchBlock::try_from_ctx(source, scroll::Endian::Little))
-            .collect::<Result<Vec<_>, _>>()?;
+        let mut catch_blocks = Vec::new();
+        for _ in 0..num_catches {
+            let catch_block = source
+                .gread::<CatchBlock>(off)
+                .map_err(|e| e.rebase(*off))?;
+            catch_blocks.push(catch_block);
+        }
 
         Ok((
             TryBlock {
@@ -37,39 +52,60 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for TryBlock {
                 num_catches,
                 catch_blocks,
             },
-            source.len(),
+            *off,
         ))
     }
 }
 
-// TODO: 解析 `CatchBlock`
-#[allow(dead_code)]
 #[derive(Debug, Getters, Default)]
 #[get = "pub"]
-struct CatchBlock {
-    /// 值是0，表示此CatchBlock块捕获了所有类型的异常。
+pub struct CatchBlock {
+    /// 值是0，表示此CatchBlock块捕获了所有类型的异常；否则值减一是 ClassRegionIndex 的索引。
     type_idx: u64,
     /// 异常处理逻辑的第一条指令的程序计数器。
     handler_pc: u64,
     /// 此CatchBlock的大小，以字节为单位。
-    catch_type: u64,
+    code_size: u64,
+}
+
+impl CatchBlock {
+    /// 是否捕获所有类型的异常
+    pub fn is_catch_all(&self) -> bool {
+        self.type_idx == 0
+    }
+
+    /// 异常处理逻辑之后第一条指令的偏移量
+    pub fn handler_end_pc(&self) -> u64 {
+        self.handler_pc + self.code_size
+    }
+
+    /// 捕获的异常类型，`None` 表示捕获所有类型的异常
+    pub fn catch_type(&self, region: &Region) -> Result<Option<TypeRef>, error::Error> {
+        if self.is_catch_all() {
+            return Ok(None);
+        }
+
+        let idx = (self.type_idx - 1) as usize;
+        let type_ref = region.get_type(idx).at(idx, "CatchBlock.type_idx")?;
+        Ok(Some(type_ref))
+    }
 }
 
 impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for CatchBlock {
     type Error = error::Error;
     fn try_from_ctx(source: &'a [u8], _: scroll::Endian) -> Result<(Self, usize), Self::Error> {
         let off = &mut 0;
-        let type_idx = Uleb128::read(source, off).unwrap();
-        let handler_pc = Uleb128::read(source, off).unwrap();
-        let catch_type = Uleb128::read(source, off).unwrap();
+        let type_idx = Uleb128::read(source, off).at(*off, "CatchBlock.type_idx")?;
+        let handler_pc = Uleb128::read(source, off).at(*off, "CatchBlock.handler_pc")?;
+        let code_size = Uleb128::read(source, off).at(*off, "CatchBlock.code_size")?;
 
         Ok((
             CatchBlock {
                 type_idx,
                 handler_pc,
-                catch_type,
+                code_size,
             },
-            source.len(),
+            *off,
         ))
     }
 }
@@ -87,8 +123,59 @@ pub struct Code {
     tries_size: u64,
     /// 所有指令的数组。
     instructions: Vec<u8>,
+    /// 指令相对于 Code 起始位置的偏移量。
+    instructions_off: usize,
     /// 一个数组，数组中每一个元素都是TryBlock类型。
-    try_blocks: Vec<(TryBlock, usize)>,
+    try_blocks: Vec<TryBlock>,
+}
+
+impl Code {
+    /// 指令 `pc` 处的异常处理标签，格式与 ark_disasm 一致，如：try_begin_label_0
+    pub fn labels_at(&self, pc: u64) -> Vec<String> {
+        let mut labels = Vec::new();
+        for (i, try_block) in self.try_blocks.iter().enumerate() {
+            if try_block.start_pc == pc {
+                labels.push(format!("try_begin_label_{}", i));
+            }
+            if try_block.end_pc() == pc {
+                labels.push(format!("try_end_label_{}", i));
+            }
+            for (j, catch_block) in try_block.catch_blocks.iter().enumerate() {
+                if catch_block.handler_pc == pc {
+                    labels.push(format!("handler_begin_label_{}_{}", i, j));
+                }
+                if catch_block.handler_end_pc() == pc {
+                    labels.push(format!("handler_end_label_{}_{}", i, j));
+                }
+            }
+        }
+        labels
+    }
+
+    /// 异常处理表，格式与 ark_disasm 一致，如：
+    /// `.catchall try_begin_label_0, try_end_label_0, handler_begin_label_0_0, handler_end_label_0_0`
+    pub fn catch_directives(
+        &self,
+        source: &[u8],
+        region: &Region,
+    ) -> Result<Vec<String>, error::Error> {
+        let mut directives = Vec::new();
+        for (i, try_block) in self.try_blocks.iter().enumerate() {
+            for (j, catch_block) in try_block.catch_blocks.iter().enumerate() {
+                let labels = format!(
+                    "try_begin_label_{i}, try_end_label_{i}, handler_begin_label_{i}_{j}, handler_end_label_{i}_{j}"
+                );
+                let directive = match catch_block.catch_type(region)? {
+                    Some(catch_type) => {
+                        format!(".catch {}, {}", catch_type.name(source)?, labels)
+                    }
+                    None => format!(".catchall {}", labels),
+                };
+                directives.push(directive);
+            }
+        }
+        Ok(directives)
+    }
 }
 
 impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Code {
@@ -96,10 +183,10 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Code {
     fn try_from_ctx(source: &'a [u8], _: scroll::Endian) -> Result<(Self, usize), Self::Error> {
         let off = &mut 0;
 
-        let num_regs = Uleb128::read(source, off).unwrap();
-        let num_args = Uleb128::read(source, off).unwrap();
-        let code_size = Uleb128::read(source, off).unwrap();
-        let tries_size = Uleb128::read(source, off).unwrap();
+        let num_regs = Uleb128::read(source, off).at(*off, "Code.num_regs")?;
+        let num_args = Uleb128::read(source, off).at(*off, "Code.num_args")?;
+        let code_size = Uleb128::read(source, off).at(*off, "Code.code_size")?;
+        let tries_size = Uleb128::read(source, off).at(*off, "Code.tries_size")?;
 
         tracing::debug!(
             "num_regs: {}, num_args: {}, code_size: {}, tries_size: {}",
@@ -109,12 +196,20 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Code {
             tries_size
         );
 
-        let instructions = source[*off..*off + code_size as usize].to_vec();
+        let instructions_off = *off;
+        let instructions = source
+            .get(*off..)
+            .and_then(|data| data.get(..code_size as usize))
+            .at(*off, "Code.instructions")?
+            .to_vec();
         *off += code_size as usize;
 
-        let try_blocks = (0..tries_size)
-            .map(|_| TryBlock::try_from_ctx(source, scroll::Endian::Little))
-            .collect::<Result<Vec<_>, _>>()?;
+        // TryBlock 紧跟在指令之后
+        let mut try_blocks = Vec::new();
+        for _ in 0..tries_size {
+            let try_block = source.gread::<TryBlock>(off).map_err(|e| e.rebase(*off))?;
+            try_blocks.push(try_block);
+        }
 
         Ok((
             Code {
@@ -123,9 +218,10 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Code {
                 code_size,
                 tries_size,
                 instructions,
+                instructions_off,
                 try_blocks,
             },
-            source.len(),
+            *off,
         ))
     }
 }
@@ -0,0 +1,460 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use getset::Getters;
+
+use crate::bytecode::{Instruction, Operand};
+use crate::cfg::{ControlFlowGraph, EdgeKind};
+
+/// 指令对累加器 acc 的隐式访问
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccAccess {
+    None,
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl AccAccess {
+    pub fn reads(&self) -> bool {
+        matches!(self, AccAccess::Read | AccAccess::ReadWrite)
+    }
+
+    pub fn writes(&self) -> bool {
+        matches!(self, AccAccess::Write | AccAccess::ReadWrite)
+    }
+
+    /// 根据助记符查询指令对 acc 的访问，未列出的指令既读又写 acc
+    pub fn of(mnemonic: &str) -> Self {
+        if ACC_NONE.contains(&mnemonic) || mnemonic == "jmp" {
+            return AccAccess::None;
+        }
+        // 除 jmp 外的跳转指令都要判断 acc
+        if ACC_READ.contains(&mnemonic) || mnemonic.starts_with('j') {
+            return AccAccess::Read;
+        }
+        if ACC_WRITE.contains(&mnemonic) {
+            return AccAccess::Write;
+        }
+        if mnemonic.starts_with("deprecated.") && !ACC_READ_WRITE.contains(&mnemonic) {
+            // 旧的指令集中，操作数都放在寄存器里，结果写到 acc
+            return AccAccess::Write;
+        }
+        AccAccess::ReadWrite
+    }
+}
+
+/// 不访问 acc 的指令
+const ACC_NONE: &[&str] = &[
+    "returnundefined",
+    "poplexenv",
+    "debugger",
+    "nop",
+    "mov",
+    ".byte",
+    "throw.notexists",
+    "throw.patternnoncoercible",
+    "throw.deletesuperproperty",
+    "throw.constassignment",
+    "throw.ifnotobject",
+    "throw.undefinedifhole",
+    "callruntime.createprivateproperty",
+    "callruntime.newsendableenv",
+    "callruntime.widenewsendableenv",
+    "deprecated.poplexenv",
+    "deprecated.setobjectwithproto",
+    "deprecated.stlexvar",
+];
+
+/// 只读 acc 的指令，除 jmp 外的跳转指令也只读 acc
+const ACC_READ: &[&str] = &[
+    "sta",
+    "return",
+    "throw",
+    "throw.ifsupernotcorrectcall",
+    "throw.undefinedifholewithname",
+    "stobjbyvalue",
+    "stobjbyindex",
+    "stobjbyname",
+    "stlexvar",
+    "trystglobalbyname",
+    "stconsttoglobalrecord",
+    "sttoglobalrecord",
+    "stthisbyname",
+    "stthisbyvalue",
+    "stglobalvar",
+    "stmodulevar",
+    "setobjectwithproto",
+    "stownbyvalue",
+    "stownbyindex",
+    "stownbyname",
+    "stownbyvaluewithnameset",
+    "stownbynamewithnameset",
+    "stsuperbyvalue",
+    "stsuperbyname",
+    "stprivateproperty",
+    "setgeneratorstate",
+    "definefieldbyname",
+    "definepropertybyname",
+    "callruntime.notifyconcurrentresult",
+    "callruntime.definefieldbyvalue",
+    "callruntime.definefieldbyindex",
+    "callruntime.defineprivateproperty",
+    "callruntime.stsendablevar",
+    "callruntime.widestsendablevar",
+    "wide.stobjbyindex",
+    "wide.stownbyindex",
+    "wide.stlexvar",
+    "wide.stmodulevar",
+    "wide.stpatchvar",
+    "deprecated.stmodulevar",
+    "deprecated.stconsttoglobalrecord",
+    "deprecated.stlettoglobalrecord",
+    "deprecated.stclasstoglobalrecord",
+];
+
+/// 只写 acc 的指令
+const ACC_WRITE: &[&str] = &[
+    "lda",
+    "lda.str",
+    "ldai",
+    "fldai",
+    "ldundefined",
+    "ldnull",
+    "ldtrue",
+    "ldfalse",
+    "ldnan",
+    "ldinfinity",
+    "ldglobal",
+    "ldnewtarget",
+    "ldthis",
+    "ldhole",
+    "ldsymbol",
+    "ldfunction",
+    "ldbigint",
+    "ldlexvar",
+    "ldglobalvar",
+    "ldthisbyname",
+    "tryldglobalbyname",
+    "ldlocalmodulevar",
+    "ldexternalmodulevar",
+    "getmodulenamespace",
+    "getunmappedargs",
+    "getnextpropname",
+    "copyrestargs",
+    "createemptyobject",
+    "createemptyarray",
+    "createarraywithbuffer",
+    "createobjectwithbuffer",
+    "createregexpwithliteral",
+    "createobjectwithexcludedkeys",
+    "creategeneratorobj",
+    "createasyncgeneratorobj",
+    "createiterresultobj",
+    "asyncgeneratorresolve",
+    "asyncfunctionenter",
+    "closeiterator",
+    "newobjrange",
+    "newlexenv",
+    "newlexenvwithname",
+    "definefunc",
+    "defineclasswithbuffer",
+    "supercallthisrange",
+    "callruntime.definesendableclass",
+    "callruntime.ldsendableclass",
+    "callruntime.ldsendableexternalmodulevar",
+    "callruntime.wideldsendableexternalmodulevar",
+    "callruntime.ldsendablevar",
+    "callruntime.wideldsendablevar",
+    "callruntime.ldlazymodulevar",
+    "callruntime.wideldlazymodulevar",
+    "callruntime.ldlazysendablemodulevar",
+    "callruntime.wideldlazysendablemodulevar",
+    "wide.createobjectwithexcludedkeys",
+    "wide.newobjrange",
+    "wide.newlexenv",
+    "wide.newlexenvwithname",
+    "wide.supercallthisrange",
+    "wide.copyrestargs",
+    "wide.ldlexvar",
+    "wide.getmodulenamespace",
+    "wide.ldlocalmodulevar",
+    "wide.ldexternalmodulevar",
+    "wide.ldpatchvar",
+];
+
+/// 旧的指令集中既读又写 acc 的指令
+const ACC_READ_WRITE: &[&str] = &["deprecated.createobjecthavingmethod"];
+
+/// 范围指令中，寄存器的数量 = 立即数 + 额外的寄存器数量
+fn range_extra(mnemonic: &str) -> Option<u64> {
+    let extra = match mnemonic.trim_start_matches("wide.") {
+        "newobjrange" | "callrange" | "supercallthisrange" | "supercallarrowrange" => 0,
+        // this 不计入参数数量
+        "callthisrange" => 1,
+        "createobjectwithexcludedkeys" => 1,
+        "deprecated.callrange" | "deprecated.callthisrange" => 1,
+        _ => return None,
+    };
+    Some(extra)
+}
+
+/// 数据的存放位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Location {
+    Acc,
+    Register(u16),
+}
+
+/// 指令读写的位置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Getters)]
+#[get = "pub"]
+pub struct Effects {
+    reads: Vec<Location>,
+    writes: Vec<Location>,
+}
+
+impl Effects {
+    /// 计算指令读写的 acc 和寄存器，范围指令会展开成连续的寄存器
+    pub fn of(instruction: &Instruction) -> Self {
+        let mnemonic = instruction.mnemonic().as_str();
+        let mut effects = Effects::default();
+
+        let registers: Vec<u16> = instruction
+            .operands()
+            .iter()
+            .filter_map(|operand| match operand {
+                Operand::Register(reg) => Some(*reg),
+                _ => None,
+            })
+            .collect();
+
+        match mnemonic {
+            // mov 的第一个寄存器是目标
+            "mov" => {
+                effects.writes.push(Location::Register(registers[0]));
+                effects.reads.push(Location::Register(registers[1]));
+            }
+            "sta" => effects.writes.push(Location::Register(registers[0])),
+            _ => {
+                let count = instruction
+                    .operands()
+                    .iter()
+                    .find_map(|operand| match operand {
+                        Operand::Imm(count) => Some(*count),
+                        _ => None,
+                    });
+                match (range_extra(mnemonic), registers.split_last(), count) {
+                    (Some(extra), Some((&first, rest)), Some(count)) => {
+                        for reg in rest {
+                            effects.reads.push(Location::Register(*reg));
+                        }
+                        for i in 0..count + extra {
+                            effects
+                                .reads
+                                .push(Location::Register(first.wrapping_add(i as u16)));
+                        }
+                    }
+                    _ => {
+                        for reg in registers {
+                            effects.reads.push(Location::Register(reg));
+                        }
+                    }
+                }
+            }
+        }
+
+        let acc = AccAccess::of(mnemonic);
+        if acc.reads() {
+            effects.reads.push(Location::Acc);
+        }
+        if acc.writes() {
+            effects.writes.push(Location::Acc);
+        }
+        effects
+    }
+}
+
+/// 定义的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DefSite {
+    /// 方法入口处的值，如参数和 this
+    Entry,
+    /// `pc` 处的指令
+    Instruction(usize),
+    /// 进入异常处理块时，异常对象被放到 acc 中，值为处理块的起始 pc
+    Exception(usize),
+}
+
+type DefState = BTreeMap<Location, BTreeSet<DefSite>>;
+
+fn merge(into: &mut DefState, from: &DefState) {
+    for (location, sites) in from {
+        into.entry(*location).or_default().extend(sites);
+    }
+}
+
+/// 到达定值分析，记录每条指令执行前每个位置可能的定义。
+///
+/// 方法中访问过的每个位置在入口处都有一个 `DefSite::Entry` 定义，表示进入方法时的值，如参数。
+#[derive(Debug, Clone, Default)]
+pub struct ReachingDefinitions {
+    before: BTreeMap<usize, DefState>,
+}
+
+impl ReachingDefinitions {
+    pub fn new(cfg: &ControlFlowGraph) -> Self {
+        let blocks = cfg.blocks();
+        let effects: Vec<Vec<Effects>> = blocks
+            .iter()
+            .map(|block| block.instructions().iter().map(Effects::of).collect())
+            .collect();
+
+        // 入口块的 IN 中，每个访问过的位置都有一个来自方法入口的定义
+        let mut entry_state = DefState::new();
+        for effects in effects.iter().flatten() {
+            for location in effects.reads().iter().chain(effects.writes()) {
+                entry_state.insert(*location, BTreeSet::from([DefSite::Entry]));
+            }
+        }
+        let entry = blocks
+            .iter()
+            .find(|block| *block.start_pc() == 0)
+            .map(|block| *block.id());
+
+        let mut block_in = vec![DefState::new(); blocks.len()];
+        let mut block_out = vec![DefState::new(); blocks.len()];
+        // 块内出现过的所有定义，异常可能在块内任意位置发生
+        let mut block_all = vec![DefState::new(); blocks.len()];
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for block in blocks {
+                let id = *block.id();
+                let mut state = if Some(id) == entry {
+                    entry_state.clone()
+                } else {
+                    DefState::new()
+                };
+                let mut is_handler = false;
+                for edge in cfg.edges().iter().filter(|edge| *edge.to() == id) {
+                    let from = *edge.from();
+                    if *edge.kind() == EdgeKind::Exception {
+                        merge(&mut state, &block_in[from]);
+                        merge(&mut state, &block_all[from]);
+                        is_handler = true;
+                    } else {
+                        merge(&mut state, &block_out[from]);
+                    }
+                }
+                if is_handler {
+                    state.insert(
+                        Location::Acc,
+                        BTreeSet::from([DefSite::Exception(*block.start_pc())]),
+                    );
+                }
+
+                let input = state.clone();
+                let mut all = DefState::new();
+                for (instruction, effects) in block.instructions().iter().zip(&effects[id]) {
+                    let site = DefSite::Instruction(*instruction.offset());
+                    for location in effects.writes() {
+                        state.insert(*location, BTreeSet::from([site]));
+                        all.entry(*location).or_default().insert(site);
+                    }
+                }
+
+                if input != block_in[id] || state != block_out[id] {
+                    block_in[id] = input;
+                    block_out[id] = state;
+                    block_all[id] = all;
+                    changed = true;
+                }
+            }
+        }
+
+        let mut before = BTreeMap::new();
+        for block in blocks {
+            let id = *block.id();
+            let mut state = block_in[id].clone();
+            for (instruction, effects) in block.instructions().iter().zip(&effects[id]) {
+                let pc = *instruction.offset();
+                before.insert(pc, state.clone());
+                for location in effects.writes() {
+                    state.insert(*location, BTreeSet::from([DefSite::Instruction(pc)]));
+                }
+            }
+        }
+
+        Self { before }
+    }
+
+    /// 执行 `pc` 处的指令前，到达 `location` 的定义
+    pub fn at(&self, pc: usize, location: Location) -> Vec<DefSite> {
+        self.before
+            .get(&pc)
+            .and_then(|state| state.get(&location))
+            .map(|sites| sites.iter().copied().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// 定义-使用链与使用-定义链
+#[derive(Debug, Clone, Default, Getters)]
+#[get = "pub"]
+pub struct DefUseChains {
+    /// 每个使用，即 (pc, 位置)，到达它的定义
+    use_defs: BTreeMap<(usize, Location), Vec<DefSite>>,
+    /// 每个定义，即 (定义位置, 位置)，使用它的指令的 pc
+    def_uses: BTreeMap<(DefSite, Location), Vec<usize>>,
+}
+
+impl DefUseChains {
+    pub fn new(cfg: &ControlFlowGraph) -> Self {
+        let reaching = ReachingDefinitions::new(cfg);
+        let mut chains = DefUseChains::default();
+
+        for block in cfg.blocks() {
+            for instruction in block.instructions() {
+                let pc = *instruction.offset();
+                for location in Effects::of(instruction).writes() {
+                    chains
+                        .def_uses
+                        .entry((DefSite::Instruction(pc), *location))
+                        .or_default();
+                }
+                for location in Effects::of(instruction).reads() {
+                    let sites = reaching.at(pc, *location);
+                    for site in &sites {
+                        let uses = chains.def_uses.entry((*site, *location)).or_default();
+                        if !uses.contains(&pc) {
+                            uses.push(pc);
+                        }
+                    }
+                    chains.use_defs.insert((pc, *location), sites);
+                }
+            }
+        }
+
+        for uses in chains.def_uses.values_mut() {
+            uses.sort_unstable();
+        }
+        chains
+    }
+
+    /// `pc` 处的指令读取 `location` 时可能的定义，`DefSite::Entry` 表示值来自方法入口
+    pub fn definitions(&self, pc: usize, location: Location) -> &[DefSite] {
+        self.use_defs
+            .get(&(pc, location))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// 使用 `site` 处对 `location` 的定义的指令
+    pub fn uses(&self, site: DefSite, location: Location) -> &[usize] {
+        self.def_uses
+            .get(&(site, location))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
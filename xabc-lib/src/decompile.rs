@@ -0,0 +1,1293 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+use crate::bytecode::{self, Instruction, Operand};
+use crate::cfg::{ControlFlowGraph, EdgeKind};
+use crate::code::Code;
+use crate::dataflow::{AccAccess, DefSite, DefUseChains, Effects, Location};
+
+/// 表达式
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// 变量、全局变量或其他可以直接写出的名字
+    Name(String),
+    Number(String),
+    Str(String),
+    Member(Box<Expr>, String),
+    Index(Box<Expr>, Box<Expr>),
+    Unary(&'static str, Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+    Ternary(Box<Expr>, Box<Expr>, Box<Expr>),
+    Call(Box<Expr>, Vec<Expr>),
+    New(Box<Expr>, Vec<Expr>),
+    Function(String),
+    Class(String, Option<Box<Expr>>),
+    /// 没有对应语法的指令，按函数调用的形式输出
+    Intrinsic(String, Vec<Expr>),
+    /// 字面量数组等，原样输出
+    Raw(String),
+}
+
+impl Expr {
+    fn name(name: &str) -> Expr {
+        Expr::Name(name.to_string())
+    }
+
+    /// 可以重复求值的简单表达式
+    fn is_simple(&self) -> bool {
+        matches!(
+            self,
+            Expr::Name(_) | Expr::Number(_) | Expr::Str(_) | Expr::Raw(_)
+        )
+    }
+
+    /// 求值时可能有副作用
+    fn has_side_effects(&self) -> bool {
+        match self {
+            Expr::Call(..) | Expr::New(..) | Expr::Intrinsic(..) | Expr::Class(..) => true,
+            Expr::Name(_) | Expr::Number(_) | Expr::Str(_) | Expr::Raw(_) | Expr::Function(_) => {
+                false
+            }
+            Expr::Member(obj, _) => obj.has_side_effects(),
+            Expr::Index(obj, key) => obj.has_side_effects() || key.has_side_effects(),
+            Expr::Unary(_, e) => e.has_side_effects(),
+            Expr::Binary(_, l, r) => l.has_side_effects() || r.has_side_effects(),
+            Expr::Ternary(c, t, f) => {
+                c.has_side_effects() || t.has_side_effects() || f.has_side_effects()
+            }
+        }
+    }
+
+    /// 表达式中是否引用了变量 `name`
+    fn references(&self, name: &str) -> bool {
+        match self {
+            Expr::Name(n) => n == name,
+            Expr::Number(_) | Expr::Str(_) | Expr::Raw(_) | Expr::Function(_) => false,
+            Expr::Member(obj, _) => obj.references(name),
+            Expr::Index(obj, key) => obj.references(name) || key.references(name),
+            Expr::Unary(_, e) => e.references(name),
+            Expr::Binary(_, l, r) => l.references(name) || r.references(name),
+            Expr::Ternary(c, t, f) => {
+                c.references(name) || t.references(name) || f.references(name)
+            }
+            Expr::Class(_, parent) => parent.as_ref().is_some_and(|p| p.references(name)),
+            Expr::Call(callee, args) | Expr::New(callee, args) => {
+                callee.references(name) || args.iter().any(|a| a.references(name))
+            }
+            Expr::Intrinsic(_, args) => args.iter().any(|a| a.references(name)),
+        }
+    }
+
+    /// 取反，只化简不会改变语义的情况
+    fn negate(self) -> Expr {
+        match self {
+            Expr::Unary("!", e) => *e,
+            Expr::Binary("==", l, r) => Expr::Binary("!=", l, r),
+            Expr::Binary("!=", l, r) => Expr::Binary("==", l, r),
+            Expr::Binary("===", l, r) => Expr::Binary("!==", l, r),
+            Expr::Binary("!==", l, r) => Expr::Binary("===", l, r),
+            e => Expr::Unary("!", Box::new(e)),
+        }
+    }
+
+    fn is_atomic(&self) -> bool {
+        !matches!(
+            self,
+            Expr::Unary(..)
+                | Expr::Binary(..)
+                | Expr::Ternary(..)
+                | Expr::New(..)
+                | Expr::Class(..)
+        )
+    }
+
+    fn fmt_operand(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_atomic() {
+            write!(f, "{}", self)
+        } else {
+            write!(f, "({})", self)
+        }
+    }
+}
+
+fn fmt_args(f: &mut fmt::Formatter<'_>, args: &[Expr]) -> fmt::Result {
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", arg)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Name(name) | Expr::Number(name) | Expr::Raw(name) => write!(f, "{}", name),
+            Expr::Str(value) => write!(f, "{:?}", value),
+            Expr::Member(obj, name) => {
+                obj.fmt_operand(f)?;
+                write!(f, ".{}", name)
+            }
+            Expr::Index(obj, key) => {
+                obj.fmt_operand(f)?;
+                write!(f, "[{}]", key)
+            }
+            Expr::Unary(op, e) => {
+                write!(f, "{}", op)?;
+                if op.chars().all(char::is_alphabetic) {
+                    write!(f, " ")?;
+                }
+                e.fmt_operand(f)
+            }
+            Expr::Binary(op, l, r) => {
+                l.fmt_operand(f)?;
+                write!(f, " {} ", op)?;
+                r.fmt_operand(f)
+            }
+            Expr::Ternary(c, t, e) => {
+                c.fmt_operand(f)?;
+                write!(f, " ? ")?;
+                t.fmt_operand(f)?;
+                write!(f, " : ")?;
+                e.fmt_operand(f)
+            }
+            Expr::Call(callee, args) => {
+                callee.fmt_operand(f)?;
+                write!(f, "(")?;
+                fmt_args(f, args)?;
+                write!(f, ")")
+            }
+            Expr::New(callee, args) => {
+                write!(f, "new ")?;
+                callee.fmt_operand(f)?;
+                write!(f, "(")?;
+                fmt_args(f, args)?;
+                write!(f, ")")
+            }
+            Expr::Function(name) => write!(f, "function {}", name),
+            Expr::Class(name, Some(parent)) => write!(f, "class {} extends {}", name, parent),
+            Expr::Class(name, None) => write!(f, "class {}", name),
+            Expr::Intrinsic(name, args) => {
+                write!(f, "{}(", name)?;
+                fmt_args(f, args)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// 语句
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Expr(Expr),
+    Assign(Expr, Expr),
+    Return(Option<Expr>),
+    Throw(Expr),
+    If(Expr, Vec<Stmt>, Vec<Stmt>),
+    While(Expr, Vec<Stmt>),
+    DoWhile(Vec<Stmt>, Expr),
+    /// `while (true)`
+    Loop(Vec<Stmt>),
+    Break,
+    Continue,
+    /// 每个 catch 的异常类型和处理代码，`None` 表示捕获所有异常
+    Try(Vec<Stmt>, Vec<(Option<String>, Vec<Stmt>)>),
+    /// 无法结构化的跳转
+    Goto(String),
+    Label(String),
+}
+
+impl Stmt {
+    fn fmt_block(f: &mut fmt::Formatter<'_>, stmts: &[Stmt], indent: usize) -> fmt::Result {
+        for stmt in stmts {
+            stmt.fmt_indent(f, indent)?;
+        }
+        Ok(())
+    }
+
+    fn fmt_indent(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        let pad = "    ".repeat(indent);
+        match self {
+            Stmt::Expr(e) => writeln!(f, "{}{};", pad, e),
+            Stmt::Assign(target, value) => writeln!(f, "{}{} = {};", pad, target, value),
+            Stmt::Return(Some(e)) => writeln!(f, "{}return {};", pad, e),
+            Stmt::Return(None) => writeln!(f, "{}return;", pad),
+            Stmt::Throw(e) => writeln!(f, "{}throw {};", pad, e),
+            Stmt::If(cond, then, otherwise) => {
+                writeln!(f, "{}if ({}) {{", pad, cond)?;
+                Stmt::fmt_block(f, then, indent + 1)?;
+                if otherwise.is_empty() {
+                    return writeln!(f, "{}}}", pad);
+                }
+                // else if 不再嵌套一层
+                if let [nested @ Stmt::If(..)] = otherwise.as_slice() {
+                    write!(f, "{}}} else ", pad)?;
+                    let text = format!("{}", Indented(nested, indent));
+                    return write!(f, "{}", text.trim_start());
+                }
+                writeln!(f, "{}}} else {{", pad)?;
+                Stmt::fmt_block(f, otherwise, indent + 1)?;
+                writeln!(f, "{}}}", pad)
+            }
+            Stmt::While(cond, body) => {
+                writeln!(f, "{}while ({}) {{", pad, cond)?;
+                Stmt::fmt_block(f, body, indent + 1)?;
+                writeln!(f, "{}}}", pad)
+            }
+            Stmt::DoWhile(body, cond) => {
+                writeln!(f, "{}do {{", pad)?;
+                Stmt::fmt_block(f, body, indent + 1)?;
+                writeln!(f, "{}}} while ({});", pad, cond)
+            }
+            Stmt::Loop(body) => {
+                writeln!(f, "{}while (true) {{", pad)?;
+                Stmt::fmt_block(f, body, indent + 1)?;
+                writeln!(f, "{}}}", pad)
+            }
+            Stmt::Break => writeln!(f, "{}break;", pad),
+            Stmt::Continue => writeln!(f, "{}continue;", pad),
+            Stmt::Try(body, handlers) => {
+                writeln!(f, "{}try {{", pad)?;
+                Stmt::fmt_block(f, body, indent + 1)?;
+                for (catch_type, handler) in handlers {
+                    match catch_type {
+                        Some(catch_type) => {
+                            writeln!(f, "{}}} catch ({}: {}) {{", pad, CATCH_VAR, catch_type)?
+                        }
+                        None => writeln!(f, "{}}} catch ({}) {{", pad, CATCH_VAR)?,
+                    }
+                    Stmt::fmt_block(f, handler, indent + 1)?;
+                }
+                writeln!(f, "{}}}", pad)
+            }
+            Stmt::Goto(label) => writeln!(f, "{}goto {};", pad, label),
+            Stmt::Label(label) => writeln!(f, "{}:", label),
+        }
+    }
+}
+
+struct Indented<'a>(&'a Stmt, usize);
+
+impl fmt::Display for Indented<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_indent(f, self.1)
+    }
+}
+
+impl fmt::Display for Stmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indent(f, 0)
+    }
+}
+
+/// 异常处理块中异常对象的名字
+const CATCH_VAR: &str = "e";
+/// 跨基本块传递的 acc
+const ACC: &str = "acc";
+
+/// 反编译得到的函数
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Vec<Stmt>,
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "function {}({}) {{", self.name, self.params.join(", "))?;
+        Stmt::fmt_block(f, &self.body, 1)?;
+        writeln!(f, "}}")
+    }
+}
+
+/// 基本块的出口
+#[derive(Debug, Clone)]
+enum Exit {
+    /// 顺序执行，或者以 return、throw 结束
+    Next,
+    Jump(usize),
+    /// 条件成立时跳到目标处
+    Branch(Expr, usize),
+}
+
+#[derive(Debug, Clone)]
+struct LiftedBlock {
+    start_pc: usize,
+    end_pc: usize,
+    stmts: Vec<Stmt>,
+    exit: Exit,
+}
+
+/// 从 "类名->方法名(参数)返回值" 格式的签名中取出方法名
+fn short_name(sign: &str) -> String {
+    let name = sign.rsplit_once("->").map_or(sign, |(_, name)| name);
+    name.split('(').next().unwrap_or(name).to_string()
+}
+
+/// 寄存器的名字，参数寄存器使用参数名
+struct Names {
+    num_regs: u64,
+    aliases: HashMap<u16, String>,
+}
+
+impl Names {
+    fn arg_name(index: u64) -> String {
+        match index {
+            0 => "FunctionObject".to_string(),
+            1 => "NewTarget".to_string(),
+            2 => "this".to_string(),
+            i => format!("arg{}", i - 3),
+        }
+    }
+
+    fn get(&self, reg: u16) -> String {
+        if let Some(alias) = self.aliases.get(&reg) {
+            return alias.clone();
+        }
+        if reg as u64 >= self.num_regs {
+            return Names::arg_name(reg as u64 - self.num_regs);
+        }
+        format!("v{}", reg)
+    }
+}
+
+/// 把一个基本块的指令提升为语句，acc 作为符号值在指令间传递
+struct Lifter<'a> {
+    chains: &'a DefUseChains,
+    names: &'a Names,
+    /// 当前基本块所有指令的 pc
+    block_pcs: BTreeSet<usize>,
+    acc: Expr,
+    acc_consumed: bool,
+    /// 只使用一次的寄存器，在使用处直接展开
+    pending: Vec<(u16, Expr)>,
+    stmts: Vec<Stmt>,
+}
+
+impl<'a> Lifter<'a> {
+    fn emit(&mut self, stmt: Stmt) {
+        // 有副作用的表达式不能推迟到这条语句之后
+        let pending = std::mem::take(&mut self.pending);
+        for (reg, expr) in pending {
+            if expr.has_side_effects() {
+                self.stmts
+                    .push(Stmt::Assign(Expr::Name(self.names.get(reg)), expr));
+            } else {
+                self.pending.push((reg, expr));
+            }
+        }
+        self.stmts.push(stmt);
+    }
+
+    fn reg(&mut self, reg: u16) -> Expr {
+        if let Some(i) = self.pending.iter().position(|(r, _)| *r == reg) {
+            return self.pending.remove(i).1;
+        }
+        Expr::Name(self.names.get(reg))
+    }
+
+    fn set_acc(&mut self, expr: Expr) {
+        if !self.acc_consumed && self.acc.has_side_effects() {
+            let acc = std::mem::replace(&mut self.acc, Expr::name(ACC));
+            self.emit(Stmt::Expr(acc));
+        }
+        self.acc = expr;
+        self.acc_consumed = false;
+    }
+
+    /// acc 的定义被多次使用
+    fn acc_reused(&self, pc: usize) -> bool {
+        self.chains
+            .definitions(pc, Location::Acc)
+            .iter()
+            .any(|site| self.chains.uses(*site, Location::Acc).len() > 1)
+    }
+
+    fn take_acc(&mut self, pc: usize) -> Expr {
+        if !self.acc.is_simple() && self.acc_reused(pc) {
+            self.materialize_acc();
+        }
+        self.acc_consumed = true;
+        self.acc.clone()
+    }
+
+    /// 把 acc 的值保存到变量 acc 中
+    fn materialize_acc(&mut self) {
+        if self.acc != Expr::name(ACC) {
+            let acc = std::mem::replace(&mut self.acc, Expr::name(ACC));
+            self.emit(Stmt::Assign(Expr::name(ACC), acc));
+        }
+    }
+
+    /// 写寄存器之前，引用了旧值的表达式要先求值
+    fn before_write(&mut self, reg: u16, is_sta: bool) {
+        let name = self.names.get(reg);
+        let pending = std::mem::take(&mut self.pending);
+        for (r, expr) in pending {
+            if r != reg && expr.references(&name) {
+                self.stmts
+                    .push(Stmt::Assign(Expr::Name(self.names.get(r)), expr));
+            } else {
+                self.pending.push((r, expr));
+            }
+        }
+        if !is_sta && self.acc.references(&name) {
+            self.materialize_acc();
+        }
+    }
+
+    /// 寄存器的定义只在同一个基本块中使用一次，可以展开到使用处
+    fn inlinable(&self, pc: usize, reg: u16) -> bool {
+        if self.names.aliases.contains_key(&reg) {
+            return false;
+        }
+        let site = DefSite::Instruction(pc);
+        let location = Location::Register(reg);
+        match self.chains.uses(site, location) {
+            [use_pc] => {
+                *use_pc > pc
+                    && self.block_pcs.contains(use_pc)
+                    && self.chains.definitions(*use_pc, location) == [site]
+            }
+            _ => false,
+        }
+    }
+
+    fn write_reg(&mut self, pc: usize, reg: u16, expr: Expr) {
+        if self.inlinable(pc, reg) {
+            self.pending.push((reg, expr));
+        } else {
+            self.emit(Stmt::Assign(Expr::Name(self.names.get(reg)), expr));
+        }
+    }
+
+    fn lift(&mut self, instruction: &Instruction) -> Option<Exit> {
+        let pc = *instruction.offset();
+        let mnemonic = instruction.mnemonic().as_str();
+        let operands = instruction.operands();
+        let regs: Vec<u16> = operands
+            .iter()
+            .filter_map(|operand| match operand {
+                Operand::Register(reg) => Some(*reg),
+                _ => None,
+            })
+            .collect();
+        let imms: Vec<u64> = operands
+            .iter()
+            .filter_map(|operand| match operand {
+                Operand::Imm(imm) => Some(*imm),
+                _ => None,
+            })
+            .collect();
+        let string = operands.iter().find_map(|operand| match operand {
+            Operand::String { value, .. } => Some(value.clone()),
+            _ => None,
+        });
+        let method = operands.iter().find_map(|operand| match operand {
+            Operand::Method { name, .. } => Some(short_name(name)),
+            _ => None,
+        });
+        let literal = operands.iter().find_map(|operand| match operand {
+            Operand::LiteralArray { value, .. } => Some(value.to_string()),
+            _ => None,
+        });
+        let base = mnemonic
+            .trim_start_matches("wide.")
+            .trim_start_matches("callruntime.");
+
+        if let Some(target) = instruction.branch_target() {
+            let target = usize::try_from(target).unwrap_or(usize::MAX);
+            if mnemonic == "jmp" {
+                return Some(Exit::Jump(target));
+            }
+            let acc = self.take_acc(pc);
+            let cond = match mnemonic {
+                "jeqz" => acc.negate(),
+                "jnez" => acc,
+                "jstricteqz" => Expr::Binary("===", Box::new(acc), Box::new(Expr::name("0"))),
+                "jnstricteqz" => Expr::Binary("!==", Box::new(acc), Box::new(Expr::name("0"))),
+                "jeqnull" => Expr::Binary("==", Box::new(acc), Box::new(Expr::name("null"))),
+                "jnenull" => Expr::Binary("!=", Box::new(acc), Box::new(Expr::name("null"))),
+                "jstricteqnull" => Expr::Binary("===", Box::new(acc), Box::new(Expr::name("null"))),
+                "jnstricteqnull" => {
+                    Expr::Binary("!==", Box::new(acc), Box::new(Expr::name("null")))
+                }
+                "jequndefined" => {
+                    Expr::Binary("==", Box::new(acc), Box::new(Expr::name("undefined")))
+                }
+                "jneundefined" => {
+                    Expr::Binary("!=", Box::new(acc), Box::new(Expr::name("undefined")))
+                }
+                "jstrictequndefined" => {
+                    Expr::Binary("===", Box::new(acc), Box::new(Expr::name("undefined")))
+                }
+                "jnstrictequndefined" => {
+                    Expr::Binary("!==", Box::new(acc), Box::new(Expr::name("undefined")))
+                }
+                _ => {
+                    let op = match mnemonic {
+                        "jeq" => "==",
+                        "jne" => "!=",
+                        "jstricteq" => "===",
+                        _ => "!==",
+                    };
+                    let rhs = self.reg(regs[0]);
+                    Expr::Binary(op, Box::new(acc), Box::new(rhs))
+                }
+            };
+            return Some(Exit::Branch(cond, target));
+        }
+
+        let binary = match base {
+            "add2" => Some("+"),
+            "sub2" => Some("-"),
+            "mul2" => Some("*"),
+            "div2" => Some("/"),
+            "mod2" => Some("%"),
+            "eq" => Some("=="),
+            "noteq" => Some("!="),
+            "less" => Some("<"),
+            "lesseq" => Some("<="),
+            "greater" => Some(">"),
+            "greatereq" => Some(">="),
+            "shl2" => Some("<<"),
+            "shr2" => Some(">>>"),
+            "ashr2" => Some(">>"),
+            "and2" => Some("&"),
+            "or2" => Some("|"),
+            "xor2" => Some("^"),
+            "exp" => Some("**"),
+            "isin" => Some("in"),
+            "instanceof" => Some("instanceof"),
+            "stricteq" => Some("==="),
+            "strictnoteq" => Some("!=="),
+            _ => None,
+        };
+        if let Some(op) = binary {
+            let lhs = self.reg(regs[0]);
+            let rhs = self.take_acc(pc);
+            self.set_acc(Expr::Binary(op, Box::new(lhs), Box::new(rhs)));
+            return None;
+        }
+
+        let unary = match base {
+            "typeof" => Some("typeof"),
+            "tonumber" | "tonumeric" => Some("+"),
+            "neg" => Some("-"),
+            "not" => Some("~"),
+            "isfalse" => Some("!"),
+            _ => None,
+        };
+        if let Some(op) = unary {
+            let acc = self.take_acc(pc);
+            let expr = if op == "!" {
+                acc.negate()
+            } else {
+                Expr::Unary(op, Box::new(acc))
+            };
+            self.set_acc(expr);
+            return None;
+        }
+
+        let name = |s: &Option<String>| s.clone().unwrap_or_default();
+        match base {
+            "ldundefined" => self.set_acc(Expr::name("undefined")),
+            "ldnull" => self.set_acc(Expr::name("null")),
+            "ldtrue" => self.set_acc(Expr::name("true")),
+            "ldfalse" => self.set_acc(Expr::name("false")),
+            "ldnan" => self.set_acc(Expr::name("NaN")),
+            "ldinfinity" => self.set_acc(Expr::name("Infinity")),
+            "ldhole" => self.set_acc(Expr::name("hole")),
+            "ldthis" => self.set_acc(Expr::name("this")),
+            "ldnewtarget" => self.set_acc(Expr::name("new.target")),
+            "ldglobal" => self.set_acc(Expr::name("globalThis")),
+            "ldsymbol" => self.set_acc(Expr::name("Symbol")),
+            "ldfunction" => self.set_acc(Expr::name("FunctionObject")),
+            "ldai" => self.set_acc(Expr::Number((imms[0] as u32 as i32).to_string())),
+            "fldai" => self.set_acc(Expr::Number(f64::from_bits(imms[0]).to_string())),
+            "lda.str" => self.set_acc(Expr::Str(name(&string))),
+            "ldbigint" => self.set_acc(Expr::Number(format!("{}n", name(&string)))),
+            "lda" => {
+                let value = self.reg(regs[0]);
+                self.set_acc(value);
+            }
+            "sta" => {
+                self.before_write(regs[0], true);
+                let expr = self.acc.clone();
+                if expr.is_simple() || !self.acc_reused(pc) {
+                    self.acc_consumed = true;
+                    if self.inlinable(pc, regs[0]) {
+                        self.pending.push((regs[0], expr));
+                    } else {
+                        self.emit(Stmt::Assign(Expr::Name(self.names.get(regs[0])), expr));
+                        if !self.acc.is_simple() {
+                            self.acc = Expr::Name(self.names.get(regs[0]));
+                        }
+                    }
+                } else {
+                    // acc 还会被使用，先保存到寄存器中
+                    self.emit(Stmt::Assign(Expr::Name(self.names.get(regs[0])), expr));
+                    self.acc = Expr::Name(self.names.get(regs[0]));
+                    self.acc_consumed = true;
+                }
+            }
+            "mov" => {
+                if self.names.aliases.contains_key(&regs[0]) {
+                    return None;
+                }
+                self.before_write(regs[0], false);
+                let value = self.reg(regs[1]);
+                self.write_reg(pc, regs[0], value);
+            }
+            "tryldglobalbyname" | "ldglobalvar" => self.set_acc(Expr::Name(name(&string))),
+            "trystglobalbyname" | "stglobalvar" | "sttoglobalrecord" | "stconsttoglobalrecord" => {
+                let value = self.take_acc(pc);
+                self.emit(Stmt::Assign(Expr::Name(name(&string)), value));
+            }
+            "ldobjbyname" => {
+                let obj = self.take_acc(pc);
+                self.set_acc(Expr::Member(Box::new(obj), name(&string)));
+            }
+            "ldsuperbyname" => {
+                self.take_acc(pc);
+                self.set_acc(Expr::Member(Box::new(Expr::name("super")), name(&string)));
+            }
+            "ldthisbyname" => {
+                self.set_acc(Expr::Member(Box::new(Expr::name("this")), name(&string)))
+            }
+            "ldobjbyvalue" | "ldsuperbyvalue" => {
+                let obj = if base == "ldobjbyvalue" {
+                    self.reg(regs[0])
+                } else {
+                    Expr::name("super")
+                };
+                let key = self.take_acc(pc);
+                self.set_acc(Expr::Index(Box::new(obj), Box::new(key)));
+            }
+            "ldthisbyvalue" => {
+                let key = self.take_acc(pc);
+                self.set_acc(Expr::Index(Box::new(Expr::name("this")), Box::new(key)));
+            }
+            "ldobjbyindex" => {
+                let obj = self.take_acc(pc);
+                let index = Expr::Number(imms[0].to_string());
+                self.set_acc(Expr::Index(Box::new(obj), Box::new(index)));
+            }
+            "stobjbyname"
+            | "stownbyname"
+            | "stownbynamewithnameset"
+            | "definefieldbyname"
+            | "definepropertybyname"
+            | "stsuperbyname" => {
+                let obj = if base == "stsuperbyname" {
+                    Expr::name("super")
+                } else {
+                    self.reg(regs[0])
+                };
+                let value = self.take_acc(pc);
+                self.emit(Stmt::Assign(
+                    Expr::Member(Box::new(obj), name(&string)),
+                    value,
+                ));
+            }
+            "stthisbyname" => {
+                let value = self.take_acc(pc);
+                let target = Expr::Member(Box::new(Expr::name("this")), name(&string));
+                self.emit(Stmt::Assign(target, value));
+            }
+            "stobjbyvalue"
+            | "stownbyvalue"
+            | "stownbyvaluewithnameset"
+            | "stsuperbyvalue"
+            | "definefieldbyvalue" => {
+                let obj = self.reg(regs[0]);
+                let key = self.reg(regs[1]);
+                let value = self.take_acc(pc);
+                let obj = if base == "stsuperbyvalue" {
+                    Expr::name("super")
+                } else {
+                    obj
+                };
+                self.emit(Stmt::Assign(
+                    Expr::Index(Box::new(obj), Box::new(key)),
+                    value,
+                ));
+            }
+            "stthisbyvalue" => {
+                let key = self.reg(regs[0]);
+                let value = self.take_acc(pc);
+                let target = Expr::Index(Box::new(Expr::name("this")), Box::new(key));
+                self.emit(Stmt::Assign(target, value));
+            }
+            "stobjbyindex" | "stownbyindex" | "definefieldbyindex" => {
+                let obj = self.reg(regs[0]);
+                let value = self.take_acc(pc);
+                let index = Expr::Number(imms[0].to_string());
+                self.emit(Stmt::Assign(
+                    Expr::Index(Box::new(obj), Box::new(index)),
+                    value,
+                ));
+            }
+            "ldlexvar" | "ldsendablevar" | "wideldsendablevar" => {
+                self.set_acc(Expr::Name(format!("lex_{}_{}", imms[0], imms[1])))
+            }
+            "stlexvar" | "stsendablevar" | "widestsendablevar" => {
+                let value = self.take_acc(pc);
+                let target = Expr::Name(format!("lex_{}_{}", imms[0], imms[1]));
+                self.emit(Stmt::Assign(target, value));
+            }
+            "ldlocalmodulevar" => self.set_acc(Expr::Name(format!("local_{}", imms[0]))),
+            "ldexternalmodulevar" | "ldlazymodulevar" | "wideldlazymodulevar" => {
+                self.set_acc(Expr::Name(format!("import_{}", imms[0])))
+            }
+            "stmodulevar" => {
+                let value = self.take_acc(pc);
+                self.emit(Stmt::Assign(
+                    Expr::Name(format!("local_{}", imms[0])),
+                    value,
+                ));
+            }
+            "getmodulenamespace" => self.set_acc(Expr::Name(format!("namespace_{}", imms[0]))),
+            "definefunc" | "definemethod" => {
+                if base == "definemethod" {
+                    self.take_acc(pc);
+                }
+                self.set_acc(Expr::Function(method.unwrap_or_default()));
+            }
+            "defineclasswithbuffer" | "definesendableclass" => {
+                let parent = self.reg(regs[0]);
+                let parent = (parent != Expr::name("hole")).then(|| Box::new(parent));
+                self.set_acc(Expr::Class(method.unwrap_or_default(), parent));
+            }
+            "createemptyobject" => self.set_acc(Expr::Raw("{}".to_string())),
+            "createemptyarray" => self.set_acc(Expr::Raw("[]".to_string())),
+            "createarraywithbuffer" => {
+                self.set_acc(Expr::Raw(format!("[{}]", literal.unwrap_or_default())))
+            }
+            "createobjectwithbuffer" => {
+                self.set_acc(Expr::Raw(format!("{{ {} }}", literal.unwrap_or_default())))
+            }
+            "callarg0" | "callarg1" | "callargs2" | "callargs3" | "callrange" => {
+                let args: Vec<Expr> = if base == "callrange" {
+                    let Some(range) = register_range(regs[0], 0..imms[0]) else {
+                        self.lift_intrinsic(instruction);
+                        return None;
+                    };
+                    range.into_iter().map(|r| self.reg(r)).collect()
+                } else {
+                    regs.iter().map(|r| self.reg(*r)).collect()
+                };
+                let callee = self.take_acc(pc);
+                self.set_acc(Expr::Call(Box::new(callee), args));
+            }
+            "callthis0" | "callthis1" | "callthis2" | "callthis3" | "callthisrange" => {
+                let mut args: Vec<Expr> = if base == "callthisrange" {
+                    let Some(range) = register_range(regs[0], 0..imms[0].saturating_add(1)) else {
+                        self.lift_intrinsic(instruction);
+                        return None;
+                    };
+                    range.into_iter().map(|r| self.reg(r)).collect()
+                } else {
+                    regs.iter().map(|r| self.reg(*r)).collect()
+                };
+                let this = args.remove(0);
+                let callee = self.take_acc(pc);
+                let callee = match &callee {
+                    // obj.method(args) 的形式
+                    Expr::Member(obj, _) if **obj == this => callee,
+                    _ => {
+                        args.insert(0, this);
+                        Expr::Member(Box::new(callee), "call".to_string())
+                    }
+                };
+                self.set_acc(Expr::Call(Box::new(callee), args));
+            }
+            "newobjrange" => {
+                let Some(range) = register_range(regs[0], 1..imms[0]) else {
+                    self.lift_intrinsic(instruction);
+                    return None;
+                };
+                let callee = self.reg(regs[0]);
+                let args = range.into_iter().map(|r| self.reg(r)).collect();
+                self.set_acc(Expr::New(Box::new(callee), args));
+            }
+            "supercallthisrange" | "supercallarrowrange" => {
+                let Some(range) = register_range(regs[0], 0..imms[0]) else {
+                    self.lift_intrinsic(instruction);
+                    return None;
+                };
+                let args = range.into_iter().map(|r| self.reg(r)).collect();
+                if base == "supercallarrowrange" {
+                    self.take_acc(pc);
+                }
+                self.set_acc(Expr::Call(Box::new(Expr::name("super")), args));
+            }
+            "return" => {
+                let value = self.take_acc(pc);
+                self.emit(Stmt::Return(Some(value)));
+            }
+            "returnundefined" => self.emit(Stmt::Return(None)),
+            "throw" => {
+                let value = self.take_acc(pc);
+                self.emit(Stmt::Throw(value));
+            }
+            // 词法环境由运行时维护，对阅读代码没有帮助
+            "newlexenv" | "newlexenvwithname" | "poplexenv" | "newsendableenv"
+            | "widenewsendableenv" => {}
+            // 暂时性死区检查
+            "throw.undefinedifholewithname" | "throw.undefinedifhole" => {}
+            "istrue" | "nop" | "debugger" => {}
+            _ => self.lift_intrinsic(instruction),
+        }
+        None
+    }
+
+    /// 没有对应语法的指令，按 `助记符(操作数)` 的形式输出
+    fn lift_intrinsic(&mut self, instruction: &Instruction) {
+        let pc = *instruction.offset();
+        let effects = Effects::of(instruction);
+        let mut args = Vec::new();
+        for operand in instruction.operands() {
+            let arg = match operand {
+                Operand::Register(reg) => self.reg(*reg),
+                Operand::String { value, .. } => Expr::Str(value.clone()),
+                Operand::Method { name, .. } => Expr::Name(short_name(name)),
+                operand => Expr::Raw(operand.to_string()),
+            };
+            args.push(arg);
+        }
+        if effects.reads().contains(&Location::Acc) {
+            let acc = self.take_acc(pc);
+            args.push(acc);
+        }
+        let expr = Expr::Intrinsic(instruction.mnemonic().clone(), args);
+        if AccAccess::of(instruction.mnemonic()).writes() {
+            self.set_acc(expr);
+        } else {
+            self.emit(Stmt::Expr(expr));
+        }
+    }
+}
+
+/// 范围调用从 `first` 开始的连续寄存器，编号超出 u16 时返回 None
+fn register_range(first: u16, range: std::ops::Range<u64>) -> Option<Vec<u16>> {
+    range
+        .map(|i| u16::try_from(i).ok().and_then(|i| first.checked_add(i)))
+        .collect()
+}
+
+/// 循环的上下文，跳到循环头是 continue，跳到循环出口是 break
+#[derive(Debug, Clone, Copy)]
+struct LoopContext {
+    header: usize,
+    exit: usize,
+}
+
+struct Structurer<'a> {
+    blocks: Vec<LiftedBlock>,
+    code: &'a Code,
+    labels: std::collections::BTreeMap<usize, String>,
+    catch_types: Vec<Vec<Option<String>>>,
+    /// 已经输出的 TryBlock 和循环，防止重复处理
+    entered_tries: HashSet<usize>,
+    entered_loops: HashSet<usize>,
+    /// 需要输出标签的 pc
+    goto_targets: BTreeSet<usize>,
+    used_gotos: BTreeSet<usize>,
+}
+
+impl Structurer<'_> {
+    fn index_at(&self, pc: usize) -> usize {
+        self.blocks
+            .iter()
+            .position(|block| block.start_pc >= pc)
+            .unwrap_or(self.blocks.len())
+    }
+
+    fn label(&self, pc: usize) -> String {
+        self.labels
+            .get(&pc)
+            .cloned()
+            .unwrap_or_else(|| format!("label_{:#x}", pc))
+    }
+
+    fn jump(&mut self, target: usize, loops: &[LoopContext]) -> Stmt {
+        if let Some(ctx) = loops.last() {
+            if target == ctx.header {
+                return Stmt::Continue;
+            }
+            if target == ctx.exit {
+                return Stmt::Break;
+            }
+        }
+        self.used_gotos.insert(target);
+        Stmt::Goto(self.label(target))
+    }
+
+    fn is_loop_target(target: usize, loops: &[LoopContext]) -> bool {
+        loops
+            .last()
+            .is_some_and(|ctx| target == ctx.header || target == ctx.exit)
+    }
+
+    fn structure(&mut self, mut i: usize, end_pc: usize, loops: &[LoopContext]) -> Vec<Stmt> {
+        let mut out = Vec::new();
+        while i < self.blocks.len() && self.blocks[i].start_pc < end_pc {
+            let start_pc = self.blocks[i].start_pc;
+            if self.goto_targets.contains(&start_pc) {
+                out.push(Stmt::Label(self.label(start_pc)));
+            }
+
+            // try/catch
+            let code = self.code;
+            let try_index = code.try_blocks().iter().enumerate().position(|(t, b)| {
+                *b.start_pc() as usize == start_pc && !self.entered_tries.contains(&t)
+            });
+            if let Some(t) = try_index {
+                self.entered_tries.insert(t);
+                let try_block = &code.try_blocks()[t];
+                let try_end = try_block.end_pc() as usize;
+                let mut body = self.structure(i, try_end, loops);
+
+                let mut next_pc = try_end;
+                let mut handlers = Vec::new();
+                let catches: Vec<(usize, usize)> = try_block
+                    .catch_blocks()
+                    .iter()
+                    .map(|c| (*c.handler_pc() as usize, c.handler_end_pc() as usize))
+                    .collect();
+                let first_handler = catches.iter().map(|c| c.0).min().unwrap_or(try_end);
+                // try 结束后跳过异常处理块的代码
+                if first_handler > try_end {
+                    let gap = self.index_at(try_end);
+                    let mut stmts = self.structure(gap, first_handler, loops);
+                    if let Some(Stmt::Goto(_)) = stmts.last() {
+                        if let Some(Exit::Jump(target)) = self
+                            .blocks
+                            .get(self.index_at(first_handler).saturating_sub(1))
+                            .map(|b| b.exit.clone())
+                        {
+                            stmts.pop();
+                            self.used_gotos.remove(&target);
+                            next_pc = next_pc.max(target);
+                        }
+                    }
+                    body.extend(stmts);
+                }
+                for (j, (handler_pc, handler_end)) in catches.into_iter().enumerate() {
+                    let start = self.index_at(handler_pc);
+                    let stmts = self.structure(start, handler_end, loops);
+                    let catch_type = self.catch_types[t].get(j).cloned().flatten();
+                    handlers.push((catch_type, stmts));
+                    next_pc = next_pc.max(handler_end);
+                }
+                out.push(Stmt::Try(body, handlers));
+                i = self.index_at(next_pc);
+                continue;
+            }
+
+            // 循环：块内有跳回循环头的边
+            let latch = (i..self.blocks.len())
+                .take_while(|j| self.blocks[*j].start_pc < end_pc)
+                .filter(|j| match self.blocks[*j].exit {
+                    Exit::Jump(target) | Exit::Branch(_, target) => target == start_pc,
+                    Exit::Next => false,
+                })
+                .last();
+            if let (Some(latch), false) = (latch, self.entered_loops.contains(&start_pc)) {
+                self.entered_loops.insert(start_pc);
+                let exit = self.blocks[latch].end_pc;
+                let mut inner = loops.to_vec();
+                inner.push(LoopContext {
+                    header: start_pc,
+                    exit,
+                });
+                let mut body = self.structure(i, exit, &inner);
+                if let Some(Stmt::Continue) = body.last() {
+                    body.pop();
+                }
+                let stmt = match (body.first(), body.last()) {
+                    (Some(Stmt::If(_, then, otherwise)), _)
+                        if then == &[Stmt::Break] && otherwise.is_empty() =>
+                    {
+                        let Stmt::If(cond, ..) = body.remove(0) else {
+                            unreachable!()
+                        };
+                        Stmt::While(cond.negate(), body)
+                    }
+                    (_, Some(Stmt::If(_, then, otherwise)))
+                        if then == &[Stmt::Continue] && otherwise.is_empty() =>
+                    {
+                        let Some(Stmt::If(cond, ..)) = body.pop() else {
+                            unreachable!()
+                        };
+                        Stmt::DoWhile(body, cond)
+                    }
+                    _ => Stmt::Loop(body),
+                };
+                out.push(stmt);
+                i = self.index_at(exit);
+                continue;
+            }
+
+            let block = self.blocks[i].clone();
+            out.extend(block.stmts);
+            match block.exit {
+                Exit::Next => i += 1,
+                Exit::Jump(target) => {
+                    out.push(self.jump(target, loops));
+                    i += 1;
+                }
+                Exit::Branch(cond, target) => {
+                    if Self::is_loop_target(target, loops) || target <= start_pc || target > end_pc
+                    {
+                        let jump = self.jump(target, loops);
+                        out.push(Stmt::If(cond, vec![jump], vec![]));
+                        i += 1;
+                        continue;
+                    }
+
+                    // then 分支最后跳过 else 分支
+                    let else_end = self
+                        .blocks
+                        .iter()
+                        .find(|b| b.end_pc == target)
+                        .and_then(|b| match b.exit {
+                            Exit::Jump(end) if end > target && end <= end_pc => Some(end),
+                            _ => None,
+                        })
+                        .filter(|end| !Self::is_loop_target(*end, loops));
+
+                    let mut then = self.structure(i + 1, target, loops);
+                    match else_end {
+                        Some(end) => {
+                            if let Some(Stmt::Goto(_)) = then.last() {
+                                then.pop();
+                                self.used_gotos.remove(&end);
+                            }
+                            let otherwise = self.structure(self.index_at(target), end, loops);
+                            out.push(Self::make_if(cond.negate(), then, otherwise));
+                            i = self.index_at(end);
+                        }
+                        None => {
+                            out.push(Self::make_if(cond.negate(), then, vec![]));
+                            i = self.index_at(target);
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// `if (c) { acc = a } else { acc = b }` 化简为 `acc = c ? a : b`
+    fn make_if(cond: Expr, then: Vec<Stmt>, otherwise: Vec<Stmt>) -> Stmt {
+        if let ([Stmt::Assign(t1, a)], [Stmt::Assign(t2, b)]) = (&then[..], &otherwise[..]) {
+            if t1 == t2 {
+                let value = Expr::Ternary(Box::new(cond), Box::new(a.clone()), Box::new(b.clone()));
+                return Stmt::Assign(t1.clone(), value);
+            }
+        }
+        Stmt::If(cond, then, otherwise)
+    }
+}
+
+/// 合并短路求值产生的连续条件跳转，第二个条件所在的基本块只能从第一个基本块进入：
+///
+/// - `if (a) goto X; if (b) goto X;` 合并为 `if (a || b) goto X;`
+/// - `if (a) goto T; if (b) goto X; T:` 合并为 `if (!a && b) goto X;`
+fn merge_conditions(blocks: &mut Vec<LiftedBlock>, code: &Code) {
+    let mut i = 0;
+    while i + 1 < blocks.len() {
+        let (first, second) = (&blocks[i], &blocks[i + 1]);
+        let second_pc = second.start_pc;
+        let only_from_first = second.stmts.is_empty()
+            && !blocks.iter().any(|b| match b.exit {
+                Exit::Jump(target) | Exit::Branch(_, target) => target == second_pc,
+                Exit::Next => false,
+            })
+            && !code.try_blocks().iter().any(|t| {
+                *t.start_pc() as usize == second_pc
+                    || t.end_pc() as usize == second_pc
+                    || t.catch_blocks()
+                        .iter()
+                        .any(|c| *c.handler_pc() as usize == second_pc)
+            });
+
+        let merged = match (&first.exit, &second.exit) {
+            (Exit::Branch(a, t1), Exit::Branch(b, t2)) if only_from_first && t1 == t2 => {
+                Some(Exit::Branch(
+                    Expr::Binary("||", Box::new(a.clone()), Box::new(b.clone())),
+                    *t2,
+                ))
+            }
+            (Exit::Branch(a, t1), Exit::Branch(b, t2))
+                if only_from_first && *t1 == second.end_pc =>
+            {
+                let cond = Expr::Binary("&&", Box::new(a.clone().negate()), Box::new(b.clone()));
+                Some(Exit::Branch(cond, *t2))
+            }
+            _ => None,
+        };
+        match merged {
+            Some(exit) => {
+                let second = blocks.remove(i + 1);
+                blocks[i].end_pc = second.end_pc;
+                blocks[i].exit = exit;
+            }
+            None => i += 1,
+        }
+    }
+}
+
+/// 反编译方法，`catch_types` 是每个 TryBlock 中每个 CatchBlock 捕获的异常类型
+pub fn decompile(
+    name: &str,
+    code: &Code,
+    cfg: &ControlFlowGraph,
+    catch_types: Vec<Vec<Option<String>>>,
+) -> Function {
+    let chains = DefUseChains::new(cfg);
+    let num_regs = *code.num_regs();
+    let num_args = *code.num_args();
+
+    // 入口处把参数复制到普通寄存器，且之后不再修改的，直接使用参数名
+    let mut defs: HashMap<u16, usize> = HashMap::new();
+    for block in cfg.blocks() {
+        for instruction in block.instructions() {
+            for location in Effects::of(instruction).writes() {
+                if let Location::Register(reg) = location {
+                    *defs.entry(*reg).or_default() += 1;
+                }
+            }
+        }
+    }
+    let mut aliases = HashMap::new();
+    if let Some(entry) = cfg.blocks().first() {
+        for instruction in entry.instructions() {
+            if instruction.mnemonic() != "mov" {
+                continue;
+            }
+            let regs: Vec<u16> = instruction
+                .operands()
+                .iter()
+                .filter_map(|operand| match operand {
+                    Operand::Register(reg) => Some(*reg),
+                    _ => None,
+                })
+                .collect();
+            if regs[1] as u64 >= num_regs && defs.get(&regs[0]) == Some(&1) {
+                aliases.insert(regs[0], Names::arg_name(regs[1] as u64 - num_regs));
+            }
+        }
+    }
+    let names = Names { num_regs, aliases };
+
+    // 后继基本块在写 acc 之前就读了 acc
+    let acc_live_in: Vec<bool> = cfg
+        .blocks()
+        .iter()
+        .map(|block| {
+            block
+                .instructions()
+                .iter()
+                .map(Effects::of)
+                .find(|e| e.reads().contains(&Location::Acc) || e.writes().contains(&Location::Acc))
+                .is_some_and(|e| e.reads().contains(&Location::Acc))
+        })
+        .collect();
+
+    let mut blocks = Vec::new();
+    for block in cfg.blocks() {
+        let id = *block.id();
+        let is_handler = cfg
+            .edges()
+            .iter()
+            .any(|edge| *edge.to() == id && *edge.kind() == EdgeKind::Exception);
+        let mut lifter = Lifter {
+            chains: &chains,
+            names: &names,
+            block_pcs: block.instructions().iter().map(|i| *i.offset()).collect(),
+            acc: Expr::name(if is_handler { CATCH_VAR } else { ACC }),
+            acc_consumed: true,
+            pending: Vec::new(),
+            stmts: Vec::new(),
+        };
+
+        let needs_acc = cfg.edges().iter().any(|edge| {
+            *edge.from() == id && *edge.kind() != EdgeKind::Exception && acc_live_in[*edge.to()]
+        });
+        let mut exit = Exit::Next;
+        for instruction in block.instructions() {
+            let is_branch = instruction.branch_target().is_some();
+            if is_branch && needs_acc {
+                lifter.materialize_acc();
+            }
+            if let Some(e) = lifter.lift(instruction) {
+                exit = e;
+            }
+        }
+        if needs_acc && !matches!(exit, Exit::Jump(_) | Exit::Branch(..)) {
+            lifter.materialize_acc();
+        }
+        // 剩下的有副作用的表达式
+        if !lifter.acc_consumed && lifter.acc.has_side_effects() {
+            let acc = lifter.acc.clone();
+            lifter.emit(Stmt::Expr(acc));
+        }
+        let pending = std::mem::take(&mut lifter.pending);
+        for (reg, expr) in pending {
+            if expr.has_side_effects() {
+                lifter.stmts.push(Stmt::Expr(expr));
+            } else {
+                lifter
+                    .stmts
+                    .push(Stmt::Assign(Expr::Name(names.get(reg)), expr));
+            }
+        }
+        blocks.push(LiftedBlock {
+            start_pc: *block.start_pc(),
+            end_pc: *block.end_pc(),
+            stmts: lifter.stmts,
+            exit,
+        });
+    }
+
+    merge_conditions(&mut blocks, code);
+
+    let instructions: Vec<Instruction> = cfg
+        .blocks()
+        .iter()
+        .flat_map(|block| block.instructions().iter().cloned())
+        .collect();
+    let mut structurer = Structurer {
+        blocks,
+        code,
+        labels: bytecode::branch_labels(&instructions),
+        catch_types,
+        entered_tries: HashSet::new(),
+        entered_loops: HashSet::new(),
+        goto_targets: BTreeSet::new(),
+        used_gotos: BTreeSet::new(),
+    };
+    let mut body = structurer.structure(0, usize::MAX, &[]);
+    // 有无法结构化的跳转时，再来一次，把标签加上
+    if !structurer.used_gotos.is_empty() {
+        structurer.goto_targets = std::mem::take(&mut structurer.used_gotos);
+        structurer.entered_tries.clear();
+        structurer.entered_loops.clear();
+        body = structurer.structure(0, usize::MAX, &[]);
+    }
+    if let Some(Stmt::Return(None)) = body.last() {
+        body.pop();
+    }
+
+    let params = (3..num_args).map(Names::arg_name).collect();
+    Function {
+        name: short_name(name),
+        params,
+        body,
+    }
+}
@@ -55,3 +55,30 @@ impl Display for Error {
         }
     }
 }
+
+impl Error {
+    /// 将相对于某个结构起始位置的偏移量，转换为相对于上一层结构（或文件）的偏移量。
+    pub(crate) fn rebase(self, base: usize) -> Error {
+        match self {
+            Error::BadOffset(offset, msg) => Error::BadOffset(base + offset, msg),
+            other => other,
+        }
+    }
+}
+
+/// 为读取结果附加出错位置的偏移量和结构名。
+pub(crate) trait ReadContext<T> {
+    fn at(self, offset: usize, name: &str) -> Result<T, Error>;
+}
+
+impl<T, E> ReadContext<T> for Result<T, E> {
+    fn at(self, offset: usize, name: &str) -> Result<T, Error> {
+        self.map_err(|_| Error::BadOffset(offset, name.to_string()))
+    }
+}
+
+impl<T> ReadContext<T> for Option<T> {
+    fn at(self, offset: usize, name: &str) -> Result<T, Error> {
+        self.ok_or_else(|| Error::BadOffset(offset, name.to_string()))
+    }
+}
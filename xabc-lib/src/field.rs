@@ -1,10 +1,15 @@
+use std::fmt;
+
 use getset::Getters;
 use scroll::ctx;
 use scroll::Pread;
 use scroll::Sleb128;
 use scroll::Uleb128;
 
-use crate::{error, uint16_t, uint32_t};
+use crate::annotation::{self, Annotation, AnnotationKind};
+use crate::error::{self, ReadContext};
+use crate::region::Region;
+use crate::{uint16_t, uint32_t};
 
 #[derive(Debug, Getters, Default)]
 #[get = "pub"]
@@ -17,24 +22,69 @@ pub struct Field {
     /// 它的值必须是 AccessFlag 的组合。
     access_flags: Vec<String>,
     // field_data: Vec<TaggedValue>,
+    /// `INT_VALUE` 标签的值，用于布尔和整数类型
+    int_value: Option<i64>,
+    /// `VALUE` 标签的值，如 `moduleRecordIdx` 指向一个模块记录
+    value: Option<uint32_t>,
+    /// 注解的偏移量
+    runtime_annotation_offs: Vec<uint32_t>,
+    annotation_offs: Vec<uint32_t>,
+    runtime_type_annotation_offs: Vec<uint32_t>,
+    type_annotation_offs: Vec<uint32_t>,
+    /// 解析后的注解，由 AbcFile 加载时填充
+    annotations: Vec<Annotation>,
     size: usize,
 }
 
+/// 字段的初始值，`VALUE` 的含义由字段类型决定
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Int(i64),
+    /// u64 的值可能超出 i64 的范围
+    UInt(u64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    /// 字面量数组或模块记录的偏移量
+    LiteralArray(uint32_t),
+    /// 无法确定含义的值
+    Raw(uint32_t),
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Int(value) => write!(f, "{}", value),
+            FieldValue::UInt(value) => write!(f, "{}", value),
+            FieldValue::Float(value) => write!(f, "{}", value),
+            FieldValue::Double(value) => write!(f, "{}", value),
+            FieldValue::String(value) => write!(f, "\"{}\"", value),
+            FieldValue::LiteralArray(offset) => write!(f, "literal_array 0x{:x}", offset),
+            FieldValue::Raw(value) => write!(f, "0x{:x}", value),
+        }
+    }
+}
+
 impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Field {
     type Error = error::Error;
     fn try_from_ctx(source: &'a [u8], _: scroll::Endian) -> Result<(Self, usize), Self::Error> {
-        let class_idx = source.pread::<uint16_t>(0).unwrap();
-        let type_idx = source.pread::<uint16_t>(2).unwrap();
-        let name_off = source.pread::<uint32_t>(4).unwrap();
+        let class_idx = source.pread::<uint16_t>(0).at(0, "Field.class_idx")?;
+        let type_idx = source.pread::<uint16_t>(2).at(2, "Field.type_idx")?;
+        let name_off = source.pread::<uint32_t>(4).at(4, "Field.name_off")?;
 
         let off = &mut 8;
-        let access_flags = Uleb128::read(source, off).unwrap();
+        let access_flags = Uleb128::read(source, off).at(*off, "Field.access_flags")?;
         let access_flags = FieldAccessFlag::parse(access_flags);
 
         // 解析 field_data
-        // TODO: 数据保存
+        let mut int_value = None;
+        let mut value = None;
+        let mut runtime_annotation_offs = Vec::new();
+        let mut annotation_offs = Vec::new();
+        let mut runtime_type_annotation_offs = Vec::new();
+        let mut type_annotation_offs = Vec::new();
         'l: loop {
-            let tag_value = source.pread::<u8>(*off).unwrap();
+            let tag_value = source.pread::<u8>(*off).at(*off, "Field.field_data")?;
             *off += 1;
             match tag_value {
                 0x00 => {
@@ -42,37 +92,36 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Field {
                     break 'l;
                 }
                 0x01 => {
-                    let num = Sleb128::read(source, off).unwrap();
+                    let num = Sleb128::read(source, off).at(*off, "Field.INT_VALUE")?;
                     tracing::debug!("INT_VALUE -> {}", num);
+                    int_value = Some(num);
                 }
                 0x02 => {
-                    let data = source.pread::<uint32_t>(*off).unwrap();
+                    let data = source.pread::<uint32_t>(*off).at(*off, "Field.VALUE")?;
                     *off += 4;
                     tracing::debug!("VALUE -> {}", data);
+                    value = Some(data);
                 }
-                0x03 => {
-                    let data = source.pread::<uint32_t>(*off).unwrap();
+                // 注解可以有多个，值都是指向 Annotation 的偏移量
+                0x03..=0x06 => {
+                    let data = source
+                        .pread::<uint32_t>(*off)
+                        .at(*off, "Field.ANNOTATION")?;
                     *off += 4;
-                    tracing::debug!("RUNTIME_ANNOTATIONS -> {}", data);
-                }
-                0x04 => {
-                    let data = source.pread::<uint32_t>(*off).unwrap();
-                    *off += 4;
-                    tracing::debug!("ANNOTATIONS -> {}", data);
-                }
-                0x05 => {
-                    let data = source.pread::<uint32_t>(*off).unwrap();
-                    *off += 4;
-                    tracing::debug!("RUNTIME_TYPE_ANNOTATION -> {}", data);
-                }
-                0x06 => {
-                    let data = source.pread::<uint32_t>(*off).unwrap();
-                    *off += 4;
-                    tracing::debug!("TYPE_ANNOTATION -> {}", data);
+                    tracing::debug!("ANNOTATION 0x{:02X} -> {}", tag_value, data);
+                    match tag_value {
+                        0x03 => runtime_annotation_offs.push(data),
+                        0x04 => annotation_offs.push(data),
+                        0x05 => runtime_type_annotation_offs.push(data),
+                        _ => type_annotation_offs.push(data),
+                    }
                 }
                 _ => {
-                    tracing::debug!("UNKNOWN: {}", tag_value);
-                    break 'l;
+                    return Err(error::Error::MalFormed(format!(
+                        "Field: unknown tag 0x{:02X} at {}",
+                        tag_value,
+                        *off - 1
+                    )));
                 }
             }
         }
@@ -86,6 +135,13 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Field {
                 name_off,
                 access_flags,
                 // field_data: Vec::new(),
+                int_value,
+                value,
+                runtime_annotation_offs,
+                annotation_offs,
+                runtime_type_annotation_offs,
+                type_annotation_offs,
+                annotations: Vec::new(),
                 size,
             },
             source.len(),
@@ -93,6 +149,32 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Field {
     }
 }
 
+impl Field {
+    /// 解析字段的注解，注解的偏移量是相对于文件的，需要在加载完所有的区域后再解析
+    pub(crate) fn resolve_annotations(&mut self, source: &[u8], region: &Region) {
+        self.annotations = annotation::parse_annotations(
+            source,
+            region,
+            [
+                (AnnotationKind::Runtime, &self.runtime_annotation_offs),
+                (AnnotationKind::Plain, &self.annotation_offs),
+                (
+                    AnnotationKind::RuntimeType,
+                    &self.runtime_type_annotation_offs,
+                ),
+                (AnnotationKind::Type, &self.type_annotation_offs),
+            ],
+        );
+    }
+
+    pub(crate) fn has_annotations(&self) -> bool {
+        !(self.runtime_annotation_offs.is_empty()
+            && self.annotation_offs.is_empty()
+            && self.runtime_type_annotation_offs.is_empty()
+            && self.type_annotation_offs.is_empty())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FieldAccessFlag {
     PUBLIC = 0x0001,
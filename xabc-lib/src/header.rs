@@ -2,9 +2,28 @@ use getset::CopyGetters;
 use std::fmt;
 
 use super::uint32_t;
+use crate::error;
+use crate::version::Version;
 
 use scroll::Pread;
 
+/// 文件头魔数
+pub const MAGIC: [u8; 8] = *b"PANDA\0\0\0";
+/// 文件头的大小
+pub const HEADER_SIZE: usize = 60;
+/// 校验和覆盖的起始位置，即魔数和校验字段之后
+pub const CHECKSUM_START: usize = 12;
+/// RegionHeader 的大小
+pub const REGION_HEADER_SIZE: usize = 40;
+
+/// 计算 `data` 的 adler32 校验和，`data` 为整个文件
+pub fn compute_checksum(data: &[u8]) -> uint32_t {
+    let data = data.get(CHECKSUM_START..).unwrap_or_default();
+    let mut adler = adler32::RollingAdler32::new();
+    adler.update_buffer(data);
+    adler.hash()
+}
+
 /// ABC file header
 /// 12*4 + 8 + 4 = 60
 #[derive(Debug, Pread, CopyGetters, Default)]
@@ -40,15 +59,88 @@ pub struct Header {
     region_off: uint32_t,
 }
 
+impl Header {
+    /// 字节码文件的版本号
+    pub fn bytecode_version(&self) -> Version {
+        Version::from(self.version)
+    }
+
+    /// 文件头记录的校验和
+    pub fn checksum_value(&self) -> uint32_t {
+        uint32_t::from_le_bytes(self.checksum)
+    }
+
+    /// 校验魔数、文件大小、校验和以及各个索引是否越界
+    pub fn verify(&self, data: &[u8]) -> Result<(), error::Error> {
+        if self.magic != MAGIC {
+            return Err(error::Error::MalFormed(format!(
+                "Header: 魔数错误 {:?}",
+                String::from_utf8_lossy(&self.magic)
+            )));
+        }
+
+        if self.file_size as usize != data.len() {
+            return Err(error::Error::MalFormed(format!(
+                "Header: 文件大小不匹配，file_size: {}，实际大小: {}",
+                self.file_size,
+                data.len()
+            )));
+        }
+
+        let checksum = compute_checksum(data);
+        if checksum != self.checksum_value() {
+            return Err(error::Error::MalFormed(format!(
+                "Header: 校验和不匹配，checksum: 0x{:08x}，实际: 0x{:08x}",
+                self.checksum_value(),
+                checksum
+            )));
+        }
+
+        let sections = [
+            ("foreign", self.foreign_off, self.foreign_size as u64),
+            (
+                "class_idx",
+                self.class_idx_off,
+                self.classes_size as u64 * 4,
+            ),
+            ("lnp_idx", self.lnp_idx_off, self.num_lnps as u64 * 4),
+            (
+                "literalarray_idx",
+                self.literalarray_idx_off,
+                self.literalarrays_size as u64 * 4,
+            ),
+            (
+                "region",
+                self.region_off,
+                self.region_size as u64 * REGION_HEADER_SIZE as u64,
+            ),
+        ];
+        for (name, off, size) in sections {
+            // 大小为 0 的区域不需要校验偏移量
+            if size == 0 {
+                continue;
+            }
+            if (off as u64) < HEADER_SIZE as u64 || off as u64 + size > self.file_size as u64 {
+                return Err(error::Error::BadOffset(
+                    off as usize,
+                    format!("Header.{}: 大小 {} 越界", name, size),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl fmt::Display for Header {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let magic = String::from_utf8_lossy(&self.magic);
-        let version = self
-            .version
-            .iter()
-            .map(|x| x.to_string())
-            .collect::<Vec<_>>()
-            .join(".");
+        let version = self.bytecode_version();
+        let api = match version.api_range() {
+            Some((min, max)) if min == max => min.to_string(),
+            Some((min, max)) => format!("{} ~ {}", min, max),
+            None => "未知".to_string(),
+        };
 
         write!(
             f,
@@ -56,6 +148,7 @@ impl fmt::Display for Header {
 magic: {}
 checksum: {:?}
 版本: {}
+API 等级: {}
 文件大小: {}
 外部区域偏移: {}
 外部区域大小: {}
@@ -71,6 +164,7 @@ checksum: {:?}
             magic,
             self.checksum,
             version,
+            api,
             self.file_size,
             self.foreign_off,
             self.foreign_size,
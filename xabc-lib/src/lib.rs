@@ -1,16 +1,28 @@
 pub mod abc;
+pub mod annotation;
+pub mod assembler;
 pub mod bytecode;
+pub mod callgraph;
+pub mod cfg;
 pub mod class;
 pub mod code;
-mod error;
+pub mod dataflow;
+pub mod decompile;
+pub mod error;
 pub mod field;
 pub mod header;
 pub mod literal;
 pub mod lnp;
 pub mod method;
+pub mod pa;
+pub mod patcher;
+pub mod proto;
 pub mod region;
 pub mod source;
 pub mod string;
+pub mod version;
+pub mod writer;
+pub mod xref;
 
 use scroll::{Sleb128, Uleb128};
 
@@ -36,9 +48,10 @@ pub type sleb128_t = Sleb128;
 #[cfg(feature = "logging")]
 fn init_logging() {
     // 测试运行的时候，不需要设置 RUST_LOG=debug
-    tracing_subscriber::fmt()
+    // 多次加载文件时，日志只需要初始化一次
+    let _ = tracing_subscriber::fmt()
         .with_max_level(tracing::Level::DEBUG)
-        .init();
+        .try_init();
 }
 
 #[cfg(not(feature = "logging"))]
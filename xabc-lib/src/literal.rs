@@ -1,11 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use getset::Getters;
 
 use scroll::Pread;
 
+use crate::error::{self, ReadContext};
 use crate::{method, region::Region, string::ABCString, uint32_t};
 
 // https://gitee.com/openharmony/arkcompiler_runtime_core/blob/master/libpandafile/literal_data_accessor.h#L32
 #[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LiteralTag {
     TAG_VALUE = 0x00,
     BOOL = 0x01,
@@ -78,207 +83,468 @@ impl LiteralTag {
     }
 }
 
+/// 静态类型语言的数组，所有元素共用一个 Tag
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedArray {
+    U1(Vec<bool>),
+    U8(Vec<u8>),
+    I8(Vec<i8>),
+    U16(Vec<u16>),
+    I16(Vec<i16>),
+    U32(Vec<u32>),
+    I32(Vec<i32>),
+    U64(Vec<u64>),
+    I64(Vec<i64>),
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+    String(Vec<String>),
+}
+
+impl TypedArray {
+    fn read(
+        source: &[u8],
+        off: &mut usize,
+        tag: LiteralTag,
+        len: usize,
+    ) -> Result<TypedArray, error::Error> {
+        macro_rules! read_values {
+            ($ty:ty) => {{
+                // `len` 来自文件，不能超过剩余数据能容纳的元素个数
+                let remaining = source.len().saturating_sub(*off) / std::mem::size_of::<$ty>();
+                let mut values = Vec::with_capacity(len.min(remaining));
+                for _ in 0..len {
+                    let value = source.gread::<$ty>(off).at(*off, "Literal.value")?;
+                    values.push(value);
+                }
+                values
+            }};
+        }
+
+        let array = match tag {
+            LiteralTag::ARRAY_U1 => {
+                TypedArray::U1(read_values!(u8).into_iter().map(|v| v != 0).collect())
+            }
+            LiteralTag::ARRAY_U8 => TypedArray::U8(read_values!(u8)),
+            LiteralTag::ARRAY_I8 => TypedArray::I8(read_values!(i8)),
+            LiteralTag::ARRAY_U16 => TypedArray::U16(read_values!(u16)),
+            LiteralTag::ARRAY_I16 => TypedArray::I16(read_values!(i16)),
+            LiteralTag::ARRAY_U32 => TypedArray::U32(read_values!(u32)),
+            LiteralTag::ARRAY_I32 => TypedArray::I32(read_values!(i32)),
+            LiteralTag::ARRAY_U64 => TypedArray::U64(read_values!(u64)),
+            LiteralTag::ARRAY_I64 => TypedArray::I64(read_values!(i64)),
+            LiteralTag::ARRAY_F32 => TypedArray::F32(read_values!(f32)),
+            LiteralTag::ARRAY_F64 => TypedArray::F64(read_values!(f64)),
+            LiteralTag::ARRAY_STRING => {
+                let string_offs = read_values!(uint32_t);
+                let mut values = Vec::with_capacity(string_offs.len());
+                for string_off in string_offs {
+                    values.push(read_string(source, string_off)?);
+                }
+                TypedArray::String(values)
+            }
+            _ => {
+                return Err(error::Error::MalFormed(format!(
+                    "LiteralArray: {:?} 不是数组类型",
+                    tag
+                )))
+            }
+        };
+
+        Ok(array)
+    }
+}
+
+impl fmt::Display for TypedArray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypedArray::U1(values) => write!(f, "u1[]: {:?}", values),
+            TypedArray::U8(values) => write!(f, "u8[]: {:?}", values),
+            TypedArray::I8(values) => write!(f, "i8[]: {:?}", values),
+            TypedArray::U16(values) => write!(f, "u16[]: {:?}", values),
+            TypedArray::I16(values) => write!(f, "i16[]: {:?}", values),
+            TypedArray::U32(values) => write!(f, "u32[]: {:?}", values),
+            TypedArray::I32(values) => write!(f, "i32[]: {:?}", values),
+            TypedArray::U64(values) => write!(f, "u64[]: {:?}", values),
+            TypedArray::I64(values) => write!(f, "i64[]: {:?}", values),
+            TypedArray::F32(values) => write!(f, "f32[]: {:?}", values),
+            TypedArray::F64(values) => write!(f, "f64[]: {:?}", values),
+            TypedArray::String(values) => write!(f, "str[]: {:?}", values),
+        }
+    }
+}
+
+/// 字面量
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    TagValue(u8),
+    Bool(bool),
+    Integer(i32),
+    Float(f32),
+    Double(f64),
+    String(String),
+    /// 方法，`name` 是方法签名
+    Method {
+        offset: uint32_t,
+        name: String,
+    },
+    GeneratorMethod {
+        offset: uint32_t,
+        name: String,
+    },
+    AsyncGeneratorMethod {
+        offset: uint32_t,
+        name: String,
+    },
+    Getter {
+        offset: uint32_t,
+        name: String,
+    },
+    Setter {
+        offset: uint32_t,
+        name: String,
+    },
+    Accessor(u8),
+    /// 方法的参数个数
+    MethodAffiliate(u16),
+    LiteralBufferIndex(uint32_t),
+    /// 嵌套的字面量数组，值为它在文件中的偏移量
+    LiteralArray(uint32_t),
+    BuiltinTypeIndex(u8),
+    TypedArray(TypedArray),
+    Null,
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Literal::TagValue(value) => write!(f, "tag_value: {}", value),
+            Literal::Bool(value) => write!(f, "bool: {}", value),
+            Literal::Integer(value) => write!(f, "i32: {}", value),
+            Literal::Float(value) => write!(f, "f32: {}", value),
+            Literal::Double(value) => write!(f, "f64: {}", value),
+            Literal::String(value) => write!(f, "str: \"{}\"", value),
+            Literal::Method { name, .. } => write!(f, "method: {}", name),
+            Literal::GeneratorMethod { name, .. } => write!(f, "generator_method: {}", name),
+            Literal::AsyncGeneratorMethod { name, .. } => {
+                write!(f, "async_generator_method: {}", name)
+            }
+            Literal::Getter { name, .. } => write!(f, "getter: {}", name),
+            Literal::Setter { name, .. } => write!(f, "setter: {}", name),
+            Literal::Accessor(value) => write!(f, "accessor: {}", value),
+            Literal::MethodAffiliate(value) => write!(f, "method_affiliate: {}", value),
+            Literal::LiteralBufferIndex(value) => write!(f, "literal_buffer_index: {}", value),
+            Literal::LiteralArray(offset) => write!(f, "literal_array: 0x{:x}", offset),
+            Literal::BuiltinTypeIndex(value) => write!(f, "builtin_type_index: {}", value),
+            Literal::TypedArray(array) => write!(f, "{}", array),
+            Literal::Null => write!(f, "null_value"),
+        }
+    }
+}
+
+/// 字面量数组
 // https://developer.huawei.com/consumer/cn/doc/harmonyos-guides-V5/arkts-bytecode-file-format-V5#literalarray
-fn parse_literal_array(source: &[u8], offset: usize, region: &Region) -> String {
-    let num_literals = source.pread_with::<uint32_t>(offset, scroll::LE).unwrap();
+#[derive(Debug, Clone, Getters, Default)]
+#[get = "pub"]
+pub struct LiteralArray {
+    /// 字面量数组在文件中的偏移量
+    offset: uint32_t,
+    /// `num_literals` 的原始值，Tag 和值分别计数
+    num_literals: uint32_t,
+    entries: Vec<Literal>,
+}
 
-    let mut off = offset;
-    off += 4;
+impl fmt::Display for LiteralArray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
 
-    let mut result = String::new();
+fn read_string(source: &[u8], string_off: uint32_t) -> Result<String, error::Error> {
+    let s = source
+        .pread::<ABCString>(string_off as usize)
+        .map_err(|e| e.rebase(string_off as usize))?;
+    Ok(s.str())
+}
+
+fn parse_literal_array(
+    source: &[u8],
+    offset: usize,
+    region: &Region,
+) -> Result<LiteralArray, error::Error> {
+    let num_literals = source
+        .pread_with::<uint32_t>(offset, scroll::LE)
+        .at(offset, "LiteralArray.num_literals")?;
+
+    let off = &mut (offset + 4);
+    let mut entries = Vec::new();
+    // Tag 和值各占一个计数
     let mut counter = 0;
-    loop {
-        if counter >= num_literals {
-            break;
-        }
+    while counter < num_literals {
+        counter += 2;
 
-        counter += 1;
+        let tag_value = source.gread::<u8>(off).at(*off, "Literal.tag")?;
+        let tag = LiteralTag::from_u8(tag_value);
+        let value_off = *off;
+        let read_method = |off: &mut usize| -> Result<(uint32_t, String), error::Error> {
+            let method_off = source
+                .gread::<uint32_t>(off)
+                .at(value_off, "Literal.value")?;
+            let name = method::get_method_sign(source, method_off as usize, region)?;
+            Ok((method_off, name))
+        };
 
-        let tag_value = source.pread::<u8>(off).unwrap();
-        off += 1;
-        match LiteralTag::from_u8(tag_value) {
+        let literal = match tag {
             LiteralTag::TAG_VALUE => {
-                tracing::debug!("TaggleValue: Match");
-                off += 1;
+                Literal::TagValue(source.gread::<u8>(off).at(value_off, "Literal.value")?)
             }
             LiteralTag::BOOL => {
-                let data = source.pread::<u8>(off).unwrap();
-                off += 1;
-                let s = format!("bool: {}, ", data);
-                result += &s;
+                Literal::Bool(source.gread::<u8>(off).at(value_off, "Literal.value")? != 0)
             }
             LiteralTag::INTEGER => {
-                let data = source.pread::<u32>(off).unwrap();
-                let s = format!("i32: 0x{:X}, ", data);
-                result += &s;
-                off += 4;
+                Literal::Integer(source.gread::<i32>(off).at(value_off, "Literal.value")?)
             }
             LiteralTag::FLOAT => {
-                let data = source.pread_with::<u32>(off, scroll::BE).unwrap();
-                let s = format!("f32: {}, ", f32::from_bits(data));
-                result += &s;
-                off += 4;
+                Literal::Float(source.gread::<f32>(off).at(value_off, "Literal.value")?)
             }
             LiteralTag::DOUBLE => {
-                let data = source.pread_with::<u64>(off, scroll::BE).unwrap();
-                let s = format!("f64: {}, ", f64::from_bits(data));
-                result += &s;
-
-                off += 8;
+                Literal::Double(source.gread::<f64>(off).at(value_off, "Literal.value")?)
             }
             LiteralTag::STRING => {
-                let string_off = source.pread::<u32>(off).unwrap();
-                let str = source.pread::<ABCString>(string_off as usize).unwrap();
-                let s = format!("str: \"{}\", ", str.str());
-                result += &s;
-
-                off += 4;
+                let string_off = source
+                    .gread::<uint32_t>(off)
+                    .at(value_off, "Literal.value")?;
+                Literal::String(read_string(source, string_off)?)
             }
             LiteralTag::METHOD => {
-                let method_off = source.pread::<uint32_t>(off).unwrap();
-                let method = method::get_method_sign(source, method_off as usize, region);
-                let s = format!("Method: {}, ", method);
-                result += &s;
-
-                off += 4;
+                let (offset, name) = read_method(off)?;
+                Literal::Method { offset, name }
             }
             LiteralTag::GENERATORMETHOD => {
-                let _off = source.pread::<uint32_t>(off).unwrap();
-                let s = format!("GeneratorMethod: {}, ", _off);
-                result += &s;
-                off += 4;
-            }
-            LiteralTag::ACCESSOR => {
-                let data = source.pread::<u8>(off).unwrap();
-                let s = format!("Accessor: {}, ", data);
-                result += &s;
-                off += 1;
-            }
-            LiteralTag::METHODAFFILIATE => {
-                let data = source.pread::<u16>(off).unwrap();
-                let s = format!("MethodAffiliate: {}, ", data);
-                result += &s;
-                off += 2;
-            }
-            LiteralTag::ARRAY_U1 => {
-                tracing::debug!("ArrayU1: Match");
-                off += 4;
-            }
-            LiteralTag::ARRAY_U8 => {
-                tracing::debug!("ArrayU8: Match");
-                off += 4;
-            }
-            LiteralTag::ARRAY_I8 => {
-                tracing::debug!("ArrayI8: Match");
-                off += 4;
-            }
-            LiteralTag::ARRAY_U16 => {
-                tracing::debug!("ArrayU16: Match");
-                off += 4;
-            }
-            LiteralTag::ARRAY_I16 => {
-                tracing::debug!("ArrayI16: Match");
-                off += 4;
-            }
-            LiteralTag::ARRAY_U32 => {
-                tracing::debug!("ArrayU32: Match");
-                off += 4;
-            }
-            LiteralTag::ARRAY_I32 => {
-                tracing::debug!("ArrayI32: Match");
-                off += 4;
-            }
-            LiteralTag::ARRAY_U64 => {
-                tracing::debug!("ArrayU64: Match");
-                off += 4;
-            }
-            LiteralTag::ARRAY_I64 => {
-                tracing::debug!("Match ArrayI64");
-                off += 4;
-            }
-            LiteralTag::ARRAY_F32 => {
-                tracing::debug!("Match ArrayF32");
-                off += 4;
-            }
-            LiteralTag::ARRAY_F64 => {
-                tracing::debug!("ArrayF64: {}", tag_value);
-                off += 8;
-            }
-            LiteralTag::ARRAY_STRING => {
-                // TODO: 一个字符串数组
-                tracing::debug!("ArrayString: {}", tag_value);
-                off += 4;
+                let (offset, name) = read_method(off)?;
+                Literal::GeneratorMethod { offset, name }
             }
             LiteralTag::ASYNC_GENERATOR_METHOD => {
-                tracing::debug!("ArrayGeneratorMethod: {}", tag_value);
-                off += 4;
+                let (offset, name) = read_method(off)?;
+                Literal::AsyncGeneratorMethod { offset, name }
             }
-            LiteralTag::LITERAL_BUFFER_INDEX => {
-                tracing::debug!("LiteralBufferIndex: {}", tag_value);
-                off += 4;
+            LiteralTag::GETTER => {
+                let (offset, name) = read_method(off)?;
+                Literal::Getter { offset, name }
             }
-            LiteralTag::LITERAL_ARRAY => {
-                tracing::debug!("LiteralArr: {}", tag_value);
-                off += 4;
+            LiteralTag::SETTER => {
+                let (offset, name) = read_method(off)?;
+                Literal::Setter { offset, name }
             }
-            LiteralTag::BUILTIN_TYPE_INDEX => {
-                tracing::debug!("BuiltinTypeIndex: {}", tag_value);
-                off += 1;
+            LiteralTag::ACCESSOR => {
+                Literal::Accessor(source.gread::<u8>(off).at(value_off, "Literal.value")?)
             }
-            LiteralTag::GETTER => {
-                let data = source.pread::<uint32_t>(off).unwrap();
-                let s = format!("Getter: 0x{:X}, ", data);
-                result += &s;
-                off += 4;
+            LiteralTag::METHODAFFILIATE => {
+                Literal::MethodAffiliate(source.gread::<u16>(off).at(value_off, "Literal.value")?)
             }
-            LiteralTag::SETTER => {
-                tracing::debug!("Setter: {}", tag_value);
-                off += 4;
+            LiteralTag::LITERAL_BUFFER_INDEX => Literal::LiteralBufferIndex(
+                source
+                    .gread::<uint32_t>(off)
+                    .at(value_off, "Literal.value")?,
+            ),
+            LiteralTag::LITERAL_ARRAY => Literal::LiteralArray(
+                source
+                    .gread::<uint32_t>(off)
+                    .at(value_off, "Literal.value")?,
+            ),
+            LiteralTag::BUILTIN_TYPE_INDEX => {
+                Literal::BuiltinTypeIndex(source.gread::<u8>(off).at(value_off, "Literal.value")?)
             }
             LiteralTag::NULL_VALUE => {
-                tracing::debug!("NullValue: {}", tag_value);
-                off += 1;
+                source.gread::<u8>(off).at(value_off, "Literal.value")?;
+                Literal::Null
             }
             LiteralTag::UNKNOWN => {
-                tracing::warn!("未知的Tag: 0x{:X}", tag_value);
-                break;
+                return Err(error::Error::MalFormed(format!(
+                    "LiteralArray: unknown tag 0x{:x} at {}",
+                    tag_value,
+                    value_off - 1
+                )));
             }
-        }
+            // 静态类型语言的数组只在开头写一次 Tag，剩余的计数都是元素
+            _ => {
+                let len = num_literals.saturating_sub(1) as usize;
+                counter = num_literals;
+                Literal::TypedArray(TypedArray::read(source, off, tag, len)?)
+            }
+        };
+        entries.push(literal);
     }
 
-    result
+    Ok(LiteralArray {
+        offset: offset as uint32_t,
+        num_literals,
+        entries,
+    })
 }
 
+/// 解析 LiteralArrayIndex，`module_record_offsets` 中的模块记录不是 Tag 格式，需要跳过
+///
+/// 无法解析的字面量数组只记录警告并跳过，不影响整个文件的加载。
 pub fn parse_literal_array_index(
     source: &[u8],
     offset: uint32_t,
     num_literals: uint32_t,
     regions: &[Region],
-) -> HashMap<usize, String> {
+    module_record_offsets: &HashSet<usize>,
+) -> Result<HashMap<usize, LiteralArray>, error::Error> {
     let mut off = offset as usize;
-    let mut literal_array_map: HashMap<usize, String> = HashMap::new();
+    let mut literal_array_map: HashMap<usize, LiteralArray> = HashMap::new();
 
     for _ in 0..num_literals {
-        let array_off = source.pread::<uint32_t>(off).unwrap();
+        let array_off = source.pread::<uint32_t>(off).at(off, "LiteralArrayIndex")?;
         off += 4;
 
-        let mut region: Option<&Region> = None;
-        for item in regions.iter() {
-            if item.is_here(array_off as usize) {
-                region = Some(item);
-                break;
-            }
+        if module_record_offsets.contains(&(array_off as usize)) {
+            continue;
         }
 
-        if region.is_none() {
+        let Some(region) = regions.iter().find(|item| item.is_here(array_off as usize)) else {
             tracing::warn!("region not found");
             continue;
+        };
+
+        // 一个字面量数组解析失败时，跳过它，不影响其他的数组
+        match parse_literal_array(source, array_off as usize, region) {
+            Ok(literal) => {
+                literal_array_map.insert(array_off as usize, literal);
+            }
+            Err(e) => tracing::warn!("LiteralArray 0x{:x}: {}", array_off, e),
         }
+    }
+
+    Ok(literal_array_map)
+}
+
+/// 导入，如：`import { a as b } from "x"`
+#[derive(Debug, Clone, Getters)]
+#[get = "pub"]
+pub struct RegularImport {
+    local_name: String,
+    import_name: String,
+    /// `module_requests` 的索引
+    module_request_idx: u16,
+}
+
+/// 命名空间导入，如：`import * as a from "x"`
+#[derive(Debug, Clone, Getters)]
+#[get = "pub"]
+pub struct NamespaceImport {
+    local_name: String,
+    module_request_idx: u16,
+}
+
+/// 本地导出，如：`export { a as b }`
+#[derive(Debug, Clone, Getters)]
+#[get = "pub"]
+pub struct LocalExport {
+    local_name: String,
+    export_name: String,
+}
+
+/// 间接导出，如：`export { a as b } from "x"`
+#[derive(Debug, Clone, Getters)]
+#[get = "pub"]
+pub struct IndirectExport {
+    export_name: String,
+    import_name: String,
+    module_request_idx: u16,
+}
+
+/// 模块记录，由类的 `moduleRecordIdx` 字段指向，布局与普通的字面量数组不同。
+// https://gitee.com/openharmony/arkcompiler_runtime_core/blob/master/libpandafile/module_data_accessor-inl.h
+#[derive(Debug, Clone, Getters, Default)]
+#[get = "pub"]
+pub struct ModuleRecord {
+    offset: uint32_t,
+    /// 依赖的模块
+    module_requests: Vec<String>,
+    regular_imports: Vec<RegularImport>,
+    namespace_imports: Vec<NamespaceImport>,
+    local_exports: Vec<LocalExport>,
+    indirect_exports: Vec<IndirectExport>,
+    /// `export * from "x"`，值为 `module_requests` 的索引
+    star_exports: Vec<u16>,
+}
+
+impl ModuleRecord {
+    /// 根据索引获取依赖的模块
+    pub fn module_request(&self, idx: u16) -> Option<&String> {
+        self.module_requests.get(idx as usize)
+    }
+}
+
+pub fn parse_module_record(source: &[u8], offset: usize) -> Result<ModuleRecord, error::Error> {
+    let mut cursor = offset;
+    let off = &mut cursor;
+    let read_u32 = |off: &mut usize| -> Result<uint32_t, error::Error> {
+        let at = *off;
+        source.gread::<uint32_t>(off).at(at, "ModuleRecord")
+    };
+    let read_u16 = |off: &mut usize| -> Result<u16, error::Error> {
+        let at = *off;
+        source.gread::<u16>(off).at(at, "ModuleRecord")
+    };
+    let read_name = |off: &mut usize| -> Result<String, error::Error> {
+        let string_off = read_u32(off)?;
+        read_string(source, string_off)
+    };
+
+    // 字面量数组的 num_literals，模块记录用不到
+    read_u32(off)?;
+
+    let mut record = ModuleRecord {
+        offset: offset as uint32_t,
+        ..Default::default()
+    };
+
+    for _ in 0..read_u32(off)? {
+        record.module_requests.push(read_name(off)?);
+    }
+
+    for _ in 0..read_u32(off)? {
+        record.regular_imports.push(RegularImport {
+            local_name: read_name(off)?,
+            import_name: read_name(off)?,
+            module_request_idx: read_u16(off)?,
+        });
+    }
+
+    for _ in 0..read_u32(off)? {
+        record.namespace_imports.push(NamespaceImport {
+            local_name: read_name(off)?,
+            module_request_idx: read_u16(off)?,
+        });
+    }
+
+    for _ in 0..read_u32(off)? {
+        record.local_exports.push(LocalExport {
+            local_name: read_name(off)?,
+            export_name: read_name(off)?,
+        });
+    }
+
+    for _ in 0..read_u32(off)? {
+        record.indirect_exports.push(IndirectExport {
+            export_name: read_name(off)?,
+            import_name: read_name(off)?,
+            module_request_idx: read_u16(off)?,
+        });
+    }
 
-        let region = region.unwrap();
-        let literal = parse_literal_array(source, array_off as usize, region);
-        literal_array_map.insert(array_off as usize, literal);
+    for _ in 0..read_u32(off)? {
+        record.star_exports.push(read_u16(off)?);
     }
 
-    literal_array_map
+    Ok(record)
 }
@@ -1,5 +1,10 @@
 use getset::Getters;
+use scroll::ctx;
+use scroll::Pread;
+use scroll::{Sleb128, Uleb128};
 
+use crate::error::{self, ReadContext};
+use crate::string::ABCString;
 use crate::uint32_t;
 
 /// 根据索引访问行号程序
@@ -14,10 +19,324 @@ impl LineNumberProgramIndex {
     pub fn push(&mut self, offset: uint32_t) {
         self.offsets.push(offset);
     }
+
+    pub fn get(&self, idx: usize) -> Option<&uint32_t> {
+        self.offsets.get(idx)
+    }
+}
+
+/// 方法的调试信息，由 `MethodData` 的 `DEBUG_INFO` 指向。
+#[derive(Debug, Getters, Default)]
+#[get = "pub"]
+pub struct DebugInfo {
+    /// 行号状态机的初始行号。
+    line_start: uint32_t,
+    /// 入参和默认参数的总数量。
+    num_parameters: u64,
+    /// 参数名的偏移量，指向一个 String，0 表示没有名字。
+    parameters: Vec<uint32_t>,
+    /// 常量池，行号程序的指令从这里读取参数。
+    constant_pool: Vec<u8>,
+    /// LineNumberProgramIndex 的一个索引，指向该方法的行号程序。
+    line_number_program_idx: u64,
+}
+
+impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for DebugInfo {
+    type Error = error::Error;
+    fn try_from_ctx(source: &'a [u8], _: scroll::Endian) -> Result<(Self, usize), Self::Error> {
+        let off = &mut 0;
+        let line_start = Uleb128::read(source, off).at(*off, "DebugInfo.line_start")?;
+        let num_parameters = Uleb128::read(source, off).at(*off, "DebugInfo.num_parameters")?;
+
+        let mut parameters = Vec::new();
+        for _ in 0..num_parameters {
+            let name_off = Uleb128::read(source, off).at(*off, "DebugInfo.parameters")?;
+            parameters.push(name_off as uint32_t);
+        }
+
+        let constant_pool_size =
+            Uleb128::read(source, off).at(*off, "DebugInfo.constant_pool_size")?;
+        let constant_pool = source
+            .get(*off..)
+            .and_then(|data| data.get(..constant_pool_size as usize))
+            .at(*off, "DebugInfo.constant_pool")?
+            .to_vec();
+        *off += constant_pool.len();
+
+        let line_number_program_idx =
+            Uleb128::read(source, off).at(*off, "DebugInfo.line_number_program_idx")?;
+
+        Ok((
+            DebugInfo {
+                line_start: line_start as uint32_t,
+                num_parameters,
+                parameters,
+                constant_pool,
+                line_number_program_idx,
+            },
+            *off,
+        ))
+    }
+}
+
+/// 行号程序的操作码
+// https://gitee.com/openharmony/arkcompiler_runtime_core/blob/master/static_core/docs/file_format.md#line-number-program
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineNumberOpcode {
+    END_SEQUENCE = 0x00,
+    ADVANCE_PC = 0x01,
+    ADVANCE_LINE = 0x02,
+    START_LOCAL = 0x03,
+    START_LOCAL_EXTENDED = 0x04,
+    END_LOCAL = 0x05,
+    RESTART_LOCAL = 0x06,
+    SET_PROLOGUE_END = 0x07,
+    SET_EPILOGUE_BEGIN = 0x08,
+    SET_FILE = 0x09,
+    SET_SOURCE_CODE = 0x0a,
+    SET_COLUMN = 0x0b,
+}
+
+impl LineNumberOpcode {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        let opcode = match value {
+            0x00 => LineNumberOpcode::END_SEQUENCE,
+            0x01 => LineNumberOpcode::ADVANCE_PC,
+            0x02 => LineNumberOpcode::ADVANCE_LINE,
+            0x03 => LineNumberOpcode::START_LOCAL,
+            0x04 => LineNumberOpcode::START_LOCAL_EXTENDED,
+            0x05 => LineNumberOpcode::END_LOCAL,
+            0x06 => LineNumberOpcode::RESTART_LOCAL,
+            0x07 => LineNumberOpcode::SET_PROLOGUE_END,
+            0x08 => LineNumberOpcode::SET_EPILOGUE_BEGIN,
+            0x09 => LineNumberOpcode::SET_FILE,
+            0x0a => LineNumberOpcode::SET_SOURCE_CODE,
+            0x0b => LineNumberOpcode::SET_COLUMN,
+            _ => return None,
+        };
+        Some(opcode)
+    }
+}
+
+/// 特殊操作码的起始值，大于等于它的操作码同时修改 pc 和行号。
+const OPCODE_BASE: u8 = 0x0c;
+const LINE_BASE: i32 = -4;
+const LINE_RANGE: u8 = 15;
+
+/// 指令对应的源码位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourcePosition {
+    /// 指令在 Code 的 instructions 中的偏移量
+    pub pc: u32,
+    pub line: u32,
+    pub column: u32,
+}
+
+/// 局部变量
+#[derive(Debug, Clone, Getters)]
+#[get = "pub"]
+pub struct LocalVariable {
+    /// 存放变量的寄存器
+    reg: i64,
+    name: String,
+    type_name: String,
+    /// 类型签名，只有 `START_LOCAL_EXTENDED` 才有
+    type_signature: Option<String>,
+    /// 变量生效的第一条指令
+    start_pc: u32,
+    /// 变量失效的第一条指令，`None` 表示直到方法结束
+    end_pc: Option<u32>,
+}
+
+/// 行号程序的执行结果
+#[derive(Debug, Getters, Default)]
+#[get = "pub"]
+pub struct LineNumberTable {
+    /// 按 pc 排序的源码位置
+    positions: Vec<SourcePosition>,
+    local_variables: Vec<LocalVariable>,
+    /// 源文件
+    source_file: Option<String>,
+    /// 源码
+    source_code: Option<String>,
+}
+
+impl LineNumberTable {
+    /// 查找 pc 对应的源码位置
+    pub fn position_of(&self, pc: u32) -> Option<&SourcePosition> {
+        let idx = self.positions.partition_point(|pos| pos.pc <= pc);
+        if idx == 0 {
+            return None;
+        }
+        self.positions.get(idx - 1)
+    }
+
+    /// 同一个 pc 只保留最后的位置
+    fn push_position(&mut self, pc: u32, line: u32, column: u32) {
+        let pos = SourcePosition { pc, line, column };
+        match self.positions.last_mut() {
+            Some(last) if last.pc == pc => *last = pos,
+            _ => self.positions.push(pos),
+        }
+    }
+}
+
+/// 前移 pc，超出 u32 范围说明行号程序是畸形的
+fn advance_pc(pc: u32, delta: u64, offset: usize) -> Result<u32, error::Error> {
+    u32::try_from(delta)
+        .ok()
+        .and_then(|delta| pc.checked_add(delta))
+        .ok_or_else(|| {
+            error::Error::MalFormed(format!(
+                "LineNumberProgram at 0x{:x}: pc overflows u32",
+                offset
+            ))
+        })
 }
 
 /// 行号程序由指令组成。每条指令都有一个字节的操作码和可选参数。
 /// 根据 opcode 参数的值可能被编码到指令中，或者指令需要从常量池中读取值。
-// NOTE: 不知道有啥作用，先不处理。
-#[allow(dead_code)]
-struct LineNumberProgram {}
+pub struct LineNumberProgram<'a> {
+    source: &'a [u8],
+    /// 行号程序在文件中的偏移量
+    offset: usize,
+}
+
+impl<'a> LineNumberProgram<'a> {
+    pub fn new(source: &'a [u8], offset: usize) -> Self {
+        Self { source, offset }
+    }
+
+    fn get_string(&self, off: u64) -> Result<Option<String>, error::Error> {
+        if off == 0 {
+            return Ok(None);
+        }
+
+        let s = self
+            .source
+            .pread::<ABCString>(off as usize)
+            .map_err(|e| e.rebase(off as usize))?;
+        Ok(Some(s.str()))
+    }
+
+    /// 执行行号程序，得到 pc 和源码位置的对应关系以及局部变量
+    pub fn run(&self, debug_info: &DebugInfo) -> Result<LineNumberTable, error::Error> {
+        let program = self
+            .source
+            .get(self.offset..)
+            .at(self.offset, "LineNumberProgram")?;
+        let constant_pool = debug_info.constant_pool();
+        let pc_off = &mut 0;
+        let cp_off = &mut 0;
+
+        let mut table = LineNumberTable::default();
+        let mut pc: u32 = 0;
+        let mut line = debug_info.line_start;
+        let mut column: u32 = 0;
+
+        loop {
+            let opcode = program
+                .pread::<u8>(*pc_off)
+                .at(self.offset + *pc_off, "LineNumberProgram")?;
+            *pc_off += 1;
+
+            let Some(opcode) = LineNumberOpcode::from_u8(opcode) else {
+                // 特殊操作码
+                let adjusted = opcode - OPCODE_BASE;
+                pc = advance_pc(
+                    pc,
+                    (adjusted / LINE_RANGE) as u64,
+                    self.offset + *pc_off - 1,
+                )?;
+                line = line.wrapping_add_signed(LINE_BASE + (adjusted % LINE_RANGE) as i32);
+                table.push_position(pc, line, column);
+                continue;
+            };
+
+            match opcode {
+                LineNumberOpcode::END_SEQUENCE => break,
+                LineNumberOpcode::ADVANCE_PC => {
+                    let value = Uleb128::read(constant_pool, cp_off)
+                        .at(*cp_off, "DebugInfo.constant_pool")?;
+                    pc = advance_pc(pc, value, self.offset + *pc_off - 1)?;
+                }
+                LineNumberOpcode::ADVANCE_LINE => {
+                    let value = Sleb128::read(constant_pool, cp_off)
+                        .at(*cp_off, "DebugInfo.constant_pool")?;
+                    line = line.wrapping_add_signed(value as i32);
+                }
+                LineNumberOpcode::START_LOCAL | LineNumberOpcode::START_LOCAL_EXTENDED => {
+                    let reg = Sleb128::read(program, pc_off)
+                        .at(self.offset + *pc_off, "LineNumberProgram")?;
+                    let name_off = Uleb128::read(constant_pool, cp_off)
+                        .at(*cp_off, "DebugInfo.constant_pool")?;
+                    let type_off = Uleb128::read(constant_pool, cp_off)
+                        .at(*cp_off, "DebugInfo.constant_pool")?;
+                    let type_signature = if opcode == LineNumberOpcode::START_LOCAL_EXTENDED {
+                        let sig_off = Uleb128::read(constant_pool, cp_off)
+                            .at(*cp_off, "DebugInfo.constant_pool")?;
+                        self.get_string(sig_off)?
+                    } else {
+                        None
+                    };
+
+                    table.local_variables.push(LocalVariable {
+                        reg,
+                        name: self.get_string(name_off)?.unwrap_or_default(),
+                        type_name: self.get_string(type_off)?.unwrap_or_default(),
+                        type_signature,
+                        start_pc: pc,
+                        end_pc: None,
+                    });
+                }
+                LineNumberOpcode::END_LOCAL => {
+                    let reg = Sleb128::read(program, pc_off)
+                        .at(self.offset + *pc_off, "LineNumberProgram")?;
+                    if let Some(var) = table
+                        .local_variables
+                        .iter_mut()
+                        .rev()
+                        .find(|var| var.reg == reg && var.end_pc.is_none())
+                    {
+                        var.end_pc = Some(pc);
+                    }
+                }
+                LineNumberOpcode::RESTART_LOCAL => {
+                    let reg = Sleb128::read(program, pc_off)
+                        .at(self.offset + *pc_off, "LineNumberProgram")?;
+                    if let Some(var) = table
+                        .local_variables
+                        .iter()
+                        .rev()
+                        .find(|var| var.reg == reg)
+                    {
+                        let mut var = var.clone();
+                        var.start_pc = pc;
+                        var.end_pc = None;
+                        table.local_variables.push(var);
+                    }
+                }
+                LineNumberOpcode::SET_PROLOGUE_END | LineNumberOpcode::SET_EPILOGUE_BEGIN => {}
+                LineNumberOpcode::SET_FILE => {
+                    let value = Uleb128::read(constant_pool, cp_off)
+                        .at(*cp_off, "DebugInfo.constant_pool")?;
+                    table.source_file = self.get_string(value)?;
+                }
+                LineNumberOpcode::SET_SOURCE_CODE => {
+                    let value = Uleb128::read(constant_pool, cp_off)
+                        .at(*cp_off, "DebugInfo.constant_pool")?;
+                    table.source_code = self.get_string(value)?;
+                }
+                LineNumberOpcode::SET_COLUMN => {
+                    let value = Uleb128::read(constant_pool, cp_off)
+                        .at(*cp_off, "DebugInfo.constant_pool")?;
+                    column = value as u32;
+                    table.push_position(pc, line, column);
+                }
+            }
+        }
+
+        Ok(table)
+    }
+}
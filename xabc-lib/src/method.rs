@@ -2,7 +2,9 @@ use getset::Getters;
 use scroll::Uleb128;
 use tracing::debug;
 
-use crate::error;
+use crate::annotation::{self, Annotation, AnnotationKind};
+use crate::error::{self, ReadContext};
+use crate::proto::{MethodSignature, Proto};
 use crate::region::Region;
 use crate::string::ABCString;
 use crate::uint16_t;
@@ -65,13 +67,15 @@ pub struct MethodData {
     /// 指向方法的 Code 对象的偏移量。
     code_off: uint32_t,
     source_lang: uint8_t,
-    runtime_annotation_off: uint32_t,
+    /// 注解可以有多个，值都是指向 Annotation 的偏移量
+    runtime_annotation_offs: Vec<uint32_t>,
+    /// 指向 ParamAnnotations 的偏移量，0 表示无
     runtime_param_annotation_off: uint32_t,
     debug_info_off: uint32_t,
-    annotation_off: uint32_t,
+    annotation_offs: Vec<uint32_t>,
     param_annotation_off: uint32_t,
-    type_annotation_off: uint32_t,
-    runtime_type_annotation_off: uint32_t,
+    type_annotation_offs: Vec<uint32_t>,
+    runtime_type_annotation_offs: Vec<uint32_t>,
 }
 
 #[derive(Debug, Getters, Default)]
@@ -88,44 +92,133 @@ pub struct Method {
     // method_data: Vec,
     size: usize,
     method_data: MethodData,
+    /// 解析后的注解，由 AbcFile 加载时填充
+    annotations: Vec<Annotation>,
+    /// 参数的注解，按参数的顺序排列，运行时注解在前
+    param_annotations: Vec<Vec<Annotation>>,
 }
 
-impl Method {}
+/// `proto_idx` 为该值时，表示方法没有原型。
+const NO_PROTO: uint16_t = 0xffff;
 
-// TODO: 方法签名还不完整
-pub fn get_method_sign(source: &[u8], offset: usize, region: &Region) -> String {
-    let mut name = String::new();
-    let mut off = offset;
-    let class_idx = source.pread::<uint16_t>(off).unwrap();
-    off += 2;
-    let class_name = region.get_class_name(class_idx as usize).to_string();
-    name += &class_name;
-    name += "->";
+impl Method {
+    /// 获取方法的返回值类型和参数类型
+    ///
+    /// 动态类型的方法没有原型，根据 Code 的参数数量生成 `any` 类型的签名，与 `ark_disasm` 的输出一致。
+    pub fn proto(&self, source: &[u8], region: &Region) -> Result<MethodSignature, error::Error> {
+        if self.proto_idx != NO_PROTO {
+            let proto_off = region
+                .proto_region_idx()
+                .offsets()
+                .get(self.proto_idx as usize)
+                .at(self.proto_idx as usize, "Method.proto_idx")?;
+            let proto = source
+                .pread::<Proto>(*proto_off as usize)
+                .map_err(|e| e.rebase(*proto_off as usize))?;
+            return proto.resolve(source, region);
+        }
+
+        let mut code_off = self.method_data.code_off as usize;
+        if code_off == 0 {
+            return Proto::tagged(0).resolve(source, region);
+        }
+
+        let off = &mut code_off;
+        let _num_regs = Uleb128::read(source, off).at(*off, "Code.num_regs")?;
+        let num_args = Uleb128::read(source, off).at(*off, "Code.num_args")?;
+        Proto::tagged(num_args as usize).resolve(source, region)
+    }
+
+    /// 解析方法及其参数的注解
+    pub(crate) fn resolve_annotations(&mut self, source: &[u8], region: &Region) {
+        let data = &self.method_data;
+        self.annotations = annotation::parse_annotations(
+            source,
+            region,
+            [
+                (AnnotationKind::Runtime, &data.runtime_annotation_offs),
+                (AnnotationKind::Plain, &data.annotation_offs),
+                (
+                    AnnotationKind::RuntimeType,
+                    &data.runtime_type_annotation_offs,
+                ),
+                (AnnotationKind::Type, &data.type_annotation_offs),
+            ],
+        );
+
+        let mut param_annotations: Vec<Vec<Annotation>> = Vec::new();
+        for (kind, offset) in [
+            (AnnotationKind::Runtime, data.runtime_param_annotation_off),
+            (AnnotationKind::Plain, data.param_annotation_off),
+        ] {
+            if offset == 0 {
+                continue;
+            }
+            let params =
+                match annotation::parse_param_annotations(source, offset as usize, kind, region) {
+                    Ok(params) => params,
+                    Err(e) => {
+                        tracing::warn!("ParamAnnotations 0x{:x}: {}", offset, e);
+                        continue;
+                    }
+                };
+            for (i, annotations) in params.into_iter().enumerate() {
+                match param_annotations.get_mut(i) {
+                    Some(param) => param.extend(annotations),
+                    None => param_annotations.push(annotations),
+                }
+            }
+        }
+        self.param_annotations = param_annotations;
+    }
+
+    pub(crate) fn has_annotations(&self) -> bool {
+        let data = &self.method_data;
+        !(data.runtime_annotation_offs.is_empty()
+            && data.annotation_offs.is_empty()
+            && data.type_annotation_offs.is_empty()
+            && data.runtime_type_annotation_offs.is_empty())
+            || data.runtime_param_annotation_off != 0
+            || data.param_annotation_off != 0
+    }
+}
+
+/// 读取 `offset` 处的方法，返回 `类名->方法名(参数类型)返回值类型`
+pub fn get_method_sign(
+    source: &[u8],
+    offset: usize,
+    region: &Region,
+) -> Result<String, error::Error> {
+    let method = source
+        .pread::<Method>(offset)
+        .map_err(|e| e.rebase(offset))?;
 
-    // TODO: 获取方法签名，获取参数
-    let _proto_idx = source.pread::<uint16_t>(off).unwrap();
-    off += 2;
+    let class_idx = method.class_idx as usize;
+    let class_name = region
+        .get_type(class_idx)
+        .at(offset, "Method.class_idx")?
+        .name(source)?;
 
-    let name_idx = source.pread::<uint32_t>(off).unwrap();
+    let name_off = method.name_off as usize;
     let method_name = source
-        .pread::<ABCString>(name_idx as usize)
-        .unwrap()
+        .pread::<ABCString>(name_off)
+        .map_err(|e| e.rebase(name_off))?
         .to_string();
 
-    name += &method_name;
+    let proto = method.proto(source, region)?;
 
-    name
+    Ok(format!("{}->{}{}", class_name, method_name, proto))
 }
 
 impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Method {
     type Error = error::Error;
     fn try_from_ctx(source: &'a [u8], _: scroll::Endian) -> Result<(Self, usize), Self::Error> {
-        let class_idx = source.pread::<uint16_t>(0).unwrap();
-        let proto_idx = source.pread::<uint16_t>(2).unwrap();
-        let name_off = source.pread::<uint32_t>(4).unwrap();
+        let class_idx = source.pread::<uint16_t>(0).at(0, "Method.class_idx")?;
+        let proto_idx = source.pread::<uint16_t>(2).at(2, "Method.proto_idx")?;
+        let name_off = source.pread::<uint32_t>(4).at(4, "Method.name_off")?;
 
         let off = &mut 8;
-        let access_flags = Uleb128::read(source, off).unwrap();
+        let access_flags = Uleb128::read(source, off).at(*off, "Method.access_flags")?;
         let access_flags = MethodAccessFlags::parse(access_flags);
 
         // 解析 method_data
@@ -133,7 +226,7 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Method {
         let mut method_data = MethodData::default();
         // NOTE: 数据保存
         'l: loop {
-            let tag_value = source.pread::<u8>(*off).unwrap();
+            let tag_value = source.pread::<u8>(*off).at(*off, "Method.method_data")?;
             *off += 1;
 
             match tag_value {
@@ -142,62 +235,79 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Method {
                     break 'l;
                 }
                 0x01 => {
-                    let code_off = source.pread::<uint32_t>(*off).unwrap();
+                    let code_off = source.pread::<uint32_t>(*off).at(*off, "Method.CODE")?;
                     *off += 4;
                     method_data.code_off = code_off;
                     debug!("CODE {:?}", code_off);
                 }
                 0x02 => {
-                    let data = source.pread::<u8>(*off).unwrap();
+                    let data = source.pread::<u8>(*off).at(*off, "Method.SOURCE_LANG")?;
                     *off += 1;
                     method_data.source_lang = data;
                     debug!("SOURCE_LANG {:?}", data);
                 }
                 0x03 => {
-                    let data = source.pread::<uint32_t>(*off).unwrap();
+                    let data = source
+                        .pread::<uint32_t>(*off)
+                        .at(*off, "Method.RUNTIME_ANNOTATION")?;
                     *off += 4;
-                    method_data.runtime_annotation_off = data;
+                    method_data.runtime_annotation_offs.push(data);
                     debug!("RUNTIME_ANNOTATION {:?}", data);
                 }
                 0x04 => {
-                    let data = source.pread::<uint32_t>(*off).unwrap();
+                    let data = source
+                        .pread::<uint32_t>(*off)
+                        .at(*off, "Method.RUNTIME_PARAM_ANNOTATION")?;
                     *off += 4;
                     method_data.runtime_param_annotation_off = data;
                     debug!("RUNTIME_PARAM_ANNOTATION {:?}", data);
                 }
                 0x05 => {
-                    let data = source.pread::<uint32_t>(*off).unwrap();
+                    let data = source
+                        .pread::<uint32_t>(*off)
+                        .at(*off, "Method.DEBUG_INFO")?;
                     *off += 4;
                     method_data.debug_info_off = data;
                     debug!("DEBUG_INFO {:?}", data);
                 }
                 0x06 => {
-                    let data = source.pread::<uint32_t>(*off).unwrap();
+                    let data = source
+                        .pread::<uint32_t>(*off)
+                        .at(*off, "Method.ANNOTATION")?;
                     *off += 4;
-                    method_data.annotation_off = data;
+                    method_data.annotation_offs.push(data);
                     debug!("ANNOTATION {:?}", data);
                 }
                 0x07 => {
-                    let data = source.pread::<uint32_t>(*off).unwrap();
+                    let data = source
+                        .pread::<uint32_t>(*off)
+                        .at(*off, "Method.PARAM_ANNOTATION")?;
                     *off += 4;
                     method_data.param_annotation_off = data;
                     debug!("PARAM_ANNOTATION {:?}", data);
                 }
                 0x08 => {
-                    let data = source.pread::<uint32_t>(*off).unwrap();
+                    let data = source
+                        .pread::<uint32_t>(*off)
+                        .at(*off, "Method.TYPE_ANNOTATION")?;
                     *off += 4;
-                    method_data.type_annotation_off = data;
+                    method_data.type_annotation_offs.push(data);
                     debug!("TYPE_ANNOTATION {:?}", data);
                 }
                 0x09 => {
-                    let data = source.pread::<uint32_t>(*off).unwrap();
+                    let data = source
+                        .pread::<uint32_t>(*off)
+                        .at(*off, "Method.RUNTIME_TYPE_ANNOTATION")?;
                     *off += 4;
-                    method_data.runtime_type_annotation_off = data;
+                    method_data.runtime_type_annotation_offs.push(data);
                     debug!("RUNTIME_TYPE_ANNOTATION {:?}", data);
                 }
                 _ => {
-                    // FIXME: 这种情况是不可能出现，一定有问题。
-                    tracing::error!("Method Data: UNKNOWN 0x{:02X}", tag_value);
+                    return Err(error::Error::MalFormed(format!(
+                        "Method: unknown tag 0x{:02X} at {}",
+                        tag_value,
+                        *off - 1
+                    )));
                 }
             }
         }
@@ -211,6 +321,8 @@ impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Method {
                 name_off,
                 access_flags,
                 method_data,
+                annotations: Vec::new(),
+                param_annotations: Vec::new(),
                 size,
             },
             source.len(),
@@ -0,0 +1,113 @@
+use std::fmt::Write;
+
+use crate::bytecode::{Instruction, Operand, BYTE_MNEMONIC};
+use crate::literal::{Literal, LiteralArray};
+use crate::proto::MethodSignature;
+
+/// .pa 文件中各个段之间的分隔注释，格式与 `ark_disasm` 一致
+pub const SECTION_SEPARATOR: &str = "# ====================";
+
+/// 类型描述符转换为 pandasm 中的名字，如：`Lcom/a/B;` -> `com.a.B`
+pub fn record_name(descriptor: &str) -> String {
+    descriptor
+        .strip_prefix('L')
+        .and_then(|name| name.strip_suffix(';'))
+        .unwrap_or(descriptor)
+        .replace('/', ".")
+}
+
+/// 方法在 pandasm 中的名字，如：`com.a.B.func_main_0`
+pub fn function_name(class_name: &str, method_name: &str) -> String {
+    format!("{}.{}", record_name(class_name), method_name)
+}
+
+/// 方法签名 `类名->方法名(参数类型)返回值类型` 转换为 pandasm 中的名字
+fn method_ref(sign: &str) -> String {
+    match sign.split_once("->") {
+        Some((class_name, method)) => {
+            let method = method.split_once('(').map_or(method, |(m, _)| m);
+            function_name(class_name, method)
+        }
+        None => sign.to_string(),
+    }
+}
+
+/// `.function` 的声明，如：`.function any com.a.B.foo(any a0, any a1)`
+pub fn function_header(
+    class_name: &str,
+    method_name: &str,
+    signature: &MethodSignature,
+    access_flags: &[String],
+) -> String {
+    let params: Vec<String> = signature
+        .params()
+        .iter()
+        .enumerate()
+        .map(|(i, ty)| format!("{} a{}", ty, i))
+        .collect();
+    let mut header = format!(
+        ".function {} {}({})",
+        signature.return_type(),
+        function_name(class_name, method_name),
+        params.join(", ")
+    );
+    if access_flags.iter().any(|flag| flag == "Static") {
+        header.push_str(" <static>");
+    }
+    header
+}
+
+/// 字面量数组，如：`0 0x1a2b { 2 [ i32: 1, str: "a", ]}`
+pub fn literal_array(idx: usize, array: &LiteralArray) -> String {
+    let mut text = format!(
+        "{} 0x{:x} {{ {} [\n",
+        idx,
+        array.offset(),
+        array.entries().len()
+    );
+    for entry in array.entries() {
+        let _ = match entry {
+            Literal::Method { name, .. } => writeln!(text, "\tmethod: {},", method_ref(name)),
+            Literal::GeneratorMethod { name, .. } => {
+                writeln!(text, "\tgenerator_method: {},", method_ref(name))
+            }
+            Literal::AsyncGeneratorMethod { name, .. } => {
+                writeln!(text, "\tasync_generator_method: {},", method_ref(name))
+            }
+            Literal::Getter { name, .. } => writeln!(text, "\tgetter: {},", method_ref(name)),
+            Literal::Setter { name, .. } => writeln!(text, "\tsetter: {},", method_ref(name)),
+            entry => writeln!(text, "\t{},", entry),
+        };
+    }
+    text.push_str("]}");
+    text
+}
+
+/// 指令的汇编形式，`num_regs` 及以上的寄存器是参数，输出为 `aN`
+pub fn instruction(instruction: &Instruction, num_regs: u64) -> String {
+    let operands: Vec<String> = instruction
+        .operands()
+        .iter()
+        .map(|operand| match operand {
+            Operand::Register(reg) if *reg as u64 >= num_regs => {
+                format!("a{}", *reg as u64 - num_regs)
+            }
+            Operand::Imm(value) => format!("0x{:x}", value),
+            Operand::Method { name, .. } => method_ref(name),
+            Operand::LiteralArray { offset, .. } => format!("0x{:x}", offset),
+            operand => operand.to_string(),
+        })
+        .collect();
+
+    let mut text = instruction.mnemonic().clone();
+    if !operands.is_empty() {
+        text.push(' ');
+        text.push_str(&operands.join(", "));
+    }
+    if instruction.mnemonic() == BYTE_MNEMONIC {
+        if let Some(diagnostic) = instruction.diagnostic() {
+            let _ = write!(text, " # {}", diagnostic);
+        }
+    }
+    text
+}
@@ -0,0 +1,247 @@
+/// 原地修改 abc 文件：替换指令和字符串，所有修改都不改变文件的大小和布局
+use std::collections::BTreeSet;
+use std::{fs, path::Path};
+
+use scroll::Pread;
+
+use crate::abc::{AbcFile, AbcReader};
+use crate::assembler::{self, Arg, CodeBuilder};
+use crate::bytecode::{Instruction, Operand};
+use crate::code::Code;
+use crate::error;
+use crate::header;
+use crate::region::Region;
+use crate::string::ABCString;
+use crate::uint32_t;
+
+/// 条件相反的跳转指令
+const INVERTED_BRANCHES: [(&str, &str); 8] = [
+    ("jeqz", "jnez"),
+    ("jstricteqz", "jnstricteqz"),
+    ("jeqnull", "jnenull"),
+    ("jstricteqnull", "jnstricteqnull"),
+    ("jequndefined", "jneundefined"),
+    ("jstrictequndefined", "jnstrictequndefined"),
+    ("jeq", "jne"),
+    ("jstricteq", "jnstricteq"),
+];
+
+/// 用于修改 `Abc` 文件，修改完成后调用 `finish` 重新计算校验和
+pub struct AbcPatcher {
+    /// 修改前的文件，用于查找方法和字符串
+    abc: AbcFile<Vec<u8>>,
+    data: Vec<u8>,
+}
+
+impl AbcPatcher {
+    pub fn new(data: Vec<u8>) -> Result<Self, error::Error> {
+        let abc = AbcReader::from_vec(data.clone())?;
+        Ok(Self { abc, data })
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, error::Error> {
+        Self::new(fs::read(path)?)
+    }
+
+    /// 修改前的文件
+    pub fn abc(&self) -> &AbcFile<Vec<u8>> {
+        &self.abc
+    }
+
+    /// 当前的文件内容，校验和在 `finish` 时才更新
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// 解码方法修改后的指令
+    pub fn instructions(&self, method: &str) -> Result<Vec<Instruction>, error::Error> {
+        let (region, code_off) = locate(&self.abc, method)?;
+        decode(&self.abc, region, &self.data, code_off).map(|(_, instructions)| instructions)
+    }
+
+    /// 将 `pc` 处的指令替换为新指令，新指令比原指令短时用 `nop` 补齐
+    ///
+    /// 新指令按字节码表编码，写入后重新解码校验，失败时撤销修改。
+    pub fn patch_instruction(
+        &mut self,
+        method: &str,
+        pc: usize,
+        mnemonic: &str,
+        args: &[Arg],
+    ) -> Result<(), error::Error> {
+        let (region, code_off) = locate(&self.abc, method)?;
+        let (code, instructions) = decode(&self.abc, region, &self.data, code_off)?;
+        let old = find_instruction(&instructions, pc)?;
+        let size = old.size();
+
+        let parser = self.abc.bytecode_parser();
+        let data = &self.data;
+        let resolve = |arg: &Arg| match arg {
+            Arg::String(value) => string_index(region, data, value),
+            _ => None,
+        };
+        let mut builder = CodeBuilder::new(0, 0);
+        builder.emit(mnemonic, args);
+        let mut bytes = assembler::assemble(&builder, &parser, &resolve)?.instructions;
+        if bytes.len() > size {
+            return Err(error::Error::MalFormed(format!(
+                "新指令 {} 为 {} 字节，超过原指令 {} 的 {} 字节",
+                mnemonic,
+                bytes.len(),
+                old.mnemonic(),
+                size
+            )));
+        }
+        let mut nop = CodeBuilder::new(0, 0);
+        nop.emit("nop", &[]);
+        let nop = assembler::assemble(&nop, &parser, &resolve)?.instructions;
+        bytes.resize(size, nop[0]);
+
+        let start = code_off as usize + code.instructions_off() + pc;
+        let backup = self.data[start..start + size].to_vec();
+        self.data[start..start + size].copy_from_slice(&bytes);
+
+        let (_, patched) = decode(&self.abc, region, &self.data, code_off)?;
+        let valid = patched
+            .iter()
+            .filter(|i| pc <= *i.offset() && *i.offset() < pc + size)
+            .all(|i| !i.is_unknown())
+            && patched
+                .iter()
+                .any(|i| *i.offset() == pc && i.mnemonic() == mnemonic);
+        if !valid {
+            self.data[start..start + size].copy_from_slice(&backup);
+            return Err(error::Error::MalFormed(format!(
+                "修改后无法解码 {:#x} 处的指令 {}",
+                pc, mnemonic
+            )));
+        }
+        Ok(())
+    }
+
+    /// 将 `pc` 处的指令替换为 `nop`
+    pub fn nop(&mut self, method: &str, pc: usize) -> Result<(), error::Error> {
+        self.patch_instruction(method, pc, "nop", &[])
+    }
+
+    /// 反转 `pc` 处的条件跳转，如 `jeqz` 改为 `jnez`，跳转目标不变
+    pub fn invert_branch(&mut self, method: &str, pc: usize) -> Result<(), error::Error> {
+        let instructions = self.instructions(method)?;
+        let old = find_instruction(&instructions, pc)?;
+        let mnemonic = INVERTED_BRANCHES
+            .iter()
+            .find_map(|(a, b)| match old.mnemonic().as_str() {
+                m if m == *a => Some(*b),
+                m if m == *b => Some(*a),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                error::Error::InvalidId(format!("{} 不是条件跳转指令", old.mnemonic()))
+            })?;
+
+        let args: Vec<Arg> = old
+            .operands()
+            .iter()
+            .filter_map(|operand| match operand {
+                Operand::Register(reg) => Some(Arg::Reg(*reg)),
+                Operand::Branch { offset, .. } => Some(Arg::Imm(*offset as i64)),
+                _ => None,
+            })
+            .collect();
+        self.patch_instruction(method, pc, mnemonic, &args)
+    }
+
+    /// 替换指令中引用的字符串常量，新字符串编码后的长度必须与原字符串相同，返回替换的数量
+    pub fn replace_string(&mut self, old: &str, new: &str) -> Result<usize, error::Error> {
+        let old_bytes = ABCString::encode(old);
+        let new_bytes = ABCString::encode(new);
+        if old_bytes.len() != new_bytes.len() {
+            return Err(error::Error::MalFormed(format!(
+                "字符串长度不同: \"{}\" {} 字节，\"{}\" {} 字节",
+                old,
+                old_bytes.len(),
+                new,
+                new_bytes.len()
+            )));
+        }
+
+        let offsets: BTreeSet<usize> = self
+            .abc
+            .regions()
+            .iter()
+            .flat_map(|region| region.method_string_literal_region_idx().offsets())
+            .map(|off| *off as usize)
+            .filter(|off| self.data.get(*off..*off + old_bytes.len()) == Some(old_bytes.as_slice()))
+            .collect();
+        for off in &offsets {
+            self.data[*off..*off + new_bytes.len()].copy_from_slice(&new_bytes);
+        }
+        Ok(offsets.len())
+    }
+
+    /// 重新计算校验和，返回修改后的文件内容
+    pub fn finish(mut self) -> Vec<u8> {
+        let checksum = header::compute_checksum(&self.data);
+        self.data[8..12].copy_from_slice(&checksum.to_le_bytes());
+        self.data
+    }
+
+    /// 重新计算校验和，并写入 `path`
+    pub fn write_file<P: AsRef<Path>>(self, path: P) -> Result<(), error::Error> {
+        fs::write(path, self.finish())?;
+        Ok(())
+    }
+}
+
+/// 方法所在的 Region 和 Code 的偏移量
+fn locate<'a>(
+    abc: &'a AbcFile<Vec<u8>>,
+    method: &str,
+) -> Result<(&'a Region, uint32_t), error::Error> {
+    let (region, m) = abc.find_method(method)?;
+    let code_off = *m.method_data().code_off();
+    if code_off == 0 {
+        return Err(error::Error::InvalidId(format!(
+            "方法没有 Code: {}",
+            method
+        )));
+    }
+    Ok((region, code_off))
+}
+
+/// 从 `data` 中读取 Code 并解码指令
+fn decode(
+    abc: &AbcFile<Vec<u8>>,
+    region: &Region,
+    data: &[u8],
+    code_off: uint32_t,
+) -> Result<(Code, Vec<Instruction>), error::Error> {
+    let code = data
+        .pread::<Code>(code_off as usize)
+        .map_err(|e| e.rebase(code_off as usize))?;
+    let instructions = abc
+        .bytecode_parser()
+        .parse(&code, region, data, abc.literal_arrays())?;
+    Ok((code, instructions))
+}
+
+fn find_instruction(instructions: &[Instruction], pc: usize) -> Result<&Instruction, error::Error> {
+    instructions
+        .iter()
+        .find(|i| *i.offset() == pc)
+        .ok_or_else(|| error::Error::InvalidId(format!("{:#x} 不是指令的起始位置", pc)))
+}
+
+/// 字符串在 MethodStringLiteralRegionIndex 中的索引
+fn string_index(region: &Region, data: &[u8], value: &str) -> Option<u16> {
+    let encoded = ABCString::encode(value);
+    region
+        .method_string_literal_region_idx()
+        .offsets()
+        .iter()
+        .position(|off| {
+            let off = *off as usize;
+            data.get(off..off + encoded.len()) == Some(encoded.as_slice())
+        })
+        .map(|idx| idx as u16)
+}
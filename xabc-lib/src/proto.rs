@@ -0,0 +1,182 @@
+/// 方法原型，描述方法的返回值类型和参数类型。
+use std::fmt;
+
+use getset::Getters;
+use scroll::ctx;
+use scroll::Pread;
+
+use crate::error::{self, ReadContext};
+use crate::region::Region;
+use crate::uint16_t;
+
+/// Shorty 中的类型编码，每个类型占 4 位。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortyType {
+    Void = 0x01,
+    U1 = 0x02,
+    I8 = 0x03,
+    U8 = 0x04,
+    I16 = 0x05,
+    U16 = 0x06,
+    I32 = 0x07,
+    U32 = 0x08,
+    F32 = 0x09,
+    F64 = 0x0a,
+    I64 = 0x0b,
+    U64 = 0x0c,
+    /// 引用类型，具体的类型在 `reference_types` 中
+    Reference = 0x0d,
+    /// 动态类型
+    Tagged = 0x0e,
+}
+
+impl ShortyType {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        let t = match value {
+            0x01 => ShortyType::Void,
+            0x02 => ShortyType::U1,
+            0x03 => ShortyType::I8,
+            0x04 => ShortyType::U8,
+            0x05 => ShortyType::I16,
+            0x06 => ShortyType::U16,
+            0x07 => ShortyType::I32,
+            0x08 => ShortyType::U32,
+            0x09 => ShortyType::F32,
+            0x0a => ShortyType::F64,
+            0x0b => ShortyType::I64,
+            0x0c => ShortyType::U64,
+            0x0d => ShortyType::Reference,
+            0x0e => ShortyType::Tagged,
+            _ => return None,
+        };
+        Some(t)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShortyType::Void => "V",
+            ShortyType::U1 => "U1",
+            ShortyType::I8 => "I8",
+            ShortyType::U8 => "U8",
+            ShortyType::I16 => "I16",
+            ShortyType::U16 => "U16",
+            ShortyType::I32 => "I32",
+            ShortyType::U32 => "U32",
+            ShortyType::F32 => "F32",
+            ShortyType::F64 => "F64",
+            ShortyType::I64 => "I64",
+            ShortyType::U64 => "U64",
+            ShortyType::Reference => "L",
+            ShortyType::Tagged => "any",
+        }
+    }
+}
+
+/// 方法原型
+#[derive(Debug, Getters, Default)]
+#[get = "pub"]
+pub struct Proto {
+    /// 第一个元素是返回值类型，其余的是参数类型。
+    shorty: Vec<ShortyType>,
+    /// 引用类型在 ClassRegionIndex 中的索引，按在 shorty 中出现的顺序排列。
+    reference_types: Vec<uint16_t>,
+}
+
+impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for Proto {
+    type Error = error::Error;
+    fn try_from_ctx(source: &'a [u8], _: scroll::Endian) -> Result<(Self, usize), Self::Error> {
+        let mut off = 0;
+        let mut shorty = Vec::new();
+
+        // 每个 uint16_t 存放 4 个类型，从低位开始，遇到 0 结束。
+        'l: loop {
+            let data = source.pread::<uint16_t>(off).at(off, "Proto.shorty")?;
+            off += 2;
+            for i in 0..4 {
+                let value = ((data >> (i * 4)) & 0xf) as u8;
+                if value == 0 {
+                    break 'l;
+                }
+
+                let t = ShortyType::from_u8(value).ok_or_else(|| {
+                    error::Error::MalFormed(format!("Proto: unknown shorty type 0x{:X}", value))
+                })?;
+                shorty.push(t);
+            }
+        }
+
+        let num_refs = shorty
+            .iter()
+            .filter(|t| **t == ShortyType::Reference)
+            .count();
+        let mut reference_types = Vec::new();
+        for _ in 0..num_refs {
+            let idx = source
+                .pread::<uint16_t>(off)
+                .at(off, "Proto.reference_types")?;
+            off += 2;
+            reference_types.push(idx);
+        }
+
+        Ok((
+            Proto {
+                shorty,
+                reference_types,
+            },
+            off,
+        ))
+    }
+}
+
+impl Proto {
+    /// 动态类型的方法没有原型，参数和返回值都是 `any`。
+    pub fn tagged(num_args: usize) -> Self {
+        Proto {
+            shorty: vec![ShortyType::Tagged; num_args + 1],
+            reference_types: Vec::new(),
+        }
+    }
+
+    /// 根据 ClassRegionIndex 得到类型名
+    pub fn resolve(&self, source: &[u8], region: &Region) -> Result<MethodSignature, error::Error> {
+        let mut refs = self.reference_types.iter();
+        let mut types = Vec::new();
+        for t in &self.shorty {
+            if *t == ShortyType::Reference {
+                let idx = *refs.next().at(0, "Proto.reference_types")? as usize;
+                let name = region
+                    .get_type(idx)
+                    .at(idx, "Proto.reference_types")?
+                    .name(source)?;
+                types.push(name);
+            } else {
+                types.push(t.name().to_string());
+            }
+        }
+
+        let return_type = if types.is_empty() {
+            ShortyType::Void.name().to_string()
+        } else {
+            types.remove(0)
+        };
+
+        Ok(MethodSignature {
+            return_type,
+            params: types,
+        })
+    }
+}
+
+/// 方法签名，如 `(I32, Lx;)V`
+#[derive(Debug, Clone, Getters, Default, PartialEq, Eq)]
+#[get = "pub"]
+pub struct MethodSignature {
+    return_type: String,
+    params: Vec<String>,
+}
+
+impl fmt::Display for MethodSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}){}", self.params.join(", "), self.return_type)
+    }
+}
@@ -1,8 +1,9 @@
 /// 索引结构，用于存放索引信息，根据索引信息可以快速定位到对应的数据。
 use getset::{CopyGetters, Getters};
 use scroll::Pread;
-use std::fmt;
 
+use crate::error;
+use crate::string::ABCString;
 use crate::uint32_t;
 
 #[derive(Debug, Pread, CopyGetters)]
@@ -29,36 +30,102 @@ pub struct RegionHeader {
     proto_idx_off: uint32_t,
 }
 
-/// 存放类型，如果不是基础类型，那么就是一个指向Class的偏移量
-//pub type FieldType = uint32_t;
+/// 基本类型，值为它在 ClassRegionIndex 中的编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrimitiveType {
+    U1 = 0x00,
+    I8 = 0x01,
+    U8 = 0x02,
+    I16 = 0x03,
+    U16 = 0x04,
+    I32 = 0x05,
+    U32 = 0x06,
+    F32 = 0x07,
+    F64 = 0x08,
+    I64 = 0x09,
+    U64 = 0x0a,
+    Any = 0x0b,
+}
 
-#[derive(Debug, CopyGetters, Getters, Default)]
-#[get = "pub"]
-pub struct FieldType {
-    // TODO: 如果类的数量特别多的话，会不会重复占用内存？
-    // TODO: 也许可以增加一个 benchmark 来验证一下
-    pub name: String,
+impl PrimitiveType {
+    pub fn from_u32(value: uint32_t) -> Option<Self> {
+        let t = match value {
+            0x00 => PrimitiveType::U1,
+            0x01 => PrimitiveType::I8,
+            0x02 => PrimitiveType::U8,
+            0x03 => PrimitiveType::I16,
+            0x04 => PrimitiveType::U16,
+            0x05 => PrimitiveType::I32,
+            0x06 => PrimitiveType::U32,
+            0x07 => PrimitiveType::F32,
+            0x08 => PrimitiveType::F64,
+            0x09 => PrimitiveType::I64,
+            0x0a => PrimitiveType::U64,
+            0x0b => PrimitiveType::Any,
+            _ => return None,
+        };
+        Some(t)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PrimitiveType::U1 => "u1",
+            PrimitiveType::I8 => "i8",
+            PrimitiveType::U8 => "u8",
+            PrimitiveType::I16 => "i16",
+            PrimitiveType::U16 => "u16",
+            PrimitiveType::I32 => "i32",
+            PrimitiveType::U32 => "u32",
+            PrimitiveType::F32 => "f32",
+            PrimitiveType::F64 => "f64",
+            PrimitiveType::I64 => "i64",
+            PrimitiveType::U64 => "u64",
+            PrimitiveType::Any => "any",
+        }
+    }
+}
+
+/// ClassRegionIndex 中的类型，只保存编码或偏移量，需要时再读取类型名
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TypeRef {
+    Primitive(PrimitiveType),
+    /// 文件中定义的类，值为类的偏移量
+    Class(uint32_t),
+    /// 外部类，值为类的偏移量
+    Foreign(uint32_t),
+    /// 既不是基本类型，也没有指向任何类的原始值
+    Unresolved(uint32_t),
 }
 
-impl fmt::Display for FieldType {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.name)
+impl TypeRef {
+    /// 获取类型名，类和外部类的名字位于它们的起始位置
+    pub fn name(&self, source: &[u8]) -> Result<String, error::Error> {
+        match self {
+            TypeRef::Primitive(t) => Ok(t.name().to_string()),
+            TypeRef::Class(offset) | TypeRef::Foreign(offset) => {
+                let name = source
+                    .pread::<ABCString>(*offset as usize)
+                    .map_err(|e| e.rebase(*offset as usize))?;
+                Ok(name.str())
+            }
+            TypeRef::Unresolved(value) => Ok(format!("<unresolved 0x{:x}>", value)),
+        }
     }
 }
 
 #[derive(Debug, Default)]
 pub struct ClassRegionIndex {
     // 一个数组，数组中每个元素都是Type类型。
-    offsets: Vec<FieldType>,
+    offsets: Vec<TypeRef>,
 }
 
 impl ClassRegionIndex {
-    pub fn push(&mut self, field_type: FieldType) {
-        self.offsets.push(field_type);
+    pub fn push(&mut self, type_ref: TypeRef) {
+        self.offsets.push(type_ref);
     }
 
-    pub fn get(&self, idx: &usize) -> &FieldType {
-        &self.offsets[*idx]
+    pub fn get(&self, idx: &usize) -> Option<TypeRef> {
+        self.offsets.get(*idx).copied()
     }
 }
 
@@ -73,8 +140,8 @@ impl MethodStringLiteralRegionIndex {
         self.offsets.push(offset);
     }
 
-    pub fn get(&self, idx: &usize) -> &uint32_t {
-        &self.offsets[*idx]
+    pub fn get(&self, idx: &usize) -> Option<&uint32_t> {
+        self.offsets.get(*idx)
     }
 }
 
@@ -106,7 +173,7 @@ impl ProtoRegionIndex {
 #[get = "pub"]
 pub struct Region {
     header: RegionHeader,
-    /// 通过索引找到类型 FieldType
+    /// 通过索引找到类型 TypeRef
     class_region_idx: ClassRegionIndex,
     /// 找到对应的方法、字符串或者字面量数组。
     method_string_literal_region_idx: MethodStringLiteralRegionIndex,
@@ -137,13 +204,12 @@ impl Region {
     }
 
     /// 根据索引获取它的类型
-    // 只有一个地方用到，解析方法的时候，有一个。
-    pub fn get_class_name(&self, idx: usize) -> &FieldType {
+    pub fn get_type(&self, idx: usize) -> Option<TypeRef> {
         self.class_region_idx.get(&idx)
     }
 
     /// 根据索引获取它的偏移量
-    pub fn get_msl_offset(&self, idx: usize) -> &uint32_t {
+    pub fn get_msl_offset(&self, idx: usize) -> Option<&uint32_t> {
         self.method_string_literal_region_idx.get(&idx)
     }
 }
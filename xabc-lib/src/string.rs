@@ -2,7 +2,18 @@ use std::{fmt, sync::Arc};
 
 use scroll::{ctx, Uleb128};
 
-use crate::error;
+use crate::error::{self, ReadContext};
+
+/// 字符串的编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringEncoding {
+    /// 全是 ASCII 字符，长度的最低位为 1
+    Ascii,
+    /// MUTF-8：`\0` 编码为 `C0 80`，补充平面的字符编码为两个代理项
+    Mutf8,
+    /// UTF-16 编码单元，小端序
+    Utf16,
+}
 
 #[derive(Debug)]
 pub struct ABCString {
@@ -10,6 +21,13 @@ pub struct ABCString {
     str: Arc<String>,
     /// ABCString 长度，包括 `\0`
     length: usize,
+    /// UTF-16 编码单元的数量
+    utf16_length: usize,
+    encoding: StringEncoding,
+    /// 原始数据，不包括长度和 `\0`
+    raw: Arc<Vec<u8>>,
+    /// 无法解码的部分被替换为 U+FFFD
+    lossy: bool,
 }
 
 impl ABCString {
@@ -20,6 +38,58 @@ impl ABCString {
     pub fn length(&self) -> usize {
         self.length
     }
+
+    pub fn utf16_length(&self) -> usize {
+        self.utf16_length
+    }
+
+    pub fn encoding(&self) -> StringEncoding {
+        self.encoding
+    }
+
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+
+    /// 是否有无法解码的部分，这些部分被替换为 U+FFFD
+    pub fn is_lossy(&self) -> bool {
+        self.lossy
+    }
+
+    /// 按 MUTF-8 编码字符串，长度为 UTF-16 编码单元的数量，最低位表示是否全是 ASCII 字符
+    pub fn encode(value: &str) -> Vec<u8> {
+        let units: Vec<u16> = value.encode_utf16().collect();
+        let is_ascii = value.is_ascii() && !value.contains('\0');
+
+        let mut data = Vec::new();
+        let mut length = ((units.len() as u64) << 1) | is_ascii as u64;
+        loop {
+            let byte = (length & 0x7f) as u8;
+            length >>= 7;
+            if length == 0 {
+                data.push(byte);
+                break;
+            }
+            data.push(byte | 0x80);
+        }
+
+        for unit in units {
+            match unit {
+                0x01..=0x7f => data.push(unit as u8),
+                0x00 | 0x80..=0x7ff => {
+                    data.push(0xc0 | (unit >> 6) as u8);
+                    data.push(0x80 | (unit & 0x3f) as u8);
+                }
+                _ => {
+                    data.push(0xe0 | (unit >> 12) as u8);
+                    data.push(0x80 | ((unit >> 6) & 0x3f) as u8);
+                    data.push(0x80 | (unit & 0x3f) as u8);
+                }
+            }
+        }
+        data.push(0);
+        data
+    }
 }
 
 impl Clone for ABCString {
@@ -27,6 +97,10 @@ impl Clone for ABCString {
         ABCString {
             str: self.str.clone(),
             length: self.length,
+            utf16_length: self.utf16_length,
+            encoding: self.encoding,
+            raw: self.raw.clone(),
+            lossy: self.lossy,
         }
     }
 }
@@ -37,27 +111,103 @@ impl fmt::Display for ABCString {
     }
 }
 
+/// 按 MUTF-8 解码为 UTF-16 编码单元，格式错误时返回 `None`
+fn decode_mutf8(bytes: &[u8]) -> Option<Vec<u16>> {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    let continuation = |i: usize| match bytes.get(i) {
+        Some(byte) if byte & 0xc0 == 0x80 => Some((byte & 0x3f) as u32),
+        _ => None,
+    };
+    while i < bytes.len() {
+        let byte = bytes[i] as u32;
+        match byte {
+            0x01..=0x7f => {
+                units.push(byte as u16);
+                i += 1;
+            }
+            0xc0..=0xdf => {
+                units.push((((byte & 0x1f) << 6) | continuation(i + 1)?) as u16);
+                i += 2;
+            }
+            0xe0..=0xef => {
+                let unit =
+                    ((byte & 0x0f) << 12) | (continuation(i + 1)? << 6) | continuation(i + 2)?;
+                units.push(unit as u16);
+                i += 3;
+            }
+            // 标准的 MUTF-8 没有 4 字节的形式，但 ark 的编译器会生成
+            0xf0..=0xf7 => {
+                let code_point = ((byte & 0x07) << 18)
+                    | (continuation(i + 1)? << 12)
+                    | (continuation(i + 2)? << 6)
+                    | continuation(i + 3)?;
+                let c = char::from_u32(code_point)?;
+                let mut pair = [0; 2];
+                units.extend_from_slice(c.encode_utf16(&mut pair));
+                i += 4;
+            }
+            _ => return None,
+        }
+    }
+    Some(units)
+}
+
 impl<'a> ctx::TryFromCtx<'a, scroll::Endian> for ABCString {
     type Error = error::Error;
     fn try_from_ctx(source: &'a [u8], _: scroll::Endian) -> Result<(Self, usize), Self::Error> {
         let off = &mut 0;
-        let utf16_length = Uleb128::read(source, off).unwrap();
-
-        // 字符串的长度
-        let count = (utf16_length >> 1) as usize;
-        let bytes = &source[*off..*off + count];
+        let utf16_length = Uleb128::read(source, off).at(0, "String.utf16_length")?;
+        let is_ascii = utf16_length & 1 == 1;
+        let utf16_length = (utf16_length >> 1) as usize;
+        let data = source.get(*off..).at(*off, "String.data")?;
+        let end = data.iter().position(|byte| *byte == 0);
 
-        let str = std::str::from_utf8(bytes).unwrap_or("-utf8-error-");
-
-        let mut len = *off + count;
+        // MUTF-8 的数据中没有 `\0`，解码后的长度必须与 utf16_length 一致
+        let mutf8 = end.and_then(|end| decode_mutf8(&data[..end]));
+        let (units, encoding, raw, lossy) = match (mutf8, end) {
+            (Some(units), Some(end)) if units.len() == utf16_length => {
+                let encoding = if is_ascii {
+                    StringEncoding::Ascii
+                } else {
+                    StringEncoding::Mutf8
+                };
+                let lossy = String::from_utf16(&units).is_err();
+                (units, encoding, &data[..end], lossy)
+            }
+            _ if !is_ascii && data.len() >= utf16_length * 2 => {
+                let raw = &data[..utf16_length * 2];
+                let units: Vec<u16> = raw
+                    .chunks_exact(2)
+                    .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                    .collect();
+                let lossy = String::from_utf16(&units).is_err();
+                (units, StringEncoding::Utf16, raw, lossy)
+            }
+            (_, Some(end)) => {
+                // 无法解码的字符串也要保留，按 UTF-8 尽量解码
+                let raw = &data[..end];
+                let units = String::from_utf8_lossy(raw).encode_utf16().collect();
+                (units, StringEncoding::Mutf8, raw, true)
+            }
+            // 没有 `\0` 结尾，数据被截断了
+            (_, None) => return Err(error::Error::BadOffset(*off, "String.data".to_string())),
+        };
 
+        let mut len = *off + raw.len();
         // 还有`\0`
-        len += 1;
+        if data.get(raw.len()) == Some(&0) {
+            len += 1;
+        }
 
         Ok((
             ABCString {
-                str: Arc::new(str.to_string()),
+                str: Arc::new(String::from_utf16_lossy(&units)),
                 length: len,
+                utf16_length,
+                encoding,
+                raw: Arc::new(raw.to_vec()),
+                lossy,
             },
             source.len(),
         ))
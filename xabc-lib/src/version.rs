@@ -0,0 +1,85 @@
+use std::fmt;
+
+use crate::error;
+
+/// 字节码文件的版本号，如 12.0.2.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Version(pub [u8; 4]);
+
+impl Version {
+    pub const fn new(major: u8, minor: u8, feature: u8, build: u8) -> Self {
+        Version([major, minor, feature, build])
+    }
+
+    /// 支持的最低版本，更早的版本使用旧的指令集
+    pub const MIN: Version = Version::new(9, 0, 0, 0);
+    /// 支持的最高版本
+    pub const MAX: Version = Version::new(12, 0, 6, 0);
+
+    /// 版本是否可以解析
+    pub fn is_supported(&self) -> bool {
+        Version::MIN <= *self && *self <= Version::MAX && !INCOMPATIBLE_VERSIONS.contains(self)
+    }
+
+    /// 生成该版本的 API 等级范围，未知的版本返回 `None`
+    pub fn api_range(&self) -> Option<(u8, u8)> {
+        let apis = API_VERSION_MAP
+            .iter()
+            .filter(|(_, version)| version == self)
+            .map(|(api, _)| *api);
+        let min = apis.clone().min()?;
+        let max = apis.max()?;
+        Some((min, max))
+    }
+
+    /// 不支持的版本返回错误
+    pub fn check(&self) -> Result<(), error::Error> {
+        if self.is_supported() {
+            return Ok(());
+        }
+
+        Err(error::Error::MalFormed(format!(
+            "不支持的版本: {}，支持的版本范围: {} ~ {}",
+            self,
+            Version::MIN,
+            Version::MAX
+        )))
+    }
+}
+
+impl From<[u8; 4]> for Version {
+    fn from(value: [u8; 4]) -> Self {
+        Version(value)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [major, minor, feature, build] = self.0;
+        write!(f, "{}.{}.{}.{}", major, minor, feature, build)
+    }
+}
+
+// https://gitee.com/openharmony/arkcompiler_runtime_core/blob/master/libpandafile/file_format_version.h
+/// API 等级与字节码版本的对应关系
+const API_VERSION_MAP: [(u8, Version); 5] = [
+    (9, Version::new(9, 0, 0, 0)),
+    (10, Version::new(9, 0, 0, 0)),
+    (11, Version::new(11, 0, 2, 0)),
+    (12, Version::new(12, 0, 2, 0)),
+    (12, Version::new(12, 0, 6, 0)),
+];
+
+/// 过渡期间的版本，与其他版本都不兼容
+const INCOMPATIBLE_VERSIONS: [Version; 4] = [
+    Version::new(10, 0, 0, 0),
+    Version::new(10, 0, 1, 0),
+    Version::new(10, 0, 2, 0),
+    Version::new(10, 0, 3, 0),
+];
+
+/// 指令集的变化点，指令只在不低于它的版本中出现
+pub const ISA_BASE: Version = Version::new(9, 0, 0, 0);
+pub const ISA_API_11: Version = Version::new(11, 0, 2, 0);
+pub const ISA_API_12: Version = Version::new(12, 0, 2, 0);
+pub const ISA_API_12_SENDABLE: Version = Version::new(12, 0, 6, 0);
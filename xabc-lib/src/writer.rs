@@ -0,0 +1,719 @@
+/// 生成 abc 文件：在内存中描述类、方法、字符串、字面量数组和 Code，再按文件格式布局写出
+use std::collections::HashMap;
+use std::{fs, path::Path};
+
+use crate::assembler::{self, Arg, CodeBuilder};
+use crate::bytecode::BytecodeParser;
+use crate::error;
+use crate::header::{self, HEADER_SIZE, MAGIC};
+use crate::proto::ShortyType;
+use crate::region::PrimitiveType;
+use crate::string::ABCString;
+use crate::version::{Version, ISA_API_12};
+use crate::{uint16_t, uint32_t};
+
+/// 类的句柄
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClassId(usize);
+
+/// 方法的句柄
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MethodId {
+    class: usize,
+    method: usize,
+}
+
+/// 字面量数组的句柄
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LiteralArrayId(usize);
+
+/// 字段的初始值
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldInit {
+    /// `INT_VALUE` 标签，sleb128 编码
+    Int(i32),
+    /// `VALUE` 标签，原样写入
+    Value(uint32_t),
+    /// `VALUE` 标签，写入字面量数组的偏移量
+    LiteralArray(LiteralArrayId),
+}
+
+/// 字面量，与 `literal::Literal` 对应，方法和字面量数组使用句柄
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiteralValue {
+    TagValue(u8),
+    Bool(bool),
+    Integer(i32),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Method(MethodId),
+    GeneratorMethod(MethodId),
+    AsyncGeneratorMethod(MethodId),
+    Getter(MethodId),
+    Setter(MethodId),
+    Accessor(u8),
+    MethodAffiliate(u16),
+    LiteralBufferIndex(uint32_t),
+    LiteralArray(LiteralArrayId),
+    BuiltinTypeIndex(u8),
+    Null,
+}
+
+/// CatchBlock，`type_idx` 为 `None` 时捕获所有类型的异常
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatchDef {
+    pub type_idx: Option<uint16_t>,
+    pub handler_pc: u64,
+    pub code_size: u64,
+}
+
+/// TryBlock
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TryDef {
+    pub start_pc: u64,
+    pub length: u64,
+    pub catches: Vec<CatchDef>,
+}
+
+#[derive(Debug, Clone)]
+struct CodeDef {
+    num_regs: u64,
+    num_args: u64,
+    instructions: Vec<u8>,
+    try_blocks: Vec<TryDef>,
+}
+
+#[derive(Debug, Clone)]
+struct FieldDef {
+    name: String,
+    type_idx: uint16_t,
+    access_flags: u64,
+    value: Option<FieldInit>,
+}
+
+#[derive(Debug, Clone)]
+struct MethodDef {
+    name: String,
+    access_flags: u64,
+    /// 返回值和参数类型，以及引用类型在 ClassRegionIndex 中的索引；动态类型的方法没有原型
+    proto: Option<(Vec<ShortyType>, Vec<uint16_t>)>,
+    code: Option<CodeDef>,
+}
+
+#[derive(Debug, Clone)]
+struct ClassDef {
+    name: String,
+    access_flags: u64,
+    /// 实现的接口在 ClassRegionIndex 中的索引
+    interfaces: Vec<uint16_t>,
+    source_file: Option<String>,
+    fields: Vec<FieldDef>,
+    methods: Vec<MethodDef>,
+}
+
+/// ClassRegionIndex 中的一项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypeEntry {
+    Primitive(PrimitiveType),
+    Class(usize),
+    Foreign(usize),
+}
+
+/// MethodStringLiteralRegionIndex 中的一项
+#[derive(Debug, Clone, PartialEq)]
+enum IndexEntry {
+    String(String),
+    Method(MethodId),
+    LiteralArray(LiteralArrayId),
+}
+
+/// 布局后各个元素的偏移量
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Layout {
+    strings: HashMap<String, uint32_t>,
+    classes: Vec<uint32_t>,
+    foreign_classes: Vec<uint32_t>,
+    methods: HashMap<MethodId, uint32_t>,
+    literal_arrays: Vec<uint32_t>,
+    file_size: uint32_t,
+}
+
+/// 用于生成 `Abc` 文件，所有数据放在同一个 Region 中
+#[derive(Debug, Clone)]
+pub struct AbcWriter {
+    version: Version,
+    classes: Vec<ClassDef>,
+    foreign_classes: Vec<String>,
+    literal_arrays: Vec<Vec<LiteralValue>>,
+    types: Vec<TypeEntry>,
+    entries: Vec<IndexEntry>,
+}
+
+impl Default for AbcWriter {
+    fn default() -> Self {
+        Self::new(ISA_API_12)
+    }
+}
+
+impl AbcWriter {
+    pub fn new(version: Version) -> Self {
+        Self {
+            version,
+            classes: Vec::new(),
+            foreign_classes: Vec::new(),
+            literal_arrays: Vec::new(),
+            types: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// 添加类，`name` 为类型描述符，如：`Lcom/example/A;`
+    pub fn add_class(&mut self, name: &str, access_flags: u64) -> ClassId {
+        self.classes.push(ClassDef {
+            name: name.to_string(),
+            access_flags,
+            interfaces: Vec::new(),
+            source_file: None,
+            fields: Vec::new(),
+            methods: Vec::new(),
+        });
+        ClassId(self.classes.len() - 1)
+    }
+
+    /// 添加类实现的接口，`type_idx` 为接口在 ClassRegionIndex 中的索引
+    pub fn add_interface(&mut self, class: ClassId, type_idx: uint16_t) {
+        self.classes[class.0].interfaces.push(type_idx);
+    }
+
+    /// 设置类的源文件名
+    pub fn set_source_file(&mut self, class: ClassId, source_file: &str) {
+        self.classes[class.0].source_file = Some(source_file.to_string());
+    }
+
+    /// 添加外部类，返回它在 ClassRegionIndex 中的索引
+    pub fn add_foreign_class(&mut self, name: &str) -> uint16_t {
+        self.foreign_classes.push(name.to_string());
+        self.type_index(TypeEntry::Foreign(self.foreign_classes.len() - 1))
+    }
+
+    /// 类在 ClassRegionIndex 中的索引
+    pub fn class_type(&mut self, class: ClassId) -> uint16_t {
+        self.type_index(TypeEntry::Class(class.0))
+    }
+
+    /// 基本类型在 ClassRegionIndex 中的索引
+    pub fn primitive_type(&mut self, t: PrimitiveType) -> uint16_t {
+        self.type_index(TypeEntry::Primitive(t))
+    }
+
+    fn type_index(&mut self, entry: TypeEntry) -> uint16_t {
+        let idx = match self.types.iter().position(|e| *e == entry) {
+            Some(idx) => idx,
+            None => {
+                self.types.push(entry);
+                self.types.len() - 1
+            }
+        };
+        idx as uint16_t
+    }
+
+    /// 添加字段，`type_idx` 为 ClassRegionIndex 中的索引
+    pub fn add_field(
+        &mut self,
+        class: ClassId,
+        name: &str,
+        type_idx: uint16_t,
+        access_flags: u64,
+        value: Option<FieldInit>,
+    ) {
+        self.class_type(class);
+        self.classes[class.0].fields.push(FieldDef {
+            name: name.to_string(),
+            type_idx,
+            access_flags,
+            value,
+        });
+    }
+
+    /// 添加一个没有 Code 的动态类型方法
+    pub fn add_method(&mut self, class: ClassId, name: &str, access_flags: u64) -> MethodId {
+        self.class_type(class);
+        let methods = &mut self.classes[class.0].methods;
+        methods.push(MethodDef {
+            name: name.to_string(),
+            access_flags,
+            proto: None,
+            code: None,
+        });
+        MethodId {
+            class: class.0,
+            method: methods.len() - 1,
+        }
+    }
+
+    fn method_mut(&mut self, method: MethodId) -> &mut MethodDef {
+        &mut self.classes[method.class].methods[method.method]
+    }
+
+    /// 设置方法的原型，`shorty` 第一个元素是返回值类型
+    pub fn set_proto(
+        &mut self,
+        method: MethodId,
+        shorty: Vec<ShortyType>,
+        reference_types: Vec<uint16_t>,
+    ) {
+        self.method_mut(method).proto = Some((shorty, reference_types));
+    }
+
+    /// 直接设置方法的指令，引用的索引需要调用者通过 `string_id` 等方法分配
+    pub fn set_raw_code(
+        &mut self,
+        method: MethodId,
+        num_regs: u64,
+        num_args: u64,
+        instructions: Vec<u8>,
+        try_blocks: Vec<TryDef>,
+    ) {
+        self.method_mut(method).code = Some(CodeDef {
+            num_regs,
+            num_args,
+            instructions,
+            try_blocks,
+        });
+    }
+
+    /// 汇编 `code` 并设置为方法的 Code，返回每个标签的 pc
+    pub fn set_code(
+        &mut self,
+        method: MethodId,
+        code: &CodeBuilder,
+    ) -> Result<HashMap<String, usize>, error::Error> {
+        for arg in code.id_args() {
+            match arg {
+                Arg::String(value) => self.string_id(value),
+                Arg::Method(id) => self.method_id(*id),
+                Arg::LiteralArray(id) => self.literal_array_id(*id),
+                _ => continue,
+            };
+        }
+
+        let parser = BytecodeParser::with_version(self.version);
+        let entries = &self.entries;
+        let resolve = |arg: &Arg| {
+            let entry = match arg {
+                Arg::String(value) => IndexEntry::String(value.clone()),
+                Arg::Method(id) => IndexEntry::Method(*id),
+                Arg::LiteralArray(id) => IndexEntry::LiteralArray(*id),
+                _ => return None,
+            };
+            entries
+                .iter()
+                .position(|e| *e == entry)
+                .map(|idx| idx as uint16_t)
+        };
+        let assembled = assembler::assemble(code, &parser, &resolve)?;
+
+        self.method_mut(method).code = Some(CodeDef {
+            num_regs: code.num_regs(),
+            num_args: code.num_args(),
+            instructions: assembled.instructions,
+            try_blocks: assembled.try_blocks,
+        });
+        Ok(assembled.labels)
+    }
+
+    /// 添加字面量数组
+    pub fn add_literal_array(&mut self, values: Vec<LiteralValue>) -> LiteralArrayId {
+        self.literal_arrays.push(values);
+        LiteralArrayId(self.literal_arrays.len() - 1)
+    }
+
+    fn index_entry(&mut self, entry: IndexEntry) -> uint16_t {
+        let idx = match self.entries.iter().position(|e| *e == entry) {
+            Some(idx) => idx,
+            None => {
+                self.entries.push(entry);
+                self.entries.len() - 1
+            }
+        };
+        idx as uint16_t
+    }
+
+    /// 字符串在 MethodStringLiteralRegionIndex 中的索引，即指令中的 `@AAAA`
+    pub fn string_id(&mut self, value: &str) -> uint16_t {
+        self.index_entry(IndexEntry::String(value.to_string()))
+    }
+
+    /// 方法在 MethodStringLiteralRegionIndex 中的索引
+    pub fn method_id(&mut self, method: MethodId) -> uint16_t {
+        self.index_entry(IndexEntry::Method(method))
+    }
+
+    /// 字面量数组在 MethodStringLiteralRegionIndex 中的索引
+    pub fn literal_array_id(&mut self, array: LiteralArrayId) -> uint16_t {
+        self.index_entry(IndexEntry::LiteralArray(array))
+    }
+
+    /// 生成文件内容
+    ///
+    /// 各个元素的大小与偏移量的值无关，先用空的布局写一遍得到偏移量，再用真实的偏移量写一遍。
+    pub fn write(&self) -> Result<Vec<u8>, error::Error> {
+        let (_, layout) = self.emit(&Layout::default())?;
+        let (mut data, second) = self.emit(&layout)?;
+        debug_assert_eq!(layout, second);
+
+        let checksum = header::compute_checksum(&data);
+        data[8..12].copy_from_slice(&checksum.to_le_bytes());
+        Ok(data)
+    }
+
+    /// 生成文件并写入 `path`
+    pub fn write_file<P: AsRef<Path>>(&self, path: P) -> Result<(), error::Error> {
+        fs::write(path, self.write()?)?;
+        Ok(())
+    }
+
+    fn emit(&self, layout: &Layout) -> Result<(Vec<u8>, Layout), error::Error> {
+        let mut out = Buffer(vec![0; HEADER_SIZE]);
+        let mut next = Layout::default();
+        let string_off = |value: &str| layout.strings.get(value).copied().unwrap_or(0);
+        let method_off = |id: &MethodId| layout.methods.get(id).copied().unwrap_or(0);
+        let literal_off =
+            |id: &LiteralArrayId| layout.literal_arrays.get(id.0).copied().unwrap_or(0);
+
+        // 字符串
+        for value in self.strings() {
+            if !next.strings.contains_key(value) {
+                next.strings.insert(value.to_string(), out.pos());
+                out.string(value);
+            }
+        }
+
+        // 字面量数组
+        for values in &self.literal_arrays {
+            next.literal_arrays.push(out.pos());
+            // Tag 和值各占一个计数
+            out.u32(values.len() as uint32_t * 2);
+            for value in values {
+                match value {
+                    LiteralValue::TagValue(v) => out.u8(0x00).u8(*v),
+                    LiteralValue::Bool(v) => out.u8(0x01).u8(*v as u8),
+                    LiteralValue::Integer(v) => out.u8(0x02).bytes(&v.to_le_bytes()),
+                    LiteralValue::Float(v) => out.u8(0x03).bytes(&v.to_le_bytes()),
+                    LiteralValue::Double(v) => out.u8(0x04).bytes(&v.to_le_bytes()),
+                    LiteralValue::String(v) => out.u8(0x05).u32(string_off(v)),
+                    LiteralValue::Method(id) => out.u8(0x06).u32(method_off(id)),
+                    LiteralValue::GeneratorMethod(id) => out.u8(0x07).u32(method_off(id)),
+                    LiteralValue::Accessor(v) => out.u8(0x08).u8(*v),
+                    LiteralValue::MethodAffiliate(v) => out.u8(0x09).u16(*v),
+                    LiteralValue::AsyncGeneratorMethod(id) => out.u8(0x16).u32(method_off(id)),
+                    LiteralValue::LiteralBufferIndex(v) => out.u8(0x17).u32(*v),
+                    LiteralValue::LiteralArray(id) => out.u8(0x18).u32(literal_off(id)),
+                    LiteralValue::BuiltinTypeIndex(v) => out.u8(0x19).u8(*v),
+                    LiteralValue::Getter(id) => out.u8(0x1a).u32(method_off(id)),
+                    LiteralValue::Setter(id) => out.u8(0x1b).u32(method_off(id)),
+                    LiteralValue::Null => out.u8(0xff).u8(0),
+                };
+            }
+        }
+
+        // 方法原型
+        let mut protos: Vec<&(Vec<ShortyType>, Vec<uint16_t>)> = Vec::new();
+        let mut proto_offsets = Vec::new();
+        for method in self.classes.iter().flat_map(|c| &c.methods) {
+            let Some(proto) = &method.proto else {
+                continue;
+            };
+            if protos.contains(&proto) {
+                continue;
+            }
+            out.align(2);
+            proto_offsets.push(out.pos());
+            protos.push(proto);
+
+            // 每个 uint16_t 存放 4 个类型，从低位开始，以 0 结束
+            let (shorty, reference_types) = proto;
+            let mut nibbles: Vec<u16> = shorty.iter().map(|t| *t as u16).collect();
+            nibbles.push(0);
+            for chunk in nibbles.chunks(4) {
+                let data = chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u16, |data, (i, n)| data | (n << (i * 4)));
+                out.u16(data);
+            }
+            for idx in reference_types {
+                out.u16(*idx);
+            }
+        }
+
+        // Code
+        let mut code_offsets = HashMap::new();
+        for (c, class) in self.classes.iter().enumerate() {
+            for (m, method) in class.methods.iter().enumerate() {
+                let Some(code) = &method.code else {
+                    continue;
+                };
+                out.align(4);
+                code_offsets.insert((c, m), out.pos());
+                out.uleb(code.num_regs)
+                    .uleb(code.num_args)
+                    .uleb(code.instructions.len() as u64)
+                    .uleb(code.try_blocks.len() as u64)
+                    .bytes(&code.instructions);
+                for try_block in &code.try_blocks {
+                    out.uleb(try_block.start_pc)
+                        .uleb(try_block.length)
+                        .uleb(try_block.catches.len() as u64);
+                    for catch in &try_block.catches {
+                        let type_idx = catch.type_idx.map_or(0, |idx| idx as u64 + 1);
+                        out.uleb(type_idx)
+                            .uleb(catch.handler_pc)
+                            .uleb(catch.code_size);
+                    }
+                }
+            }
+        }
+
+        // 类、字段和方法
+        let class_type = |c: usize| {
+            self.types
+                .iter()
+                .position(|e| *e == TypeEntry::Class(c))
+                .unwrap_or(0) as uint16_t
+        };
+        for (c, class) in self.classes.iter().enumerate() {
+            out.align(4);
+            next.classes.push(out.pos());
+            out.string(&class.name)
+                // 父类
+                .u32(0)
+                .uleb(class.access_flags)
+                .uleb(class.fields.len() as u64)
+                .uleb(class.methods.len() as u64);
+            if !class.interfaces.is_empty() {
+                out.u8(0x01).uleb(class.interfaces.len() as u64);
+                for idx in &class.interfaces {
+                    out.u16(*idx);
+                }
+            }
+            if let Some(source_file) = &class.source_file {
+                out.u8(0x07).u32(string_off(source_file));
+            }
+            out.u8(0x00);
+
+            for field in &class.fields {
+                out.u16(class_type(c))
+                    .u16(field.type_idx)
+                    .u32(string_off(&field.name))
+                    .uleb(field.access_flags);
+                match &field.value {
+                    Some(FieldInit::Int(v)) => out.u8(0x01).sleb(*v as i64),
+                    Some(FieldInit::Value(v)) => out.u8(0x02).u32(*v),
+                    Some(FieldInit::LiteralArray(id)) => out.u8(0x02).u32(literal_off(id)),
+                    None => &mut out,
+                };
+                out.u8(0x00);
+            }
+
+            for (m, method) in class.methods.iter().enumerate() {
+                next.methods.insert(
+                    MethodId {
+                        class: c,
+                        method: m,
+                    },
+                    out.pos(),
+                );
+                let proto_idx = match &method.proto {
+                    Some(proto) => protos.iter().position(|p| *p == proto).unwrap() as uint16_t,
+                    None => 0xffff,
+                };
+                out.u16(class_type(c))
+                    .u16(proto_idx)
+                    .u32(string_off(&method.name))
+                    .uleb(method.access_flags);
+                if let Some(code_off) = code_offsets.get(&(c, m)) {
+                    out.u8(0x01).u32(*code_off);
+                }
+                out.u8(0x00);
+            }
+        }
+
+        // 外部区域
+        let foreign_off = out.pos();
+        for name in &self.foreign_classes {
+            next.foreign_classes.push(out.pos());
+            out.string(name);
+        }
+        let foreign_size = out.pos() - foreign_off;
+
+        // ClassIndex
+        out.align(4);
+        let class_idx_off = out.pos();
+        for i in 0..self.classes.len() {
+            out.u32(layout.classes.get(i).copied().unwrap_or(0));
+        }
+        for i in 0..self.foreign_classes.len() {
+            out.u32(layout.foreign_classes.get(i).copied().unwrap_or(0));
+        }
+
+        // LiteralArrayIndex
+        let literalarray_idx_off = out.pos();
+        for i in 0..self.literal_arrays.len() {
+            out.u32(literal_off(&LiteralArrayId(i)));
+        }
+
+        // ClassRegionIndex
+        let class_region_idx_off = out.pos();
+        for entry in &self.types {
+            let value = match entry {
+                TypeEntry::Primitive(t) => *t as uint32_t,
+                TypeEntry::Class(c) => layout.classes.get(*c).copied().unwrap_or(0),
+                TypeEntry::Foreign(f) => layout.foreign_classes.get(*f).copied().unwrap_or(0),
+            };
+            out.u32(value);
+        }
+
+        // MethodStringLiteralRegionIndex
+        let msl_idx_off = out.pos();
+        for entry in &self.entries {
+            let value = match entry {
+                IndexEntry::String(value) => string_off(value),
+                IndexEntry::Method(id) => method_off(id),
+                IndexEntry::LiteralArray(id) => literal_off(id),
+            };
+            out.u32(value);
+        }
+
+        // ProtoRegionIndex，FieldRegionIndex 为空
+        let proto_idx_off = out.pos();
+        for off in &proto_offsets {
+            out.u32(*off);
+        }
+
+        // RegionHeader，整个文件是一个 Region
+        let region_off = out.pos();
+        out.u32(0)
+            .u32(layout.file_size)
+            .u32(self.types.len() as uint32_t)
+            .u32(class_region_idx_off)
+            .u32(self.entries.len() as uint32_t)
+            .u32(msl_idx_off)
+            .u32(0)
+            .u32(proto_idx_off)
+            .u32(protos.len() as uint32_t)
+            .u32(proto_idx_off);
+
+        let lnp_idx_off = out.pos();
+        next.file_size = out.pos();
+
+        let mut header = Buffer(Vec::with_capacity(HEADER_SIZE));
+        header
+            .bytes(&MAGIC)
+            // 校验和最后计算
+            .u32(0)
+            .bytes(&self.version.0)
+            .u32(next.file_size)
+            .u32(foreign_off)
+            .u32(foreign_size)
+            .u32((self.classes.len() + self.foreign_classes.len()) as uint32_t)
+            .u32(class_idx_off)
+            .u32(0)
+            .u32(lnp_idx_off)
+            .u32(self.literal_arrays.len() as uint32_t)
+            .u32(literalarray_idx_off)
+            .u32(1)
+            .u32(region_off);
+        out.0[..HEADER_SIZE].copy_from_slice(&header.0);
+
+        Ok((out.0, next))
+    }
+
+    /// 需要写入字符串区域的字符串：字段名、方法名、指令和字面量数组中的字符串
+    fn strings(&self) -> impl Iterator<Item = &str> {
+        let names = self.classes.iter().flat_map(|class| {
+            class
+                .fields
+                .iter()
+                .map(|f| f.name.as_str())
+                .chain(class.methods.iter().map(|m| m.name.as_str()))
+                .chain(class.source_file.as_deref())
+        });
+        let entries = self.entries.iter().filter_map(|entry| match entry {
+            IndexEntry::String(value) => Some(value.as_str()),
+            _ => None,
+        });
+        let literals = self
+            .literal_arrays
+            .iter()
+            .flatten()
+            .filter_map(|value| match value {
+                LiteralValue::String(value) => Some(value.as_str()),
+                _ => None,
+            });
+        names.chain(entries).chain(literals)
+    }
+}
+
+/// 小端字节序的输出缓冲区
+struct Buffer(Vec<u8>);
+
+impl Buffer {
+    fn pos(&self) -> uint32_t {
+        self.0.len() as uint32_t
+    }
+
+    fn bytes(&mut self, data: &[u8]) -> &mut Self {
+        self.0.extend_from_slice(data);
+        self
+    }
+
+    fn u8(&mut self, value: u8) -> &mut Self {
+        self.0.push(value);
+        self
+    }
+
+    fn u16(&mut self, value: u16) -> &mut Self {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn u32(&mut self, value: u32) -> &mut Self {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn uleb(&mut self, mut value: u64) -> &mut Self {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.0.push(byte);
+                return self;
+            }
+            self.0.push(byte | 0x80);
+        }
+    }
+
+    fn sleb(&mut self, mut value: i64) -> &mut Self {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+            if done {
+                self.0.push(byte);
+                return self;
+            }
+            self.0.push(byte | 0x80);
+        }
+    }
+
+    fn string(&mut self, value: &str) -> &mut Self {
+        self.bytes(&ABCString::encode(value))
+    }
+
+    fn align(&mut self, alignment: usize) {
+        while !self.0.len().is_multiple_of(alignment) {
+            self.0.push(0);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+/// 交叉引用：方法、字符串被哪些指令引用
+use std::collections::HashMap;
+use std::fmt;
+
+use getset::Getters;
+
+use crate::bytecode::{Instruction, Operand};
+use crate::literal::Literal;
+use crate::uint32_t;
+
+/// 一处引用
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[get = "pub"]
+pub struct Xref {
+    /// 引用所在的方法，格式：类名->方法名
+    method: String,
+    /// 引用所在指令的偏移量
+    pc: usize,
+    /// 引用所在指令的助记符
+    mnemonic: String,
+}
+
+impl fmt::Display for Xref {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} @ 0x{:x}: {}", self.method, self.pc, self.mnemonic)
+    }
+}
+
+/// 所有方法的交叉引用
+#[derive(Debug, Default)]
+pub struct XrefIndex {
+    /// 方法的偏移量 -> 调用或定义它的位置，包括字面量数组中的方法
+    methods: HashMap<uint32_t, Vec<Xref>>,
+    /// 字符串 -> 使用它的位置
+    strings: HashMap<String, Vec<Xref>>,
+    /// 属性名、全局变量名 -> 按名字访问它的位置
+    names: HashMap<String, Vec<Xref>>,
+}
+
+/// 按名字访问属性或全局变量的指令，如 `ldobjbyname`、`tryldglobalbyname`、`stglobalvar`
+fn is_name_access(mnemonic: &str) -> bool {
+    mnemonic.contains("byname") || mnemonic.contains("globalvar")
+}
+
+impl XrefIndex {
+    /// `code` 为 `AbcFile::parse_code` 的结果
+    pub fn new(code: &[(String, Vec<Instruction>)]) -> Self {
+        let mut index = XrefIndex::default();
+        for (method, instructions) in code {
+            for instruction in instructions {
+                let xref = || Xref {
+                    method: method.clone(),
+                    pc: *instruction.offset(),
+                    mnemonic: instruction.mnemonic().clone(),
+                };
+                for operand in instruction.operands() {
+                    match operand {
+                        Operand::Method { offset, .. } => {
+                            index.methods.entry(*offset).or_default().push(xref());
+                        }
+                        Operand::String { value, .. } => {
+                            index.strings.entry(value.clone()).or_default().push(xref());
+                            if is_name_access(instruction.mnemonic()) {
+                                index.names.entry(value.clone()).or_default().push(xref());
+                            }
+                        }
+                        Operand::LiteralArray { value, .. } => {
+                            for entry in value.entries() {
+                                match entry {
+                                    Literal::Method { offset, .. }
+                                    | Literal::GeneratorMethod { offset, .. }
+                                    | Literal::AsyncGeneratorMethod { offset, .. }
+                                    | Literal::Getter { offset, .. }
+                                    | Literal::Setter { offset, .. } => {
+                                        index.methods.entry(*offset).or_default().push(xref());
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        // 类的遍历顺序不固定，排序后输出稳定
+        let sort = |xrefs: &mut Vec<Xref>| {
+            xrefs.sort_by(|a, b| (&a.method, a.pc).cmp(&(&b.method, b.pc)));
+            xrefs.dedup();
+        };
+        index.methods.values_mut().for_each(sort);
+        index.strings.values_mut().for_each(sort);
+        index.names.values_mut().for_each(sort);
+        index
+    }
+
+    /// 引用偏移量为 `method_off` 的方法的位置
+    pub fn callers_of(&self, method_off: uint32_t) -> &[Xref] {
+        self.methods.get(&method_off).map_or(&[], Vec::as_slice)
+    }
+
+    /// 使用字符串 `value` 的位置
+    pub fn string_refs(&self, value: &str) -> &[Xref] {
+        self.strings.get(value).map_or(&[], Vec::as_slice)
+    }
+
+    /// 按名字访问属性或全局变量 `name` 的位置
+    pub fn name_refs(&self, name: &str) -> &[Xref] {
+        self.names.get(name).map_or(&[], Vec::as_slice)
+    }
+}
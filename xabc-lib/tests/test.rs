@@ -1,7 +1,1226 @@
 //use rs_xabc::abc::ABCReader;
 
+use xabc_lib::abc::AbcReader;
+use xabc_lib::annotation::{AnnotationKind, AnnotationValue};
+use xabc_lib::assembler::{Arg, CodeBuilder};
+use xabc_lib::bytecode::Operand;
+use xabc_lib::callgraph::{CallKind, NodeKind};
+use xabc_lib::field::FieldValue;
+use xabc_lib::patcher::AbcPatcher;
+use xabc_lib::region::{PrimitiveType, TypeRef};
+use xabc_lib::string::{ABCString, StringEncoding};
+use xabc_lib::writer::{AbcWriter, FieldInit, LiteralValue};
+
 #[test]
 fn test_aac() {
     //let header = ABCReader::from_file("fixtures/demo.abc").unwrap();
     //println!("{}", header.header());
 }
+
+#[test]
+fn test_truncated_file() {
+    let data = std::fs::read("fixtures/demo.abc").unwrap();
+    assert!(AbcReader::from_vec(data.clone()).is_ok());
+
+    // 截断的文件只能返回错误，不能 panic
+    for len in (0..data.len()).step_by(61) {
+        if let Ok(abc) = AbcReader::from_array(&data[..len]) {
+            let _ = abc.get_method_names();
+            let _ = abc.get_strings();
+        }
+    }
+}
+
+#[test]
+fn test_parse_method() {
+    use xabc_lib::bytecode::Operand;
+
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+    let instructions = abc
+        .parse_method("Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->onCreate")
+        .unwrap();
+
+    assert_eq!(instructions[0].mnemonic(), "mov");
+    assert!(matches!(
+        instructions[0].operands()[..],
+        [Operand::Register(0), Operand::Register(12)]
+    ));
+
+    let lda_str = instructions
+        .iter()
+        .find(|ins| ins.mnemonic() == "lda.str")
+        .unwrap();
+    assert!(matches!(
+        &lda_str.operands()[0],
+        Operand::String { value, .. } if value == "testTag"
+    ));
+
+    // 指令首尾相接
+    for pair in instructions.windows(2) {
+        assert_eq!(pair[0].offset() + pair[0].size(), *pair[1].offset());
+    }
+    assert_eq!(instructions.last().unwrap().mnemonic(), "returnundefined");
+
+    assert!(abc.parse_method("La;->b").is_err());
+}
+
+#[test]
+fn test_method_signature() {
+    use scroll::Pread;
+    use xabc_lib::proto::{Proto, ShortyType};
+
+    // (I32, Lx;)V -> 0x1 0x7 0xd 0x0，引用类型的索引为 5
+    let data = [0x71, 0x0d, 0x05, 0x00];
+    let proto = data.pread::<Proto>(0).unwrap();
+    assert_eq!(
+        proto.shorty(),
+        &vec![ShortyType::Void, ShortyType::I32, ShortyType::Reference]
+    );
+    assert_eq!(proto.reference_types(), &vec![5]);
+
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+    let signatures = abc.get_method_signatures().unwrap();
+    assert!(signatures.contains(
+        &"Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->onCreate(any, any, any, any, any)any"
+            .to_string()
+    ));
+
+    let name = "Lcom.example.myapplication/entry/ets/pages/Index;->message";
+    let instructions = abc
+        .parse_method(&format!("{}(any, any, any)any", name))
+        .unwrap();
+    assert_eq!(instructions.len(), abc.parse_method(name).unwrap().len());
+    assert!(abc.parse_method(&format!("{}(any)any", name)).is_err());
+
+    // proto_idx 越界时报错，不当作没有原型的方法
+    use xabc_lib::method::Method;
+    let source = std::fs::read("fixtures/demo.abc").unwrap();
+    let (region, _) = abc.get_code(name).unwrap().unwrap();
+    let data = [0, 0, 0x34, 0x12, 0, 0, 0, 0, 0x01, 0x00];
+    let method = data.pread::<Method>(0).unwrap();
+    let err = method.proto(&source, region).unwrap_err();
+    assert!(err.to_string().contains("Method.proto_idx"));
+}
+
+#[test]
+fn test_line_number_table() {
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+    let name = "Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->onCreate";
+    let table = abc.get_line_number_table(name).unwrap().unwrap();
+
+    assert!(table
+        .source_file()
+        .as_ref()
+        .unwrap()
+        .ends_with("EntryAbility.ts"));
+
+    let pos = table.position_of(0x1c).unwrap();
+    assert_eq!((pos.pc, pos.line, pos.column), (0x1b, 8, 19));
+    assert!(table.position_of(0).is_none());
+
+    let names: Vec<&str> = table
+        .local_variables()
+        .iter()
+        .map(|var| var.name().as_str())
+        .collect();
+    assert!(names.contains(&"this"));
+    assert!(names.contains(&"want"));
+
+    // 两次 ADVANCE_PC 使 pc 超出 u32，返回错误而不是溢出
+    use scroll::Pread;
+    use xabc_lib::lnp::{DebugInfo, LineNumberProgram};
+    let debug_info = [
+        1, 0, 10, 0xff, 0xff, 0xff, 0xff, 0x0f, 0xff, 0xff, 0xff, 0xff, 0x0f, 0,
+    ]
+    .pread::<DebugInfo>(0)
+    .unwrap();
+    let program = [0x01, 0x01, 0x00];
+    let err = LineNumberProgram::new(&program, 0)
+        .run(&debug_info)
+        .unwrap_err();
+    assert!(err.to_string().contains("pc overflows"));
+}
+
+#[test]
+fn test_try_blocks() {
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+    let name =
+        "Lcom.example.myapplication/entry/ets/entrybackupability/EntryBackupAbility;->onBackup";
+    let (region, code) = abc.get_code(name).unwrap().unwrap();
+
+    assert_eq!(code.try_blocks().len(), 1);
+    let try_block = &code.try_blocks()[0];
+    assert_eq!((*try_block.start_pc(), *try_block.length()), (9, 45));
+
+    let catch_block = &try_block.catch_blocks()[0];
+    assert!(catch_block.is_catch_all());
+    assert_eq!(*catch_block.handler_pc(), try_block.end_pc());
+    assert_eq!(catch_block.handler_end_pc(), *code.code_size());
+    assert_eq!(catch_block.catch_type(region).unwrap(), None);
+
+    assert_eq!(code.labels_at(9), vec!["try_begin_label_0"]);
+    assert_eq!(
+        code.labels_at(54),
+        vec!["try_end_label_0", "handler_begin_label_0_0"]
+    );
+}
+
+#[test]
+fn test_literal_array() {
+    use xabc_lib::literal::Literal;
+
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+
+    let array = abc.get_literal_array(10614).unwrap();
+    assert_eq!(*array.num_literals() as usize, array.entries().len() * 2);
+    assert_eq!(
+        array.entries()[0],
+        Literal::String("bundleName".to_string())
+    );
+
+    let array = abc.get_literal_array(5550).unwrap();
+    assert!(matches!(
+        &array.entries()[1],
+        Literal::Method { name, .. } if name.ends_with("->onBackup(any, any, any)any")
+    ));
+    assert_eq!(array.entries()[2], Literal::MethodAffiliate(0));
+
+    // 模块记录不是普通的字面量数组
+    assert!(abc.get_literal_array(4172).is_none());
+    let record = &abc.module_records()[&4172];
+    assert_eq!(
+        record.module_requests(),
+        &vec!["@ohos:app.ability.UIAbility", "@ohos:hilog"]
+    );
+    let import = &record.regular_imports()[1];
+    assert_eq!(import.local_name(), "hilog");
+    assert_eq!(
+        record.module_request(*import.module_request_idx()).unwrap(),
+        "@ohos:hilog"
+    );
+    assert_eq!(record.local_exports()[0].local_name(), "EntryAbility");
+
+    // 畸形的字面量数组只跳过它自己：超大的元素个数不能导致分配失败，未知的 Tag 不能导致加载失败
+    let count = abc.literal_arrays().len();
+    let mut data = std::fs::read("fixtures/demo.abc").unwrap();
+    data[10614..10618].copy_from_slice(&u32::MAX.to_le_bytes());
+    data[10618] = 0x12;
+    data[5554] = 0xee;
+    let abc = AbcReader::from_vec(data).unwrap();
+    assert!(abc.get_literal_array(10614).is_none());
+    assert!(abc.get_literal_array(5550).is_none());
+    assert_eq!(abc.literal_arrays().len(), count - 2);
+}
+
+#[test]
+fn test_verify() {
+    let data = std::fs::read("fixtures/demo.abc").unwrap();
+    let abc = AbcReader::from_array(&data).unwrap();
+    assert!(abc.verify().is_ok());
+    assert_eq!(
+        abc.header().checksum_value(),
+        xabc_lib::header::compute_checksum(&data)
+    );
+
+    // 修改字符串内容，结构不变但校验和不匹配
+    let pos = data.windows(7).position(|w| w == b"testTag").unwrap();
+    let mut tampered = data.clone();
+    tampered[pos] = b'T';
+    let abc = AbcReader::from_vec(tampered).unwrap();
+    assert!(abc.verify().is_err());
+
+    // 文件末尾追加数据
+    let mut appended = data.clone();
+    appended.push(0);
+    let abc = AbcReader::from_vec(appended).unwrap();
+    assert!(abc.verify().is_err());
+
+    // 两个相同的 RegionHeader 连续存放，按 RegionHeader 的大小逐个读取
+    use xabc_lib::header::{compute_checksum, REGION_HEADER_SIZE};
+    let mut writer = AbcWriter::default();
+    let class = writer.add_class("Lcom/example/Regions;", 0x1);
+    let method = writer.add_method(class, "func_main_0", 0x8);
+    let mut code = CodeBuilder::new(0, 3);
+    code.emit("returnundefined", &[]);
+    writer.set_code(method, &code).unwrap();
+    let mut data = writer.write().unwrap();
+    let region = data.len() - REGION_HEADER_SIZE;
+    assert_eq!(data[56..60], (region as u32).to_le_bytes());
+    data.extend_from_within(region..);
+    let file_size = data.len() as u32;
+    data[16..20].copy_from_slice(&file_size.to_le_bytes());
+    data[52..56].copy_from_slice(&2u32.to_le_bytes());
+    let checksum = compute_checksum(&data);
+    data[8..12].copy_from_slice(&checksum.to_le_bytes());
+    let abc = AbcReader::from_vec(data).unwrap();
+    assert!(abc.verify().is_ok());
+    assert_eq!(abc.header().region_size(), 2);
+    assert_eq!(
+        abc.parse_method("Lcom/example/Regions;->func_main_0")
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_version() {
+    use xabc_lib::bytecode::BytecodeParser;
+    use xabc_lib::version::Version;
+
+    let data = std::fs::read("fixtures/demo.abc").unwrap();
+    let abc = AbcReader::from_array(&data).unwrap();
+    let version = abc.header().bytecode_version();
+    assert_eq!(version.to_string(), "12.0.2.0");
+    assert_eq!(version.api_range(), Some((12, 12)));
+    assert_eq!(Version::new(9, 0, 0, 0).api_range(), Some((9, 10)));
+
+    // callruntime.definesendableclass 从 API 12 开始出现
+    let parser = BytecodeParser::with_version(Version::new(11, 0, 2, 0));
+    assert!(parser.prefix_opcode_table.contains_key(&0x01fb));
+    assert!(!parser.prefix_opcode_table.contains_key(&0x07fb));
+    let parser = BytecodeParser::with_version(version);
+    assert!(parser.prefix_opcode_table.contains_key(&0x07fb));
+
+    // 旧指令集和过渡版本都不支持
+    for unsupported in [[0, 0, 0, 2], [10, 0, 1, 0], [99, 0, 0, 0]] {
+        let mut data = data.clone();
+        data[12..16].copy_from_slice(&unsupported);
+        assert!(AbcReader::from_vec(data).is_err());
+    }
+}
+
+#[test]
+fn test_deprecated_opcodes() {
+    use xabc_lib::bytecode::BytecodeParser;
+
+    let parser = BytecodeParser::new();
+    let deprecated: Vec<_> = parser
+        .prefix_opcode_table
+        .iter()
+        .filter(|(key, _)| *key & 0xff == 0xfc)
+        .collect();
+    assert_eq!(deprecated.len(), 0x2f);
+
+    let cases = [
+        (0x00fc, "deprecated.ldlexenv", 2),
+        (0x07fc, "deprecated.neg", 3),
+        (0x12fc, "deprecated.defineclasswithbuffer", 10),
+        (0x1dfc, "deprecated.ldobjbyindex", 7),
+        (0x20fc, "deprecated.stlexvar", 4),
+        (0x27fc, "deprecated.ldmodulevar", 7),
+    ];
+    for (key, mnemonic, size) in cases {
+        let bcf = &parser.prefix_opcode_table[&key];
+        assert_eq!(bcf.mnemonic(), mnemonic);
+        assert_eq!(bcf.get_size(), size);
+    }
+
+    // 32位ID 直接是字符串在文件中的偏移量，解码后接着解码下一条指令
+    use scroll::Pread;
+    use xabc_lib::code::Code;
+    let source = std::fs::read("fixtures/demo.abc").unwrap();
+    let abc = AbcReader::from_array(&source).unwrap();
+    let method = "Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->onCreate";
+    let string_offset = abc
+        .parse_method(method)
+        .unwrap()
+        .iter()
+        .find_map(|ins| match &ins.operands()[..] {
+            [Operand::String { offset, value, .. }] if value == "testTag" => Some(*offset),
+            _ => None,
+        })
+        .unwrap();
+    let (region, _) = abc.get_code(method).unwrap().unwrap();
+    let mut data = vec![0, 0, 8, 0, 0xfc, 0x27];
+    data.extend(string_offset.to_le_bytes());
+    data.extend([0x01, 0x65]);
+    let code = data.pread::<Code>(0).unwrap();
+    let instructions = parser
+        .parse(&code, region, &source, abc.literal_arrays())
+        .unwrap();
+    assert_eq!(instructions.len(), 2);
+    assert_eq!(instructions[0].size(), 7);
+    assert!(matches!(
+        &instructions[0].operands()[..],
+        [Operand::String { idx: None, offset, value }, Operand::Imm(1)]
+            if *offset == string_offset && value == "testTag"
+    ));
+    assert_eq!(instructions[1].mnemonic(), "returnundefined");
+}
+
+#[test]
+fn test_unknown_bytes() {
+    use scroll::Pread;
+    use xabc_lib::bytecode::{BytecodeParser, Coverage};
+    use xabc_lib::code::Code;
+
+    let source = std::fs::read("fixtures/demo.abc").unwrap();
+    let abc = AbcReader::from_array(&source).unwrap();
+    let method = "Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->onCreate";
+    let (region, _) = abc.get_code(method).unwrap().unwrap();
+
+    let parser = BytecodeParser::new();
+    let unknown = (0..=0xffu8)
+        .find(|op| {
+            ![0xfb, 0xfc, 0xfd, 0xfe].contains(op)
+                && !parser.opcode_table.contains_key(&(*op as u16))
+        })
+        .unwrap();
+
+    // lda v7; 未知字节; 被截断的 ldai，剩下的 0x00 仍按 ldundefined 解码
+    let data = [0, 0, 5, 0, 0x60, 0x07, unknown, 0x62, 0x00];
+    let code = data.pread::<Code>(0).unwrap();
+    let instructions = parser
+        .parse(&code, region, &source, abc.literal_arrays())
+        .unwrap();
+
+    let mnemonics: Vec<_> = instructions.iter().map(|i| i.mnemonic().as_str()).collect();
+    assert_eq!(mnemonics, ["lda", ".byte", ".byte", "ldundefined"]);
+    assert!(instructions[1].is_unknown());
+    assert!(instructions[2]
+        .diagnostic()
+        .as_ref()
+        .unwrap()
+        .contains("truncated"));
+    assert_eq!(
+        instructions[1].to_string(),
+        format!("0x{:02X} : .byte 0x{:02x}", unknown, unknown)
+    );
+
+    let coverage = Coverage::from_instructions(&instructions);
+    assert_eq!(*coverage.decoded_bytes(), 3);
+    assert_eq!(*coverage.unknown_bytes(), 2);
+
+    // lda.str 引用的字符串索引越界，整条指令当作数据，后面的 sta 仍然解码
+    let data = [0, 0, 5, 0, 0x3e, 0xff, 0xff, 0x61, 0x00];
+    let code = data.pread::<Code>(0).unwrap();
+    let instructions = parser
+        .parse(&code, region, &source, abc.literal_arrays())
+        .unwrap();
+    let mnemonics: Vec<_> = instructions.iter().map(|i| i.mnemonic().as_str()).collect();
+    assert_eq!(mnemonics, [".byte", ".byte", ".byte", "sta"]);
+    assert!(instructions[..3].iter().all(|i| i.is_unknown()));
+    assert!(instructions[0]
+        .diagnostic()
+        .as_ref()
+        .unwrap()
+        .contains("lda.str"));
+    let coverage = Coverage::from_instructions(&instructions);
+    assert_eq!(*coverage.decoded_bytes(), 2);
+    assert_eq!(*coverage.unknown_bytes(), 3);
+
+    let coverage = abc.coverage().unwrap();
+    assert_eq!(*coverage.unknown_bytes(), 0);
+}
+
+#[test]
+fn test_branch_labels() {
+    use xabc_lib::bytecode::{branch_labels, Operand};
+
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+    let method = "Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->ifTest";
+    let instructions = abc.parse_method(method).unwrap();
+    let labels = branch_labels(&instructions);
+    assert_eq!(labels.len(), 6);
+
+    // 循环末尾向回跳转的 jmp，偏移量是负数
+    let back_jump = instructions
+        .iter()
+        .find(|i| i.bytes() == &[0x4d, 0xd1])
+        .unwrap();
+    let Operand::Branch {
+        offset,
+        target,
+        label,
+    } = &back_jump.operands()[0]
+    else {
+        panic!("jmp 的操作数应为分支偏移量");
+    };
+    assert_eq!(*offset, -47);
+    assert_eq!(*target, *back_jump.offset() as i64 - 47);
+    assert_eq!(label.as_deref(), Some("label_4"));
+    assert_eq!(labels[&(*target as usize)], "label_4");
+    assert_eq!(back_jump.to_string(), "0x4DD1 : jmp label_4");
+}
+
+#[test]
+fn test_cfg() {
+    use xabc_lib::cfg::EdgeKind;
+
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+
+    // if/else 和 for 循环
+    let method = "Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->ifTest";
+    let cfg = abc.get_cfg(method).unwrap().unwrap();
+    assert_eq!(cfg.blocks().len(), 10);
+    assert_eq!(cfg.blocks()[0].successors(), &[2, 1]);
+    assert_eq!(cfg.blocks()[3].predecessors(), &[1, 2]);
+    assert_eq!(cfg.immediate_dominator(3), Some(0));
+    // 循环头支配循环体，循环体跳回循环头
+    assert_eq!(cfg.blocks()[8].successors(), &[7]);
+    assert!(cfg.dominates(7, 8));
+    assert!(!cfg.dominates(1, 3));
+    assert_eq!(cfg.dominators(9), [9, 7, 6, 3, 0]);
+    let kinds: Vec<_> = cfg
+        .edges()
+        .iter()
+        .filter(|e| *e.from() == 7)
+        .map(|e| *e.kind())
+        .collect();
+    assert_eq!(kinds, [EdgeKind::True, EdgeKind::False]);
+
+    // try 中的 return 没有后继，只有到异常处理块的边
+    let method =
+        "Lcom.example.myapplication/entry/ets/entrybackupability/EntryBackupAbility;->onBackup";
+    let cfg = abc.get_cfg(method).unwrap().unwrap();
+    assert_eq!(cfg.blocks().len(), 3);
+    let edges: Vec<_> = cfg
+        .edges()
+        .iter()
+        .map(|e| (*e.from(), *e.to(), *e.kind()))
+        .collect();
+    assert_eq!(
+        edges,
+        [(0, 1, EdgeKind::Unconditional), (1, 2, EdgeKind::Exception)]
+    );
+    assert_eq!(cfg.block_at(0x36).map(|b| *b.id()), Some(2));
+}
+
+#[test]
+fn test_cfg_dot() {
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+    let method = "Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->ifTest";
+    let dot = abc.get_cfg(method).unwrap().unwrap().to_dot(method);
+
+    assert!(dot.starts_with(&format!("digraph \"{}\" {{", method)));
+    assert!(dot.contains("bb2 [label=\"bb2 [0x0032, 0x0040)\\llabel_0:\\l"));
+    assert!(dot.contains("bb0 -> bb2 [label=\"true\"];"));
+    assert!(dot.contains("bb0 -> bb1 [label=\"false\"];"));
+    assert!(dot.contains("bb8 -> bb7;"));
+    assert!(dot.contains("ldobjbyname \\\"field1\\\""));
+    assert!(dot.ends_with("}\n"));
+}
+
+#[test]
+fn test_def_use() {
+    use xabc_lib::dataflow::{AccAccess, DefSite, DefUseChains, Effects, Location};
+
+    assert_eq!(AccAccess::of("lda"), AccAccess::Write);
+    assert_eq!(AccAccess::of("sta"), AccAccess::Read);
+    assert_eq!(AccAccess::of("add2"), AccAccess::ReadWrite);
+    assert_eq!(AccAccess::of("jmp"), AccAccess::None);
+    assert_eq!(AccAccess::of("jnez"), AccAccess::Read);
+
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+
+    // callthisrange +4 v7：this 是 v7，参数是 v8 ~ v11，函数在 acc 中
+    let method = "Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->onCreate";
+    let instructions = abc.parse_method(method).unwrap();
+    let call = instructions
+        .iter()
+        .find(|i| i.mnemonic() == "callthisrange")
+        .unwrap();
+    let effects = Effects::of(call);
+    let mut reads = (7..=11).map(Location::Register).collect::<Vec<_>>();
+    reads.push(Location::Acc);
+    assert_eq!(effects.reads(), &reads);
+    assert_eq!(effects.writes(), &[Location::Acc]);
+
+    let method = "Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->ifTest";
+    let cfg = abc.get_cfg(method).unwrap().unwrap();
+    let chains = DefUseChains::new(&cfg);
+    // 参数来自方法入口
+    assert_eq!(
+        chains.definitions(0x00, Location::Register(11)),
+        [DefSite::Entry]
+    );
+    // if/else 两个分支对 v5 的定义在汇合处都能到达
+    assert_eq!(
+        chains.definitions(0x7c, Location::Register(5)),
+        [DefSite::Instruction(0x5a), DefSite::Instruction(0x6a)]
+    );
+    // 循环变量 v4 的定义来自循环前和循环体
+    assert_eq!(
+        chains.definitions(0x92, Location::Register(4)),
+        [DefSite::Instruction(0x90), DefSite::Instruction(0xbf)]
+    );
+    assert_eq!(
+        chains.uses(DefSite::Instruction(0xbf), Location::Register(4)),
+        [0x92, 0xa9, 0xb3]
+    );
+
+    // 异常处理块中，acc 是异常对象
+    let method =
+        "Lcom.example.myapplication/entry/ets/entrybackupability/EntryBackupAbility;->onBackup";
+    let cfg = abc.get_cfg(method).unwrap().unwrap();
+    let chains = DefUseChains::new(&cfg);
+    assert_eq!(
+        chains.definitions(0x36, Location::Acc),
+        [DefSite::Exception(0x36)]
+    );
+
+    // 只有一个分支写 v0，汇合处方法入口的值也能到达
+    let mut writer = AbcWriter::default();
+    let class = writer.add_class("Lcom/example/Diamond;", 0x1);
+    let method = writer.add_method(class, "func_main_0", 0x8);
+    let mut code = CodeBuilder::new(1, 3);
+    code.emit("lda", &[Arg::Reg(1)])
+        .emit("jeqz", &[Arg::Label("join".to_string())])
+        .emit("lda", &[Arg::Reg(2)])
+        .emit("sta", &[Arg::Reg(0)])
+        .label("join")
+        .emit("lda", &[Arg::Reg(0)])
+        .emit("return", &[]);
+    writer.set_code(method, &code).unwrap();
+    let abc = AbcReader::from_vec(writer.write().unwrap()).unwrap();
+    let name = "Lcom/example/Diamond;->func_main_0";
+    let instructions = abc.parse_method(name).unwrap();
+    let sta = *instructions[3].offset();
+    let join = *instructions[4].offset();
+    let cfg = abc.get_cfg(name).unwrap().unwrap();
+    let chains = DefUseChains::new(&cfg);
+    assert_eq!(
+        chains.definitions(join, Location::Register(0)),
+        [DefSite::Entry, DefSite::Instruction(sta)]
+    );
+    assert_eq!(chains.uses(DefSite::Entry, Location::Register(0)), [join]);
+}
+
+#[test]
+fn test_decompile() {
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+    let decompile = |method: &str| {
+        let name = format!("Lcom.example.myapplication/entry/ets/{}", method);
+        abc.decompile_method(&name).unwrap().unwrap().to_string()
+    };
+
+    // if/else 化简为条件表达式，for 循环还原为 while
+    let code = decompile("entryability/EntryAbility;->ifTest");
+    assert!(code.starts_with("function ifTest(arg0) {\n    v5 = this.field1;\n"));
+    assert!(code.contains("    v5 = (arg0 > 20) ? (v5 + 30) : (v5 - 10);\n"));
+    assert!(code.contains("    this.field1 = v9.innerCall(v5);\n"));
+    assert!(code.contains(
+        "    while (v4 < v5) {\n        v7 = this;\n        v7.field1 = v7.field1 + v4;\n        v4 = v4 + 1;\n    }\n"
+    ));
+
+    // 短路求值合并为一个条件
+    let code = decompile("entryability/EntryAbility;->#7548038477501280574#");
+    assert!(code.contains("    if (arg0.code) {\n"));
+    assert!(code.contains("acc = (!((v10 !== null) && (v10 !== undefined))) ? \"\" : v10;"));
+    assert!(!code.contains("goto"));
+
+    let code = decompile("entrybackupability/EntryBackupAbility;->onBackup");
+    assert!(code.contains(
+        "    try {\n        v5 = import_1;\n        v5.info(0, \"testTag\", \"onBackup ok\");\n"
+    ));
+    assert!(code.contains("    } catch (e) {\n        v4 = e;\n"));
+
+    // 范围调用的寄存器编号超出 u16，按指令原样输出
+    let mut writer = AbcWriter::default();
+    let class = writer.add_class("Lcom/example/Range;", 0x1);
+    let method = writer.add_method(class, "func_main_0", 0x8);
+    let mut code = CodeBuilder::new(1, 3);
+    code.emit("lda", &[Arg::Reg(1)])
+        .emit("wide.callrange", &[Arg::Imm(0xffff), Arg::Reg(0xf0)])
+        .emit("return", &[]);
+    writer.set_code(method, &code).unwrap();
+    let abc = AbcReader::from_vec(writer.write().unwrap()).unwrap();
+    let code = abc
+        .decompile_method("Lcom/example/Range;->func_main_0")
+        .unwrap()
+        .unwrap()
+        .to_string();
+    assert!(code.contains("wide.callrange("));
+}
+
+#[test]
+fn test_write_pa() {
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+    let mut buf = Vec::new();
+    abc.write_pa(&mut buf).unwrap();
+    let pa = String::from_utf8(buf).unwrap();
+
+    assert!(pa.starts_with(".language ECMAScript\n"));
+    assert!(pa.contains("\n.record com.example.myapplication.entry.ets.pages.Index {\n"));
+    assert!(pa.contains(
+        "\tmethod: com.example.myapplication.entry.ets.entryability.EntryAbility.onCreate,\n"
+    ));
+
+    // 跳转和异常处理都使用标签
+    assert!(pa.contains("\tjeqz label_0\n"));
+    assert!(pa.contains(
+        "\tasyncfunctionreject v3\n\treturn\nhandler_end_label_0_0:\n\n\t.catchall try_begin_label_0, try_end_label_0, handler_begin_label_0_0, handler_end_label_0_0\n}\n"
+    ));
+
+    // 输出稳定，便于比较
+    let mut again = Vec::new();
+    abc.write_pa(&mut again).unwrap();
+    assert_eq!(pa.as_bytes(), again.as_slice());
+}
+
+#[test]
+fn test_writer_round_trip() {
+    let mut writer = AbcWriter::default();
+    let hilog = writer.add_foreign_class("L@ohos.hilog;");
+    let class = writer.add_class("Lcom/example/Demo;", 0x1);
+    writer.add_field(class, "logger", hilog, 0x1, Some(FieldInit::Int(-3)));
+    let main = writer.add_method(class, "func_main_0", 0x8);
+    let helper = writer.add_method(class, "helper", 0x8);
+    let array = writer.add_literal_array(vec![
+        LiteralValue::String("helper".to_string()),
+        LiteralValue::Method(helper),
+        LiteralValue::MethodAffiliate(0),
+        LiteralValue::Integer(1),
+    ]);
+
+    let mut code = CodeBuilder::new(2, 3);
+    code.label("try_begin")
+        .emit("lda.str", &[Arg::String("你好".to_string())])
+        .emit("sta", &[Arg::Reg(0)])
+        .emit("definefunc", &[Arg::Method(helper), Arg::Imm(0)])
+        .emit("createobjectwithbuffer", &[Arg::LiteralArray(array)])
+        .emit("lda", &[Arg::Reg(0)])
+        .emit("jeqz", &[Arg::Label("end".to_string())])
+        .emit("tryldglobalbyname", &[Arg::String("print".to_string())])
+        .emit("callarg1", &[Arg::Reg(4)])
+        .label("try_end")
+        .label("end")
+        .emit("returnundefined", &[])
+        .label("handler")
+        .emit("sta", &[Arg::Reg(1)])
+        .emit("returnundefined", &[])
+        .label("handler_end")
+        .try_catch("try_begin", "try_end", "handler", "handler_end", None);
+    let labels = writer.set_code(main, &code).unwrap();
+    assert_eq!(labels["end"], 25);
+
+    // 跳转距离超过 127 时换成 16 位偏移量
+    let mut code = CodeBuilder::new(0, 3);
+    code.emit("jmp", &[Arg::Label("far".to_string())]);
+    for _ in 0..200 {
+        code.emit("ldundefined", &[]);
+    }
+    code.label("far").emit("returnundefined", &[]);
+    writer.set_code(helper, &code).unwrap();
+
+    let data = writer.write().unwrap();
+    let abc = AbcReader::from_vec(data).unwrap();
+    abc.verify().unwrap();
+    assert_eq!(abc.get_class_names(), vec!["Lcom/example/Demo;"]);
+
+    let mut signatures = abc.get_method_signatures().unwrap();
+    signatures.sort();
+    assert_eq!(
+        signatures,
+        vec![
+            "Lcom/example/Demo;->func_main_0(any, any, any)any",
+            "Lcom/example/Demo;->helper(any, any, any)any",
+        ]
+    );
+
+    let instructions = abc.parse_method("Lcom/example/Demo;->func_main_0").unwrap();
+    let listing: Vec<String> = instructions
+        .iter()
+        .map(|i| i.to_string().split(" : ").nth(1).unwrap().to_string())
+        .collect();
+    assert_eq!(
+        listing,
+        vec![
+            "lda.str \"你好\"",
+            "sta v0",
+            "definefunc Lcom/example/Demo;->helper(any, any, any)any +0",
+            "createobjectwithbuffer { str: \"helper\", method: Lcom/example/Demo;->helper(any, any, any)any, method_affiliate: 0, i32: 1 }",
+            "lda v0",
+            "jeqz label_0",
+            "tryldglobalbyname \"print\"",
+            "callarg1 v4",
+            "returnundefined",
+            "sta v1",
+            "returnundefined",
+        ]
+    );
+    assert_eq!(instructions[5].branch_target(), Some(25));
+
+    let (_, code) = abc
+        .get_code("Lcom/example/Demo;->func_main_0")
+        .unwrap()
+        .unwrap();
+    let try_block = &code.try_blocks()[0];
+    assert_eq!((*try_block.start_pc(), try_block.end_pc()), (0, 25));
+    assert!(try_block.catch_blocks()[0].is_catch_all());
+    assert_eq!(*try_block.catch_blocks()[0].handler_pc(), 26);
+
+    let instructions = abc.parse_method("Lcom/example/Demo;->helper").unwrap();
+    assert_eq!(instructions[0].size(), 3);
+    assert_eq!(instructions[0].branch_target(), Some(203));
+
+    let mut pa = Vec::new();
+    abc.write_pa(&mut pa).unwrap();
+    let pa = String::from_utf8(pa).unwrap();
+    assert!(pa.contains("\tL@ohos.hilog; logger = -3\n"));
+    assert!(pa.contains("\tjmp label_0\n"));
+}
+
+#[test]
+fn test_patcher() {
+    let method = "Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->ifTest";
+    let data = std::fs::read("fixtures/demo.abc").unwrap();
+    let mut patcher = AbcPatcher::new(data.clone()).unwrap();
+
+    let instructions = patcher.instructions(method).unwrap();
+    let branch = instructions
+        .iter()
+        .find(|i| i.mnemonic() == "jeqz")
+        .unwrap();
+    let target = |i: &xabc_lib::bytecode::Instruction| match &i.operands()[0] {
+        Operand::Branch { target, .. } => *target,
+        operand => panic!("{:?}", operand),
+    };
+    let (branch_pc, branch_target) = (*branch.offset(), target(branch));
+    let greater_pc = *instructions
+        .iter()
+        .find(|i| i.mnemonic() == "greater")
+        .unwrap()
+        .offset();
+
+    patcher.invert_branch(method, branch_pc).unwrap();
+    patcher.nop(method, greater_pc).unwrap();
+    assert_eq!(patcher.replace_string("field1", "FIELD1").unwrap(), 1);
+
+    // 长度不同或放不下的修改返回错误，不改变文件
+    assert!(patcher.replace_string("field1", "field").is_err());
+    assert!(patcher
+        .patch_instruction(method, branch_pc, "ldai", &[Arg::Imm(1)])
+        .is_err());
+    assert!(patcher.nop(method, branch_pc + 1).is_err());
+
+    let patched = patcher.finish();
+    assert_eq!(patched.len(), data.len());
+    let abc = AbcReader::from_vec(patched).unwrap();
+    abc.verify().unwrap();
+
+    let instructions = abc.parse_method(method).unwrap();
+    let branch = instructions
+        .iter()
+        .find(|i| *i.offset() == branch_pc)
+        .unwrap();
+    assert_eq!(branch.mnemonic(), "jnez");
+    assert_eq!(target(branch), branch_target);
+    let nops: Vec<_> = instructions
+        .iter()
+        .filter(|i| (greater_pc..greater_pc + 3).contains(i.offset()))
+        .map(|i| i.mnemonic().as_str())
+        .collect();
+    assert_eq!(nops, ["nop", "nop", "nop"]);
+    assert!(instructions
+        .iter()
+        .any(|i| i.mnemonic() == "ldobjbyname" && i.operands()[0].to_string() == "\"FIELD1\""));
+}
+
+#[test]
+fn test_xref() {
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+    let prefix = "Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->";
+
+    // 类中的方法由 defineclasswithbuffer 的字面量数组引用
+    let callers = abc.callers_of(&format!("{}onCreate", prefix)).unwrap();
+    assert_eq!(callers.len(), 1);
+    assert_eq!(callers[0].method(), &format!("{}func_main_0", prefix));
+    assert_eq!(callers[0].mnemonic(), "defineclasswithbuffer");
+
+    let names = abc.name_refs("field1").unwrap();
+    assert!(names
+        .iter()
+        .any(|xref| xref.method() == &format!("{}ifTest", prefix) && *xref.pc() == 0xe));
+    assert!(names.iter().all(|xref| xref.mnemonic().contains("byname")));
+
+    // lda.str 使用字符串，但不是按名字访问
+    let strings = abc.string_refs("testTag").unwrap();
+    assert!(strings.iter().any(|xref| xref.mnemonic() == "lda.str"));
+    assert!(abc.name_refs("testTag").unwrap().is_empty());
+}
+
+#[test]
+fn test_call_graph() {
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+    let graph = abc.call_graph().unwrap();
+    let prefix = "Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->";
+    let on_create = format!("{}onCreate", prefix);
+
+    // hilog 从 `@ohos:hilog` 导入，`ldobjbyname "info"` 之后调用
+    assert_eq!(graph.nodes()["@ohos.hilog.info"], NodeKind::System);
+    assert!(graph
+        .callees(&on_create)
+        .any(|edge| edge.callee() == "@ohos.hilog.info" && *edge.kind() == CallKind::Call));
+    assert!(graph
+        .callers(&on_create)
+        .any(|edge| edge.caller() == &format!("{}func_main_0", prefix)
+            && *edge.kind() == CallKind::Define));
+    assert_eq!(graph.nodes()["@system.router"], NodeKind::System);
+
+    let reaching = graph.reaching("@ohos.hilog");
+    assert!(reaching.contains(on_create.as_str()));
+    assert!(reaching.contains(format!("{}func_main_0", prefix).as_str()));
+    assert!(!reaching.contains(format!("{}ifTest", prefix).as_str()));
+
+    assert!(graph.to_json().contains(&format!(
+        "{{\"caller\": \"{}\", \"callee\": \"@ohos.hilog.info\", \"kind\": \"call\"",
+        on_create
+    )));
+    assert!(graph
+        .to_dot()
+        .contains(&format!("    \"{}\" -> \"@ohos.hilog.info\";\n", on_create)));
+
+    // callruntime.* 不是调用，不能把累加器中的值当作被调用的函数
+    let mut writer = AbcWriter::default();
+    let class = writer.add_class("Lcom/example/Runtime;", 0x1);
+    let method = writer.add_method(class, "func_main_0", 0x8);
+    let mut code = CodeBuilder::new(0, 3);
+    code.emit("tryldglobalbyname", &[Arg::String("obj".to_string())])
+        .emit("ldobjbyname", &[Arg::String("x".to_string())])
+        .emit("callruntime.topropertykey", &[])
+        .emit("tryldglobalbyname", &[Arg::String("obj".to_string())])
+        .emit("ldobjbyname", &[Arg::String("z".to_string())])
+        .emit("callarg0", &[])
+        .emit("returnundefined", &[]);
+    writer.set_code(method, &code).unwrap();
+    let abc = AbcReader::from_vec(writer.write().unwrap()).unwrap();
+    let graph = abc.call_graph().unwrap();
+    let calls: Vec<_> = graph
+        .callees("Lcom/example/Runtime;->func_main_0")
+        .filter(|edge| *edge.kind() == CallKind::Call)
+        .map(|edge| edge.callee().as_str())
+        .collect();
+    assert_eq!(calls, ["obj.z"]);
+}
+
+#[test]
+fn test_string_encoding() {
+    use scroll::Pread;
+
+    for (value, encoding) in [
+        ("testTag", StringEncoding::Ascii),
+        ("", StringEncoding::Ascii),
+        ("你好，世界", StringEncoding::Mutf8),
+        ("a\0b", StringEncoding::Mutf8),
+        ("😀", StringEncoding::Mutf8),
+    ] {
+        let data = ABCString::encode(value);
+        let string = data.pread::<ABCString>(0).unwrap();
+        assert_eq!(string.str(), value);
+        assert_eq!(string.encoding(), encoding);
+        assert_eq!(string.length(), data.len());
+        assert_eq!(string.utf16_length(), value.encode_utf16().count());
+        assert!(!string.is_lossy());
+    }
+
+    // 补充平面的字符也可能按 4 字节编码
+    let data = [2 << 1, 0xf0, 0x9f, 0x98, 0x80, 0];
+    assert_eq!(data.pread::<ABCString>(0).unwrap().str(), "😀");
+
+    // UTF-16 编码单元，小端序
+    let data = [2 << 1, 0x60, 0x4f, 0x7d, 0x59, 0];
+    let string = data.pread::<ABCString>(0).unwrap();
+    assert_eq!(string.str(), "你好");
+    assert_eq!(string.encoding(), StringEncoding::Utf16);
+    assert_eq!(string.raw(), &[0x60, 0x4f, 0x7d, 0x59]);
+
+    // 无法解码的字符串也要保留
+    let data = [2 << 1 | 1, 0xff, 0x61, 0];
+    let string = data.pread::<ABCString>(0).unwrap();
+    assert!(string.is_lossy());
+    assert_eq!(string.str(), "\u{fffd}a");
+    assert_eq!(string.length(), 4);
+
+    // 没有 `\0` 结尾
+    assert!([3 << 1 | 1, 0x61, 0x62].pread::<ABCString>(0).is_err());
+}
+
+#[test]
+fn test_class_data() {
+    let mut writer = AbcWriter::default();
+    let listener = writer.add_foreign_class("Lohos/Listener;");
+    let page = writer.add_class("Lcom/example/Page;", 0x1);
+    writer.add_interface(page, listener);
+    writer.set_source_file(page, "entry/src/main/ets/pages/Index.ets");
+    writer.add_field(page, "title", listener, 0x1, None);
+    writer.add_method(page, "build", 0x1);
+    let helper = writer.add_class("Lcom/example/Helper;", 0x1);
+    writer.set_source_file(helper, "entry/src/main/ets/pages/Index.ets");
+    writer.add_method(helper, "run", 0x1);
+    writer.add_class("Lcom/example/Other;", 0x1);
+
+    let abc = AbcReader::from_vec(writer.write().unwrap()).unwrap();
+    let mut offsets: Vec<u32> = abc.classes().keys().copied().collect();
+    offsets.sort();
+    for pair in offsets.windows(2) {
+        // 类按 4 字节对齐，解析的长度必须正好到下一个类
+        let size = *abc.classes()[&pair[0]].offset() as u32;
+        assert_eq!((pair[0] + size).next_multiple_of(4), pair[1]);
+    }
+
+    let page_off = offsets[0];
+    let page = &abc.classes()[&page_off];
+    assert_eq!(page.name().str(), "Lcom/example/Page;");
+    assert_eq!(
+        abc.get_interfaces(page_off).unwrap(),
+        vec!["Lohos/Listener;"]
+    );
+    assert_eq!(page.fields().len(), 1);
+    assert_eq!(page.method_map().len(), 1);
+    assert!(abc.get_source_file(offsets[2]).unwrap().is_none());
+
+    let groups = abc.classes_by_source_file().unwrap();
+    assert_eq!(
+        groups["entry/src/main/ets/pages/Index.ets"],
+        vec!["Lcom/example/Helper;", "Lcom/example/Page;"]
+    );
+    assert_eq!(groups.len(), 1);
+
+    // 示例文件的类只有 SOURCE_LANG 标签
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+    assert!(abc
+        .classes()
+        .values()
+        .all(|class| class.source_lang().is_some() && class.interfaces().is_empty()));
+}
+
+#[test]
+fn test_field_values() {
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+    let (offset, class) = abc
+        .classes()
+        .iter()
+        .find(|(_, class)| {
+            class.name().str() == "Lcom.example.myapplication/entry/ets/entryability/EntryAbility;"
+        })
+        .unwrap();
+    let value = |name: &str| {
+        let field = class
+            .fields()
+            .iter()
+            .find(|field| abc.get_string(*field.name_off()).unwrap().str() == name)
+            .unwrap();
+        abc.get_field_value(*offset, field).unwrap()
+    };
+    // 模块记录字段指向 ModuleRecord
+    assert_eq!(
+        value("moduleRecordIdx"),
+        Some(FieldValue::LiteralArray(0x104c))
+    );
+    assert_eq!(value("isCommonjs"), Some(FieldValue::Int(0)));
+
+    let fields = abc.get_fields().unwrap();
+    assert!(fields.contains(
+        &"Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->moduleRecordIdx: u32 = literal_array 0x104c"
+            .to_string()
+    ));
+
+    let mut writer = AbcWriter::default();
+    let string = writer.add_foreign_class("Lstd/core/String;");
+    let class = writer.add_class("Lcom/example/Config;", 0x1);
+    writer.add_field(class, "retries", string, 0x9, Some(FieldInit::Int(-3)));
+    writer.add_field(class, "name", string, 0x1, None);
+    let abc = AbcReader::from_vec(writer.write().unwrap()).unwrap();
+    let mut fields = abc.get_fields().unwrap();
+    fields.sort();
+    assert_eq!(
+        fields,
+        vec![
+            "Lcom/example/Config;->name: Lstd/core/String; (PUBLIC)",
+            "Lcom/example/Config;->retries: Lstd/core/String; (PUBLIC STATIC) = -3",
+        ]
+    );
+
+    // 整数的 VALUE 即使恰好是字符串的偏移量，也按整数解析
+    let build = |value: u32| {
+        let mut writer = AbcWriter::default();
+        let string = writer.add_foreign_class("Lstd/core/String;");
+        let int = writer.primitive_type(PrimitiveType::I32);
+        let class = writer.add_class("Lcom/example/Config;", 0x1);
+        writer.add_field(class, "title", string, 0x1, Some(FieldInit::Value(value)));
+        writer.add_field(class, "count", int, 0x1, Some(FieldInit::Value(value)));
+        AbcReader::from_vec(writer.write().unwrap()).unwrap()
+    };
+    let abc = build(0);
+    let class = abc.classes().values().next().unwrap();
+    let string_off = *class.fields()[0].name_off();
+    let mut fields = build(string_off).get_fields().unwrap();
+    fields.sort();
+    assert_eq!(
+        fields,
+        vec![
+            format!("Lcom/example/Config;->count: i32 (PUBLIC) = {}", string_off),
+            "Lcom/example/Config;->title: Lstd/core/String; (PUBLIC) = \"title\"".to_string(),
+        ]
+    );
+
+    // 只有 u32 和引用类型的 VALUE 才可能是字面量数组的偏移量
+    let mut writer = AbcWriter::default();
+    let array = writer.add_literal_array(vec![LiteralValue::Integer(1)]);
+    let types = [
+        PrimitiveType::I32,
+        PrimitiveType::U1,
+        PrimitiveType::F32,
+        PrimitiveType::U32,
+    ]
+    .map(|t| writer.primitive_type(t));
+    let class = writer.add_class("Lcom/example/Config;", 0x1);
+    for (name, type_idx) in ["a", "b", "c", "d"].into_iter().zip(types) {
+        writer.add_field(
+            class,
+            name,
+            type_idx,
+            0x1,
+            Some(FieldInit::LiteralArray(array)),
+        );
+    }
+    let abc = AbcReader::from_vec(writer.write().unwrap()).unwrap();
+    let array_off = *abc.literal_arrays().keys().next().unwrap() as u32;
+    let mut fields = abc.get_fields().unwrap();
+    fields.sort();
+    assert_eq!(
+        fields,
+        vec![
+            format!("Lcom/example/Config;->a: i32 (PUBLIC) = {}", array_off),
+            "Lcom/example/Config;->b: u1 (PUBLIC) = 1".to_string(),
+            format!(
+                "Lcom/example/Config;->c: f32 (PUBLIC) = {}",
+                f32::from_bits(array_off)
+            ),
+            format!(
+                "Lcom/example/Config;->d: u32 (PUBLIC) = literal_array 0x{:x}",
+                array_off
+            ),
+        ]
+    );
+
+    // u64 的值超出 i64 的范围时不能显示为负数
+    let build = |value: u32| {
+        let mut writer = AbcWriter::default();
+        let u64_type = writer.primitive_type(PrimitiveType::U64);
+        let class = writer.add_class("Lcom/example/Config;", 0x1);
+        writer.add_field(class, "max", u64_type, 0x1, Some(FieldInit::Value(value)));
+        writer.write().unwrap()
+    };
+    let end = build(0).len() as u32;
+    let mut data = build(end);
+    data.extend(u64::MAX.to_le_bytes());
+    let abc = AbcReader::from_vec(data).unwrap();
+    let (offset, class) = abc.classes().iter().next().unwrap();
+    assert_eq!(
+        abc.get_field_value(*offset, &class.fields()[0]).unwrap(),
+        Some(FieldValue::UInt(u64::MAX))
+    );
+}
+
+#[test]
+fn test_annotations() {
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+    let class = abc
+        .classes()
+        .values()
+        .find(|class| {
+            class.name().str() == "Lcom.example.myapplication/entry/ets/entryability/EntryAbility;"
+        })
+        .unwrap();
+    let method = class
+        .method_map()
+        .values()
+        .find(|method| abc.get_string(*method.name_off()).unwrap().str() == "onCreate")
+        .unwrap();
+
+    let annotations = method.annotations();
+    assert_eq!(annotations.len(), 1);
+    let annotation = &annotations[0];
+    assert_eq!(*annotation.kind(), AnnotationKind::Plain);
+    assert_eq!(annotation.type_name(), "L_ESSlotNumberAnnotation;");
+    assert_eq!(annotation.elements()[0].name(), "SlotNumber");
+    assert_eq!(*annotation.elements()[0].tag(), '7');
+    assert_eq!(annotation.get("SlotNumber"), Some(&AnnotationValue::U32(4)));
+    assert_eq!(
+        annotation.to_string(),
+        "L_ESSlotNumberAnnotation;(SlotNumber=4)"
+    );
+    assert!(method.param_annotations().is_empty());
+    assert!(class.annotations().is_empty());
+    assert!(class
+        .fields()
+        .iter()
+        .all(|field| field.annotations().is_empty()));
+
+    // 注解的类型索引越界，只跳过这个注解，文件仍能加载
+    let offset = *annotation.offset() as usize;
+    let mut data = std::fs::read("fixtures/demo.abc").unwrap();
+    data[offset..offset + 2].copy_from_slice(&u16::MAX.to_le_bytes());
+    let abc = AbcReader::from_vec(data).unwrap();
+    let methods: Vec<_> = abc
+        .classes()
+        .values()
+        .flat_map(|class| class.method_map().values())
+        .collect();
+    let on_create = methods
+        .iter()
+        .find(|method| abc.get_string(*method.name_off()).unwrap().str() == "onCreate")
+        .unwrap();
+    assert!(on_create.annotations().is_empty());
+    assert!(methods
+        .iter()
+        .any(|method| !method.annotations().is_empty()));
+}
+
+#[test]
+fn test_type_refs() {
+    let abc = AbcReader::from_file("fixtures/demo.abc").unwrap();
+    let fields = abc.get_fields().unwrap();
+    assert!(fields.contains(
+        &"Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->isCommonjs: u8 = 0"
+            .to_string()
+    ));
+
+    let (region, _) = abc
+        .get_code("Lcom.example.myapplication/entry/ets/entryability/EntryAbility;->func_main_0")
+        .unwrap()
+        .unwrap();
+    let (offset, class) = abc
+        .classes()
+        .iter()
+        .find(|(_, class)| {
+            class.name().str() == "Lcom.example.myapplication/entry/ets/entryability/EntryAbility;"
+        })
+        .unwrap();
+    let field = &class.fields()[0];
+    let type_ref = region.get_type(*field.type_idx() as usize).unwrap();
+    assert!(matches!(type_ref, TypeRef::Primitive(_)));
+    let method = class.method_map().values().next().unwrap();
+    assert_eq!(
+        region.get_type(*method.class_idx() as usize),
+        Some(TypeRef::Class(*offset))
+    );
+    assert_eq!(
+        abc.get_type_name(TypeRef::Class(*offset)).unwrap(),
+        class.name().str()
+    );
+
+    // 0xb 是 any，是最后一个基本类型
+    let mut writer = AbcWriter::default();
+    let any = writer.primitive_type(PrimitiveType::Any);
+    let flag = writer.primitive_type(PrimitiveType::U1);
+    let hilog = writer.add_foreign_class("L@ohos.hilog;");
+    let class = writer.add_class("Lcom/example/Types;", 0x1);
+    writer.add_field(class, "value", any, 0x1, None);
+    writer.add_field(class, "enabled", flag, 0x1, Some(FieldInit::Int(1)));
+    writer.add_field(class, "logger", hilog, 0x1, None);
+    let abc = AbcReader::from_vec(writer.write().unwrap()).unwrap();
+    let mut fields = abc.get_fields().unwrap();
+    fields.sort();
+    assert_eq!(
+        fields,
+        vec![
+            "Lcom/example/Types;->enabled: u1 (PUBLIC) = 1",
+            "Lcom/example/Types;->logger: L@ohos.hilog; (PUBLIC)",
+            "Lcom/example/Types;->value: any (PUBLIC)",
+        ]
+    );
+}
@@ -1,4 +1,5 @@
 use xabc_lib::abc::AbcReader;
+use xabc_lib::bytecode::{self, Coverage};
 
 use clap::Parser;
 
@@ -13,6 +14,10 @@ struct Args {
     #[arg(short, long)]
     infos: bool,
 
+    /// 校验文件的魔数、大小、校验和以及索引是否越界
+    #[arg(short, long)]
+    verify: bool,
+
     /// 输出类列表
     #[arg(short = 'z', long)]
     classes: bool,
@@ -21,20 +26,76 @@ struct Args {
     #[arg(short, long)]
     methods: bool,
 
+    /// 输出方法签名列表
+    #[arg(short = 'M', long)]
+    signatures: bool,
+
+    /// 输出字段列表，包括类型、访问标志和初始值
+    #[arg(short = 'F', long)]
+    fields: bool,
+
     /// 输出字符串列表
     #[arg(short, long)]
     strings: bool,
 
-    /// 解析指定方法, 格式：类名->方法名，如: La/b/c;->mtd
+    /// 解析指定方法, 格式：类名->方法名[签名]，如: La/b/c;->mtd 或 La/b/c;->mtd(any)any
     #[arg(short = 'c', long)]
     method: Option<String>,
+
+    /// 输出指定方法的行号表和局部变量，需配合 -c 使用
+    #[arg(short, long)]
+    lines: bool,
+
+    /// 以 DOT 格式输出指定方法的控制流图，格式同 -c
+    #[arg(short, long, value_name = "METHOD")]
+    dot: Option<String>,
+
+    /// 反编译指定方法，输出伪代码，格式同 -c
+    #[arg(short = 'D', long, value_name = "METHOD")]
+    decompile: Option<String>,
+
+    /// 输出所有方法的解码覆盖率，即成功解码与无法解码的字节数
+    #[arg(short = 'C', long)]
+    coverage: bool,
+
+    /// 查询交叉引用：方法输出调用或引用它的位置，格式同 -c；其他输出使用该字符串的位置
+    #[arg(short = 'x', long, value_name = "TARGET")]
+    xref: Option<String>,
+
+    /// 交叉引用使用的索引：method、string 或 name（按名字访问属性或全局变量），默认由 -x 的格式决定
+    #[arg(long, value_name = "KIND", value_parser = ["method", "string", "name"], requires = "xref")]
+    xref_kind: Option<String>,
+
+    /// 输出整个文件的调用图，格式为 json 或 dot
+    #[arg(short = 'g', long, value_name = "FORMAT", value_parser = ["json", "dot"])]
+    callgraph: Option<String>,
+
+    /// 反汇编整个文件，以 ark_disasm 的 .pa 格式输出到指定文件，`-` 表示标准输出
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<String>,
 }
 
 fn main() {
     let args = Args::parse();
     let path = args.path;
 
-    let abc = AbcReader::from_file(path).unwrap();
+    let abc = match AbcReader::from_file(&path) {
+        Ok(abc) => abc,
+        Err(e) => {
+            eprintln!("解析文件失败: {} -> {}", path, e);
+            std::process::exit(1);
+        }
+    };
+    if args.verify {
+        match abc.verify() {
+            Ok(()) => println!("校验通过"),
+            Err(e) => {
+                eprintln!("校验失败: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     if args.infos {
         println!("{}", abc.header());
     }
@@ -48,13 +109,47 @@ fn main() {
     }
 
     if args.methods {
-        let mut methods = abc.get_method_names();
+        let mut methods = match abc.get_method_names() {
+            Ok(methods) => methods,
+            Err(e) => {
+                eprintln!("获取方法列表失败: {}", e);
+                std::process::exit(1);
+            }
+        };
         methods.sort();
         for method in methods {
             println!("{}", method);
         }
     }
 
+    if args.signatures {
+        let mut signatures = match abc.get_method_signatures() {
+            Ok(signatures) => signatures,
+            Err(e) => {
+                eprintln!("获取方法签名失败: {}", e);
+                std::process::exit(1);
+            }
+        };
+        signatures.sort();
+        for signature in signatures {
+            println!("{}", signature);
+        }
+    }
+
+    if args.fields {
+        let mut fields = match abc.get_fields() {
+            Ok(fields) => fields,
+            Err(e) => {
+                eprintln!("获取字段列表失败: {}", e);
+                std::process::exit(1);
+            }
+        };
+        fields.sort();
+        for field in fields {
+            println!("{}", field);
+        }
+    }
+
     if args.strings {
         let mut strings = abc.get_strings();
         strings.sort();
@@ -63,11 +158,175 @@ fn main() {
         }
     }
 
+    if args.coverage {
+        match abc.coverage() {
+            Ok(coverage) => println!("{}", coverage),
+            Err(e) => {
+                eprintln!("统计覆盖率失败: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(target) = args.xref {
+        let kind = args
+            .xref_kind
+            .as_deref()
+            .unwrap_or(if target.contains("->") {
+                "method"
+            } else {
+                "string"
+            });
+        let xrefs = match kind {
+            "method" => abc.callers_of(&target),
+            "name" => abc.name_refs(&target),
+            _ => abc.string_refs(&target),
+        };
+        match xrefs {
+            Ok(xrefs) => {
+                for xref in xrefs {
+                    println!("{}", xref);
+                }
+            }
+            Err(e) => {
+                eprintln!("查询交叉引用失败: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(format) = args.callgraph {
+        match abc.call_graph() {
+            Ok(graph) if format == "dot" => print!("{}", graph.to_dot()),
+            Ok(graph) => print!("{}", graph.to_json()),
+            Err(e) => {
+                eprintln!("构建调用图失败: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(output) = args.output {
+        let result = if output == "-" {
+            abc.write_pa(&mut std::io::stdout().lock())
+        } else {
+            abc.write_pa_file(&output)
+        };
+        if let Err(e) = result {
+            eprintln!("反汇编失败: {}", e);
+            std::process::exit(1);
+        }
+    }
+
     if let Some(method) = args.method {
         if !method.contains("->") {
             println!("方法格式错误，正确格式：\"类名->方法名\"");
             return;
         }
-        abc.parse_method(method);
+        let code = match abc.get_code(&method) {
+            Ok(code) => code,
+            Err(e) => {
+                eprintln!("解析方法失败: {}", e);
+                std::process::exit(1);
+            }
+        };
+        match abc.parse_method(&method) {
+            Ok(instructions) => {
+                let branch_labels = bytecode::branch_labels(&instructions);
+                for instruction in &instructions {
+                    if let Some((_, code)) = &code {
+                        for label in code.labels_at(*instruction.offset() as u64) {
+                            println!("{}:", label);
+                        }
+                    }
+                    if let Some(label) = branch_labels.get(instruction.offset()) {
+                        println!("{}:", label);
+                    }
+                    match instruction.diagnostic() {
+                        Some(diagnostic) => println!("{} # {}", instruction, diagnostic),
+                        None => println!("{}", instruction),
+                    }
+                }
+                let coverage = Coverage::from_instructions(&instructions);
+                if *coverage.unknown_bytes() > 0 {
+                    eprintln!("{}", coverage);
+                }
+            }
+            Err(e) => {
+                eprintln!("解析方法失败: {}", e);
+                std::process::exit(1);
+            }
+        }
+        if let Some((region, code)) = &code {
+            // 结束标签可能在最后一条指令之后
+            for label in code.labels_at(*code.code_size()) {
+                println!("{}:", label);
+            }
+            match abc.catch_directives(code, region) {
+                Ok(directives) => {
+                    for directive in directives {
+                        println!("{}", directive);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("解析异常处理表失败: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        if args.lines {
+            match abc.get_line_number_table(&method) {
+                Ok(Some(table)) => {
+                    if let Some(file) = table.source_file() {
+                        println!("source_file: {}", file);
+                    }
+                    for pos in table.positions() {
+                        println!("{:#06x} -> {}:{}", pos.pc, pos.line, pos.column);
+                    }
+                    for var in table.local_variables() {
+                        let end_pc = match var.end_pc() {
+                            Some(end_pc) => format!("{:#06x}", end_pc),
+                            None => "end".to_string(),
+                        };
+                        println!(
+                            "v{} {}: {} [{:#06x}, {})",
+                            var.reg(),
+                            var.name(),
+                            var.type_name(),
+                            var.start_pc(),
+                            end_pc
+                        );
+                    }
+                }
+                Ok(None) => println!("方法没有调试信息"),
+                Err(e) => {
+                    eprintln!("解析行号表失败: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+
+    if let Some(method) = args.dot {
+        match abc.get_cfg(&method) {
+            Ok(Some(cfg)) => print!("{}", cfg.to_dot(&method)),
+            Ok(None) => println!("方法没有 Code"),
+            Err(e) => {
+                eprintln!("构建控制流图失败: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(method) = args.decompile {
+        match abc.decompile_method(&method) {
+            Ok(Some(function)) => print!("{}", function),
+            Ok(None) => println!("方法没有 Code"),
+            Err(e) => {
+                eprintln!("反编译失败: {}", e);
+                std::process::exit(1);
+            }
+        }
     }
 }